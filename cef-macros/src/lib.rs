@@ -0,0 +1,296 @@
+#![doc = include_str!("../README.md")]
+
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::{format_ident, quote, ToTokens};
+use syn::{parse_macro_input, spanned::Spanned};
+
+/// Name of the field which [`macro@wrap`] adds to a handler struct to hold the
+/// `RcImpl` pointer.
+const RC_IMPL_FIELD: &str = "rc_impl";
+
+/// Generate the reference counting boilerplate for a Rust implementation of a CEF interface.
+///
+/// On a struct, the attribute takes the name of the wrapper type, e.g. `#[wrap(RenderHandler)]`.
+/// It adds a hidden field holding the `RcImpl` pointer and generates:
+/// - A `new` constructor taking the remaining fields in declaration order and returning the
+///   wrapper type.
+/// - The `Wrap*` trait implementation.
+/// - A `Clone` implementation which increments the reference count.
+/// - The `Rc` implementation.
+///
+/// On an `impl Impl*` block, the attribute takes no arguments and fills in `get_raw`.
+#[proc_macro_attribute]
+pub fn wrap(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let item = parse_macro_input!(item as syn::Item);
+    let result = match item {
+        syn::Item::Struct(item) => {
+            let attr = parse_macro_input!(attr as syn::Ident);
+            wrap_struct(attr, item)
+        }
+        syn::Item::Impl(item) => {
+            if attr.is_empty() {
+                wrap_impl(item)
+            } else {
+                Err(syn::Error::new(
+                    Span::call_site(),
+                    "#[wrap] on an impl block does not take any arguments",
+                ))
+            }
+        }
+        item => Err(syn::Error::new_spanned(
+            item,
+            "#[wrap] must be applied to a struct or an impl block",
+        )),
+    };
+
+    result.unwrap_or_else(syn::Error::into_compile_error).into()
+}
+
+fn wrap_struct(
+    wrapper: syn::Ident,
+    mut item: syn::ItemStruct,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let rc_impl = format_ident!("{RC_IMPL_FIELD}");
+    let raw_type = make_raw_type(&wrapper);
+    let wrap_trait = format_ident!("Wrap{wrapper}");
+
+    let fields = match &item.fields {
+        syn::Fields::Named(fields) => fields.named.iter().cloned().collect::<Vec<_>>(),
+        syn::Fields::Unit => vec![],
+        syn::Fields::Unnamed(fields) => {
+            return Err(syn::Error::new_spanned(
+                fields,
+                "#[wrap] does not support tuple structs",
+            ))
+        }
+    };
+    if let Some(field) = fields
+        .iter()
+        .find(|field| field.ident.as_ref().is_some_and(|ident| *ident == rc_impl))
+    {
+        return Err(syn::Error::new_spanned(
+            field,
+            format!("#[wrap] reserves the field name `{RC_IMPL_FIELD}`"),
+        ));
+    }
+
+    let field_names = fields
+        .iter()
+        .filter_map(|field| field.ident.as_ref())
+        .collect::<Vec<_>>();
+    let field_types = fields.iter().map(|field| &field.ty).collect::<Vec<_>>();
+
+    let mut named: syn::FieldsNamed = syn::parse_quote!({
+        #rc_impl: *mut ::cef::rc::RcImpl<::cef::sys::#raw_type, Self>,
+    });
+    named.named.extend(fields.iter().cloned());
+    item.fields = syn::Fields::Named(named);
+
+    let name = &item.ident;
+    let vis = &item.vis;
+    let (impl_generics, ty_generics, where_clause) = item.generics.split_for_impl();
+
+    Ok(quote! {
+        #item
+
+        impl #impl_generics #name #ty_generics #where_clause {
+            #vis fn new(#(#field_names: #field_types),*) -> ::cef::#wrapper {
+                ::cef::#wrapper::new(Self {
+                    #rc_impl: ::std::ptr::null_mut(),
+                    #(#field_names),*
+                })
+            }
+        }
+
+        impl #impl_generics ::cef::#wrap_trait for #name #ty_generics #where_clause {
+            fn wrap_rc(
+                &mut self,
+                object: *mut ::cef::rc::RcImpl<::cef::sys::#raw_type, Self>,
+            ) {
+                self.#rc_impl = object;
+            }
+        }
+
+        impl #impl_generics ::std::clone::Clone for #name #ty_generics #where_clause {
+            fn clone(&self) -> Self {
+                unsafe {
+                    let rc_impl = &mut *self.#rc_impl;
+                    ::cef::rc::Rc::add_ref(&rc_impl.interface);
+                }
+
+                Self {
+                    #rc_impl: self.#rc_impl,
+                    #(#field_names: ::std::clone::Clone::clone(&self.#field_names)),*
+                }
+            }
+        }
+
+        impl #impl_generics ::cef::rc::Rc for #name #ty_generics #where_clause {
+            fn as_base(&self) -> &::cef::sys::cef_base_ref_counted_t {
+                unsafe {
+                    let base = &*self.#rc_impl;
+                    ::std::mem::transmute(&base.cef_object)
+                }
+            }
+        }
+    })
+}
+
+fn wrap_impl(mut item: syn::ItemImpl) -> syn::Result<proc_macro2::TokenStream> {
+    let rc_impl = format_ident!("{RC_IMPL_FIELD}");
+    let Some((_, impl_trait, _)) = &item.trait_ else {
+        return Err(syn::Error::new(
+            item.span(),
+            "#[wrap] must be applied to an impl of one of the Impl* traits",
+        ));
+    };
+    let wrapper = impl_trait
+        .segments
+        .last()
+        .map(|segment| segment.ident.to_string())
+        .and_then(|name| name.strip_prefix("Impl").map(ToOwned::to_owned))
+        .filter(|name| !name.is_empty())
+        .ok_or_else(|| {
+            syn::Error::new_spanned(
+                impl_trait,
+                "#[wrap] must be applied to an impl of one of the Impl* traits",
+            )
+        })?;
+    let raw_type = make_raw_type(&format_ident!("{wrapper}"));
+
+    let has_get_raw = item.items.iter().any(|item| match item {
+        syn::ImplItem::Fn(method) => method.sig.ident == "get_raw",
+        _ => false,
+    });
+    if !has_get_raw {
+        item.items.push(syn::parse_quote! {
+            fn get_raw(&self) -> *mut ::cef::sys::#raw_type {
+                self.#rc_impl.cast()
+            }
+        });
+    }
+
+    Ok(item.into_token_stream())
+}
+
+/// Embed every file below a directory in the binary, for serving it with
+/// `cef::scheme::StaticDir::embedded`.
+///
+/// The path is relative to the directory containing the crate's `Cargo.toml`. The macro expands
+/// to a `&'static [(&'static str, &'static [u8])]` of the files, sorted by their `/` separated
+/// path relative to the embedded directory, e.g. `assets/app.js`.
+///
+/// ```rust,ignore
+/// static ASSETS: &[(&str, &[u8])] = cef::include_dir!("dist");
+/// ```
+///
+/// Cargo rebuilds the crate when one of the embedded files changes, because they are included
+/// with `include_bytes!`, but not when files are added to or removed from the directory. A build
+/// script which watches the directory takes care of that:
+///
+/// ```rust,ignore
+/// // build.rs
+/// fn main() {
+///     println!("cargo:rerun-if-changed=dist");
+/// }
+/// ```
+#[proc_macro]
+pub fn include_dir(input: TokenStream) -> TokenStream {
+    let path = parse_macro_input!(input as syn::LitStr);
+    include_dir_impl(&path)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn include_dir_impl(path: &syn::LitStr) -> syn::Result<proc_macro2::TokenStream> {
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR")
+        .map_err(|_| syn::Error::new_spanned(path, "CARGO_MANIFEST_DIR is not set"))?;
+    let root = std::path::Path::new(&manifest_dir).join(path.value());
+
+    let mut files = vec![];
+    collect_files(&root, &root, &mut files).map_err(|err| {
+        syn::Error::new_spanned(path, format!("cannot read {}: {err}", root.display()))
+    })?;
+    files.sort();
+
+    let files = files.into_iter().map(|(name, path)| {
+        let path = path.to_string_lossy();
+        quote! { (#name, include_bytes!(#path) as &[u8]) }
+    });
+    Ok(quote! {
+        &[#(#files),*] as &[(&str, &[u8])]
+    })
+}
+
+fn collect_files(
+    root: &std::path::Path,
+    dir: &std::path::Path,
+    files: &mut Vec<(String, std::path::PathBuf)>,
+) -> std::io::Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_files(root, &path, files)?;
+        } else if let Ok(name) = path.strip_prefix(root) {
+            let name = name
+                .components()
+                .map(|component| component.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+            files.push((name, path));
+        }
+    }
+    Ok(())
+}
+
+/// Reverse the `update-bindings` naming convention to recover the name of the raw CEF struct,
+/// e.g. `RenderHandler` becomes `_cef_render_handler_t`.
+fn make_raw_type(wrapper: &syn::Ident) -> syn::Ident {
+    let name = wrapper.to_string();
+    let name = match name.strip_prefix("Cef") {
+        Some(name) if name.starts_with("String") => name,
+        _ => name.as_str(),
+    };
+
+    let mut snake_case = String::with_capacity(name.len() + 8);
+    for (i, c) in name.chars().enumerate() {
+        if c.is_ascii_uppercase() {
+            if i > 0 {
+                snake_case.push('_');
+            }
+            snake_case.push(c.to_ascii_lowercase());
+        } else {
+            snake_case.push(c);
+        }
+    }
+
+    syn::Ident::new(&format!("_cef_{snake_case}_t"), wrapper.span())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn collect_files_joins_with_slashes() {
+        let root = std::env::temp_dir().join(format!("cef-macros-{}", std::process::id()));
+        let nested = root.join("assets").join("img");
+        std::fs::create_dir_all(&nested).unwrap();
+        std::fs::write(root.join("index.html"), "").unwrap();
+        std::fs::write(nested.join("logo.png"), "").unwrap();
+
+        let mut files = vec![];
+        let result = collect_files(&root, &root, &mut files);
+        std::fs::remove_dir_all(&root).unwrap();
+        result.unwrap();
+        files.sort();
+
+        let names = files
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["assets/img/logo.png", "index.html"]);
+        assert_eq!(files[0].1, nested.join("logo.png"));
+    }
+}
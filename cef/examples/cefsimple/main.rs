@@ -1,493 +1,281 @@
-use cef::{args::Args, rc::*, sandbox_info::SandboxInfo, *};
-use lazy_static::lazy_static;
-use named_pipe::{PipeOptions, PipeServer};
-use std::io::Write;
+use cef::osr::input::InputDispatcher;
+use cef::osr::stream::{self, Codec, Endpoint, Frame, FrameProcessor, FrameStream, PixelFormat};
+use cef::osr::view::OsrView;
+use cef::*;
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
-use std::sync::atomic::{AtomicU32, Ordering};
 
-lazy_static! {
-    static ref PIPE_CONN: Mutex<Option<PipeServer>> = Mutex::new(None);
+/// Where the frames are streamed to, unless overridden with `CEFSIMPLE_STREAM`, e.g.
+/// `CEFSIMPLE_STREAM=shm:/petplay-webxr` for shared memory. Set `CEFSIMPLE_STREAM_CODEC` to
+/// compress the frames, e.g. `CEFSIMPLE_STREAM_CODEC=lz4` with the `lz4` feature enabled.
+#[cfg(target_os = "windows")]
+const DEFAULT_STREAM_ENDPOINT: &str = "pipe:petplay-webxr";
+#[cfg(unix)]
+const DEFAULT_STREAM_ENDPOINT: &str = "unix:/tmp/petplay-webxr.sock";
+
+/// Initial size of the browser view. The device scale factor is 1 unless overridden with
+/// `CEFSIMPLE_SCALE`, e.g. `CEFSIMPLE_SCALE=2` to paint frames at twice the size.
+const VIEW_WIDTH: i32 = 1200 * 2;
+const VIEW_HEIGHT: i32 = 600 * 2;
+
+fn demo_view() -> OsrView {
+    let scale = match std::env::var("CEFSIMPLE_SCALE").map(|scale| scale.parse::<f32>()) {
+        Ok(Ok(scale)) => scale,
+        Ok(Err(err)) => panic!("Invalid CEFSIMPLE_SCALE: {err}"),
+        Err(_) => 1.0,
+    };
+    OsrView::new(VIEW_WIDTH, VIEW_HEIGHT, scale)
 }
 
-
-
-lazy_static! {
-    static ref LAST_FPS_LOG: Mutex<Option<Instant>> = Mutex::new(None);
+fn listen_frame_stream() -> std::io::Result<FrameStream> {
+    let endpoint: Endpoint = std::env::var("CEFSIMPLE_STREAM")
+        .as_deref()
+        .unwrap_or(DEFAULT_STREAM_ENDPOINT)
+        .parse()?;
+    let codec: Codec = match std::env::var("CEFSIMPLE_STREAM_CODEC") {
+        Ok(codec) => codec.parse()?,
+        Err(_) => Codec::None,
+    };
+    println!("[Rust] Streaming frames to {endpoint}, compressed with {codec}");
+    let stream = FrameStream::new(endpoint.listen()?)?;
+    stream.set_codec(codec);
+    Ok(stream)
 }
-static FRAME_COUNT: AtomicU32 = AtomicU32::new(0);
-
-fn log_fps() {
-    let now = Instant::now();
-    let mut last_log = LAST_FPS_LOG.lock().unwrap();
-    if let Some(last) = *last_log {
-        FRAME_COUNT.fetch_add(1, Ordering::Relaxed);
-        let elapsed = now.duration_since(last);
-        if elapsed >= Duration::from_secs(1) {
-            println!("[Rust] FPS: {}", FRAME_COUNT.load(Ordering::Relaxed));
-            FRAME_COUNT.store(0, Ordering::Relaxed);
-            *last_log = Some(now);
-        }
-    } else {
-        *last_log = Some(now);
-        FRAME_COUNT.store(1, Ordering::Relaxed);
-    }
+
+#[derive(Default)]
+struct FpsCounter {
+    last_log: Option<Instant>,
+    frames: u32,
 }
 
-fn send_frame_over_pipe(width: i32, height: i32, buffer: &[u8]) {
-    let mut conn_guard = PIPE_CONN.lock().unwrap();
-    if conn_guard.is_none() {
-        let pipe_name = r"\\.\pipe\petplay-webxr";
-        match PipeOptions::new(pipe_name)
-                .in_buffer(1024 * 1024) // 1 MB
-                .out_buffer(200 * 1024 * 1024) // 40 MB - Adjust as needed based on max frame size
-                .single() // Create a single server instance
-        {
-            Ok(connecting_server) => {
-                println!("[Rust] Pipe server created at {}. Waiting for client connection...", pipe_name);
-                match connecting_server.wait() {
-                    Ok(connected_server) => {
-                        println!("[Rust] Client connected to pipe server.");
-                        *conn_guard = Some(connected_server);
-                    }
-                    Err(e) => {
-                         eprintln!("[Rust] Failed to wait for client connection: {}", e);
-                         return;
-                    }
-                }
-            }
-            Err(e) => {
-                eprintln!("[Rust] Failed to create named pipe server options: {}", e);
-                return;
-            }
-        }
-    }
-    if let Some(ref mut pipe) = *conn_guard {
-        let total_size = buffer.len() as u32;
-        let width = width as u32;
-        let height = height as u32;
-        let num_chunks = 1u32;
-        let chunk_size = total_size;
-        let mut header = Vec::with_capacity(16);
-        header.extend(&width.to_le_bytes());
-        header.extend(&height.to_le_bytes());
-        header.extend(&total_size.to_le_bytes());
-        header.extend(&num_chunks.to_le_bytes());
-        if let Err(e) = pipe.write_all(&header) {
-            eprintln!(
-                "[Rust] Failed to send header: {}. Client likely disconnected.",
-                e
-            );
-            *conn_guard = None;
-            return;
-        }
-        if let Err(e) = pipe.write_all(&chunk_size.to_le_bytes()) {
-            eprintln!(
-                "[Rust] Failed to send chunk size: {}. Client likely disconnected.",
-                e
-            );
-            *conn_guard = None;
-            return;
-        }
-        if let Err(e) = pipe.write_all(buffer) {
-            eprintln!(
-                "[Rust] Failed to send frame data: {}. Client likely disconnected.",
-                e
-            );
-            *conn_guard = None;
-            return;
-        }
-        if let Err(e) = pipe.flush() {
-            eprintln!(
-                "[Rust] Failed to flush pipe: {}. Client likely disconnected.",
-                e
-            );
-            *conn_guard = None;
+impl FpsCounter {
+    fn tick(&mut self) {
+        let now = Instant::now();
+        let Some(last_log) = self.last_log else {
+            self.last_log = Some(now);
+            self.frames = 1;
             return;
+        };
+
+        self.frames += 1;
+        if now.duration_since(last_log) >= Duration::from_secs(1) {
+            println!("[Rust] FPS: {}", self.frames);
+            self.frames = 0;
+            self.last_log = Some(now);
         }
     }
 }
 
+#[wrap(App)]
 struct DemoApp {
-    object: *mut RcImpl<cef_dll_sys::_cef_app_t, Self>,
-
     window: Arc<Mutex<Option<Window>>>,
 }
 
-impl DemoApp {
-    fn new(window: Arc<Mutex<Option<Window>>>) -> App {
-        App::new(Self {
-            object: std::ptr::null_mut(),
-            window,
-        })
-    }
-}
-
-impl WrapApp for DemoApp {
-    fn wrap_rc(&mut self, object: *mut RcImpl<cef_dll_sys::_cef_app_t, Self>) {
-        self.object = object;
-    }
-}
-
-impl Clone for DemoApp {
-    fn clone(&self) -> Self {
-        let object = unsafe {
-            let rc_impl = &mut *self.object;
-            rc_impl.interface.add_ref();
-            self.object
-        };
-        let window = self.window.clone();
-
-        Self { object, window }
-    }
-}
-
-impl Rc for DemoApp {
-    fn as_base(&self) -> &cef_dll_sys::cef_base_ref_counted_t {
-        unsafe {
-            let base = &*self.object;
-            std::mem::transmute(&base.cef_object)
-        }
-    }
-}
-
+#[wrap]
 impl ImplApp for DemoApp {
-    fn get_raw(&self) -> *mut cef_dll_sys::_cef_app_t {
-        self.object.cast()
-    }
-
     fn get_browser_process_handler(&self) -> Option<BrowserProcessHandler> {
         Some(DemoBrowserProcessHandler::new(self.window.clone()))
     }
 }
 
+#[wrap(BrowserProcessHandler)]
 struct DemoBrowserProcessHandler {
-    object: *mut RcImpl<cef_dll_sys::cef_browser_process_handler_t, Self>,
-
     window: Arc<Mutex<Option<Window>>>,
 }
 
-impl DemoBrowserProcessHandler {
-    fn new(window: Arc<Mutex<Option<Window>>>) -> BrowserProcessHandler {
-        BrowserProcessHandler::new(Self {
-            object: std::ptr::null_mut(),
-            window,
-        })
-    }
-}
-
-impl Rc for DemoBrowserProcessHandler {
-    fn as_base(&self) -> &cef_dll_sys::cef_base_ref_counted_t {
-        unsafe {
-            let base = &*self.object;
-            std::mem::transmute(&base.cef_object)
-        }
-    }
-}
-
-impl WrapBrowserProcessHandler for DemoBrowserProcessHandler {
-    fn wrap_rc(&mut self, object: *mut RcImpl<cef_dll_sys::_cef_browser_process_handler_t, Self>) {
-        self.object = object;
-    }
-}
-
-impl Clone for DemoBrowserProcessHandler {
-    fn clone(&self) -> Self {
-        let object = unsafe {
-            let rc_impl = &mut *self.object;
-            rc_impl.interface.add_ref();
-            rc_impl
-        };
-
-        let window = self.window.clone();
-
-        Self { object, window }
-    }
-}
-
+#[wrap]
 impl ImplBrowserProcessHandler for DemoBrowserProcessHandler {
-    fn get_raw(&self) -> *mut cef_dll_sys::_cef_browser_process_handler_t {
-        self.object.cast()
-    }
-
     fn on_context_initialized(&self) {
         println!("cef context intiialized");
 
         let mut window_info = WindowInfo::default();
         window_info.windowless_rendering_enabled = 1;
 
-        let mut client = DemoClient::new();
-
-        let mut browser_settings = BrowserSettings::default();
-        browser_settings.windowless_frame_rate = 90000;
-
-        let request_context: Option<&mut RequestContext> = None;
-        let extra_info: Option<&mut DictionaryValue> = None;
-
-        let browser = browser_host_create_browser_sync(
-            Some(&window_info),
-            Some(&mut client),
-            Some(&CefString::from("http://127.0.0.1:5173/index.html")),
-            Some(&browser_settings),
-            extra_info,
-            request_context,
-        );
-        if browser.is_none() {
-            panic!("Failed to create windowless browser");
+        let stream = match listen_frame_stream() {
+            Ok(stream) => Arc::new(stream),
+            Err(err) => panic!("Failed to listen for frame stream consumers: {err}"),
+        };
+        let view = Arc::new(demo_view());
+        let mut client = DemoClient::new(stream.clone(), view.clone());
+
+        let browser_settings = BrowserSettings::builder()
+            .windowless_frame_rate(240)
+            .build()
+            .expect("invalid browser settings");
+
+        let browser = match browser::create_browser_sync(
+            &window_info,
+            &mut client,
+            "http://127.0.0.1:5173/index.html",
+            &browser_settings,
+            None::<&mut RequestContext>,
+        ) {
+            Ok(browser) => browser,
+            Err(err) => panic!("Failed to create windowless browser: {err}"),
+        };
+        view.set_browser(&browser);
+
+        match InputDispatcher::new(&browser) {
+            Ok(dispatcher) => {
+                // The view can be resized at any time, so take its sizes for each event.
+                stream.set_input_handler(move |event| {
+                    let (view_width, view_height) = view.size();
+                    let (frame_width, frame_height) = view.frame_size();
+                    dispatcher.set_view_size(view_width, view_height);
+                    dispatcher.set_frame_size(frame_width, frame_height);
+                    // The consumer sees flipped frames, see demo_frame_processor.
+                    dispatcher.dispatch(event.map_position(|x, y| (x, frame_height as f32 - y)))
+                });
+            }
+            Err(err) => eprintln!("[Rust] Input from the frame stream is disabled: {err}"),
         }
     }
 }
 
-use cef::{DisplayHandler, ImplDisplayHandler, LogSeverity, WrapDisplayHandler};
-use cef::{
-    ImplBrowser, ImplRenderHandler, PaintElementType, Rect, RenderHandler, WrapRenderHandler,
-};
+use cef::DisplayHandler;
+use cef::{ImplBrowser, ImplRenderHandler, PaintElementType, Rect, RenderHandler};
 
-#[derive(Clone)]
+#[wrap(RenderHandler)]
 struct DemoRenderHandler {
-    object: *mut RcImpl<cef_dll_sys::_cef_render_handler_t, Self>,
-}
-
-impl DemoRenderHandler {
-    fn new() -> RenderHandler {
-        RenderHandler::new(Self {
-            object: std::ptr::null_mut(),
-        })
-    }
-}
-
-impl Rc for DemoRenderHandler {
-    fn as_base(&self) -> &cef_dll_sys::cef_base_ref_counted_t {
-        unsafe {
-            let base = &*self.object;
-            std::mem::transmute(&base.cef_object)
+    stream: Arc<FrameStream>,
+    view: Arc<OsrView>,
+    fps: Arc<Mutex<FpsCounter>>,
+    processor: Arc<Mutex<FrameProcessor>>,
+}
+
+/// Flip the frames for the consumer, which draws them bottom up. Set `CEFSIMPLE_CHROMA_KEY` to
+/// a hex color to also make that color transparent, e.g. `CEFSIMPLE_CHROMA_KEY=000000` for
+/// pages with a black background.
+fn demo_frame_processor() -> FrameProcessor {
+    let processor = FrameProcessor::new().flip_vertical();
+    let Ok(color) = std::env::var("CEFSIMPLE_CHROMA_KEY") else {
+        return processor;
+    };
+    match u32::from_str_radix(&color, 16) {
+        Ok(rgb) if color.len() == 6 => {
+            let [_, r, g, b] = rgb.to_be_bytes();
+            processor.chroma_key([r, g, b], 0)
         }
+        _ => panic!("Invalid CEFSIMPLE_CHROMA_KEY: expected RRGGBB, got {color:?}"),
     }
 }
 
-impl WrapRenderHandler for DemoRenderHandler {
-    fn wrap_rc(&mut self, object: *mut RcImpl<cef_dll_sys::_cef_render_handler_t, Self>) {
-        self.object = object;
-    }
-}
-
-fn process_and_flip_buffer(width: i32, height: i32, buffer: &[u8]) -> Vec<u8> {
-    let width = width as usize;
-    let height = height as usize;
-    let stride = width * 4;
-    let mut flipped = vec![0u8; buffer.len()];
-
-    for y in 0..height {
-        let src_row = &buffer[y * stride..(y + 1) * stride];
-        let dst_row = &mut flipped[(height - 1 - y) * stride..(height - y) * stride];
-        for x in 0..width {
-            let src_idx = x * 4;
-            let dst_idx = src_idx;
-            let b = src_row[src_idx];
-            let g = src_row[src_idx + 1];
-            let r = src_row[src_idx + 2];
-            let a = src_row[src_idx + 3];
-            let new_a = if r == 0 && g == 0 && b == 0 { 0 } else { a };
-            dst_row[dst_idx] = b;
-            dst_row[dst_idx + 1] = g;
-            dst_row[dst_idx + 2] = r;
-            dst_row[dst_idx + 3] = new_a;
-        }
+#[wrap]
+impl ImplRenderHandler for DemoRenderHandler {
+    fn get_view_rect(&self, _browser: Option<&mut impl ImplBrowser>, rect: Option<&mut Rect>) {
+        self.view.get_view_rect(rect);
     }
-    flipped
-}
 
-impl ImplRenderHandler for DemoRenderHandler {
-    fn get_raw(&self) -> *mut cef_dll_sys::_cef_render_handler_t {
-        self.object.cast()
+    fn get_screen_info(
+        &self,
+        _browser: Option<&mut impl ImplBrowser>,
+        screen_info: Option<&mut ScreenInfo>,
+    ) -> bool {
+        self.view.get_screen_info(screen_info)
     }
 
-    fn get_view_rect(&self, _browser: Option<&mut impl ImplBrowser>, rect: Option<&mut Rect>) {
-        if let Some(rect) = rect {
-            rect.x = 0;
-            rect.y = 0;
-            rect.width = 1200 * 2;
-            rect.height = 600 * 2;
-        }
+    fn get_screen_point(
+        &self,
+        _browser: Option<&mut impl ImplBrowser>,
+        view_x: ::std::os::raw::c_int,
+        view_y: ::std::os::raw::c_int,
+        screen_x: Option<&mut ::std::os::raw::c_int>,
+        screen_y: Option<&mut ::std::os::raw::c_int>,
+    ) -> bool {
+        self.view
+            .get_screen_point(view_x, view_y, screen_x, screen_y)
     }
 
     fn on_paint(
         &self,
         _browser: Option<&mut impl ImplBrowser>,
         _type_: PaintElementType,
-        _dirty_rects_count: usize,
-        _dirty_rects: Option<&Rect>,
-        buffer: *const u8,
+        dirty_rects: Option<&[Rect]>,
+        buffer: Option<&[u8]>,
         width: ::std::os::raw::c_int,
         height: ::std::os::raw::c_int,
     ) {
-        let buffer_len = (width * height * 4) as usize;
-        if buffer.is_null() || buffer_len == 0 {
+        let Some(pixel_data) = buffer else {
             eprintln!("[Rust] OnPaint: buffer is null or size is zero");
             return;
+        };
+        let (width, height) = (width as u32, height as u32);
+        let mut processor = self.processor.lock().unwrap();
+        let dirty: Vec<_> = dirty_rects
+            .unwrap_or_default()
+            .iter()
+            .map(|rect| processor.map_rect(stream::stream_rect(rect), width, height))
+            .collect();
+        let frame =
+            match processor.process(&Frame::new(width, height, PixelFormat::Bgra8, pixel_data)) {
+                Ok(frame) => frame,
+                Err(err) => {
+                    eprintln!("[Rust] Failed to process frame: {err}");
+                    return;
+                }
+            };
+        let result = if dirty.is_empty() {
+            self.stream.send(&frame)
+        } else {
+            self.stream.send_dirty(&frame, &dirty)
+        };
+        if let Err(err) = result {
+            eprintln!("[Rust] Failed to send frame: {err}");
         }
-        let pixel_data = unsafe { std::slice::from_raw_parts(buffer, buffer_len) };
-        let processed = process_and_flip_buffer(width, height, pixel_data);
-        send_frame_over_pipe(width, height, &processed);
-        log_fps();
+        self.fps.lock().unwrap().tick();
     }
 }
 
-#[derive(Clone)]
-struct DemoDisplayHandler {
-    object: *mut RcImpl<cef_dll_sys::_cef_display_handler_t, Self>,
-}
-
-impl DemoDisplayHandler {
-    fn new() -> DisplayHandler {
-        DisplayHandler::new(Self {
-            object: std::ptr::null_mut(),
+fn demo_display_handler() -> DisplayHandler {
+    DisplayHandler::builder()
+        .on_console_message(|_browser, _level, message, source, line| {
+            if let (Some(msg), Some(src)) = (message, source) {
+                println!("[Browser Console] [{}:{}] {}", src, line, msg);
+            } else if let Some(msg) = message {
+                println!("[Browser Console] {}", msg);
+            }
+            false
         })
-    }
-}
-
-impl Rc for DemoDisplayHandler {
-    fn as_base(&self) -> &cef_dll_sys::cef_base_ref_counted_t {
-        unsafe {
-            let base = &*self.object;
-            std::mem::transmute(&base.cef_object)
-        }
-    }
-}
-
-impl WrapDisplayHandler for DemoDisplayHandler {
-    fn wrap_rc(&mut self, object: *mut RcImpl<cef_dll_sys::_cef_display_handler_t, Self>) {
-        self.object = object;
-    }
-}
-
-impl ImplDisplayHandler for DemoDisplayHandler {
-    fn get_raw(&self) -> *mut cef_dll_sys::_cef_display_handler_t {
-        self.object.cast()
-    }
-
-    fn on_console_message(
-        &self,
-        _browser: Option<&mut impl ImplBrowser>,
-        _level: LogSeverity,
-        message: Option<&CefString>,
-        source: Option<&CefString>,
-        line: ::std::os::raw::c_int,
-    ) -> ::std::os::raw::c_int {
-        if let (Some(msg), Some(src)) = (message, source) {
-            println!("[Browser Console] [{}:{}] {}", src, line, msg);
-        } else if let Some(msg) = message {
-            println!("[Browser Console] {}", msg);
-        }
-        0
-    }
-}
-
-struct DemoClient(*mut RcImpl<cef_dll_sys::_cef_client_t, Self>);
-
-impl DemoClient {
-    fn new() -> Client {
-        Client::new(Self(std::ptr::null_mut()))
-    }
-}
-
-impl WrapClient for DemoClient {
-    fn wrap_rc(&mut self, object: *mut RcImpl<cef_dll_sys::_cef_client_t, Self>) {
-        self.0 = object;
-    }
+        .build()
 }
 
-impl Clone for DemoClient {
-    fn clone(&self) -> Self {
-        unsafe {
-            let rc_impl = &mut *self.0;
-            rc_impl.interface.add_ref();
-        }
-
-        Self(self.0)
-    }
-}
-
-impl Rc for DemoClient {
-    fn as_base(&self) -> &cef_dll_sys::cef_base_ref_counted_t {
-        unsafe {
-            let base = &*self.0;
-            std::mem::transmute(&base.cef_object)
-        }
-    }
+#[wrap(Client)]
+struct DemoClient {
+    stream: Arc<FrameStream>,
+    view: Arc<OsrView>,
 }
 
+#[wrap]
 impl ImplClient for DemoClient {
-    fn get_raw(&self) -> *mut cef_dll_sys::_cef_client_t {
-        self.0.cast()
-    }
-
     fn get_render_handler(&self) -> Option<RenderHandler> {
-        Some(DemoRenderHandler::new())
+        Some(DemoRenderHandler::new(
+            self.stream.clone(),
+            self.view.clone(),
+            Default::default(),
+            Arc::new(Mutex::new(demo_frame_processor())),
+        ))
     }
 
     fn get_display_handler(&self) -> Option<DisplayHandler> {
-        Some(DemoDisplayHandler::new())
+        Some(demo_display_handler())
     }
 }
 
+#[wrap(WindowDelegate)]
 struct _DemoWindowDelegate {
-    base: *mut RcImpl<cef_dll_sys::_cef_window_delegate_t, Self>,
-
     browser_view: BrowserView,
 }
 
-impl _DemoWindowDelegate {
-    fn _new(browser_view: BrowserView) -> WindowDelegate {
-        WindowDelegate::new(Self {
-            base: std::ptr::null_mut(),
-            browser_view,
-        })
-    }
-}
-
-impl WrapWindowDelegate for _DemoWindowDelegate {
-    fn wrap_rc(&mut self, object: *mut RcImpl<cef_dll_sys::_cef_window_delegate_t, Self>) {
-        self.base = object;
-    }
-}
-
-impl Clone for _DemoWindowDelegate {
-    fn clone(&self) -> Self {
-        unsafe {
-            let rc_impl = &mut *self.base;
-            rc_impl.interface.add_ref();
-        }
-
-        Self {
-            base: self.base,
-            browser_view: self.browser_view.clone(),
-        }
-    }
-}
-
-impl Rc for _DemoWindowDelegate {
-    fn as_base(&self) -> &cef_dll_sys::cef_base_ref_counted_t {
-        unsafe {
-            let base = &*self.base;
-            std::mem::transmute(&base.cef_object)
-        }
-    }
-}
-
+#[wrap]
 impl ImplViewDelegate for _DemoWindowDelegate {
     fn on_child_view_changed(
         &self,
         _view: Option<&mut impl ImplView>,
-        _added: ::std::os::raw::c_int,
+        _added: bool,
         _child: Option<&mut impl ImplView>,
     ) {
         // view.as_panel().map(|x| x.as_window().map(|w| w.close()));
     }
-
-    fn get_raw(&self) -> *mut cef_dll_sys::_cef_view_delegate_t {
-        self.base.cast()
-    }
 }
 
 impl ImplPanelDelegate for _DemoWindowDelegate {}
@@ -505,81 +293,42 @@ impl ImplWindowDelegate for _DemoWindowDelegate {
         quit_message_loop();
     }
 
-    fn with_standard_window_buttons(
-        &self,
-        _window: Option<&mut impl ImplWindow>,
-    ) -> ::std::os::raw::c_int {
-        1
+    fn with_standard_window_buttons(&self, _window: Option<&mut impl ImplWindow>) -> bool {
+        true
     }
 
-    fn can_resize(&self, _window: Option<&mut impl ImplWindow>) -> ::std::os::raw::c_int {
-        1
+    fn can_resize(&self, _window: Option<&mut impl ImplWindow>) -> bool {
+        true
     }
 
-    fn can_maximize(&self, _window: Option<&mut impl ImplWindow>) -> ::std::os::raw::c_int {
-        1
+    fn can_maximize(&self, _window: Option<&mut impl ImplWindow>) -> bool {
+        true
     }
 
-    fn can_minimize(&self, _window: Option<&mut impl ImplWindow>) -> ::std::os::raw::c_int {
-        1
+    fn can_minimize(&self, _window: Option<&mut impl ImplWindow>) -> bool {
+        true
     }
 
-    fn can_close(&self, _window: Option<&mut impl ImplWindow>) -> ::std::os::raw::c_int {
-        1
+    fn can_close(&self, _window: Option<&mut impl ImplWindow>) -> bool {
+        true
     }
 }
 
-fn main() {
-    #[cfg(target_os = "macos")]
-    let _loader = {
-        let loader = library_loader::LibraryLoader::new(&std::env::current_exe().unwrap(), false);
-        assert!(loader.load());
-        loader
-    };
-
-    let _ = api_hash(sys::CEF_API_VERSION_LAST, 0);
-
-    let args = Args::new();
-    let cmd = args.as_cmd_line().unwrap();
-
-    let sandbox = SandboxInfo::new();
-
-    let switch = CefString::from("type");
-    let is_browser_process = cmd.has_switch(Some(&switch)) != 1;
-
+fn main() -> cef::Result<()> {
     let window = Arc::new(Mutex::new(None));
     let mut app = DemoApp::new(window.clone());
 
-    let ret = execute_process(
-        Some(args.as_main_args()),
-        Some(&mut app),
-        sandbox.as_mut_ptr(),
-    );
-
-    if is_browser_process {
-        println!("launch browser process");
-        assert!(ret == -1, "cannot execute browser process");
-    } else {
-        let process_type = CefString::from(&cmd.get_switch_value(Some(&switch)));
-        println!("launch process {process_type}");
-        assert!(ret >= 0, "cannot execute non-browser process");
-        return;
+    let settings = Settings::builder()
+        .windowless_rendering_enabled(true)
+        .no_sandbox(true)
+        .build()?;
+
+    let runtime = launch(&mut app, &settings)?;
+    match runtime.process_type() {
+        None => println!("launch browser process"),
+        Some(process_type) => println!("launch process {process_type}"),
     }
-    let mut settings = Settings::default();
-    settings.windowless_rendering_enabled = 1;
-    settings.no_sandbox = 1;
-
-    assert_eq!(
-        initialize(
-            Some(args.as_main_args()),
-            Some(&settings),
-            Some(&mut app),
-            sandbox.as_mut_ptr()
-        ),
-        1
-    );
-
-    run_message_loop();
-
-    shutdown();
+
+    runtime.run_message_loop();
+    Ok(())
 }
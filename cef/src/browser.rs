@@ -0,0 +1,22 @@
+//! [Result] returning wrappers for creating browsers.
+
+use crate::*;
+
+/// See [browser_host_create_browser_sync] for more documentation.
+pub fn create_browser_sync(
+    window_info: &WindowInfo,
+    client: &mut impl ImplClient,
+    url: &str,
+    settings: &BrowserSettings,
+    request_context: Option<&mut impl ImplRequestContext>,
+) -> Result<Browser> {
+    browser_host_create_browser_sync(
+        Some(window_info),
+        Some(client),
+        Some(&CefString::from(url)),
+        Some(settings),
+        None::<&mut DictionaryValue>,
+        request_context,
+    )
+    .ok_or(Error::CreateBrowser)
+}
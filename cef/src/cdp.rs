@@ -0,0 +1,807 @@
+//! Typed Chrome DevTools Protocol client on top of [ImplBrowserHost::send_dev_tools_message],
+//! enabled with the `serde` feature.
+//!
+//! Commands implement [Command] with the protocol method name and response type, events
+//! implement [Event]. [Client::call] assigns each command a message ID and returns a
+//! [CommandFuture] which resolves when the matching result arrives. Events are delivered to
+//! the callbacks registered with [Client::subscribe] until the [Subscription] is dropped.
+//!
+//! The [page], [runtime], [network] and [emulation] modules cover the commonly used parts of
+//! the protocol. Anything else can be sent with [Client::call_raw] and [Client::subscribe_raw],
+//! or by implementing [Command] and [Event] for your own types:
+//!
+//! ```no_run
+//! use cef::{cdp::*, *};
+//!
+//! async fn screenshot(browser: &Browser) -> Result<Vec<u8>, Error> {
+//!     let client = Client::new(browser)?;
+//!     client
+//!         .call(&emulation::SetDeviceMetricsOverride::new(1280, 720, 1.0))
+//!         .await?;
+//!     let _load = client.subscribe(|event: page::LoadEventFired| {
+//!         println!("loaded at {}", event.timestamp);
+//!     });
+//!     client.call(&page::CaptureScreenshot::default()).await?.decode()
+//! }
+//! ```
+
+use std::{
+    collections::HashMap,
+    fmt::{self, Display, Formatter},
+    future::Future,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicI32, AtomicU64, Ordering},
+        Arc, Mutex, Weak,
+    },
+    task::{Context, Poll},
+};
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::{
+    future::{self, CallbackFuture, Sender},
+    rc::lock,
+    *,
+};
+
+/// A DevTools protocol command.
+pub trait Command: Serialize {
+    /// The protocol method name, e.g. `Page.captureScreenshot`.
+    const METHOD: &'static str;
+
+    type Response: DeserializeOwned + Send + 'static;
+}
+
+/// A DevTools protocol event.
+pub trait Event: DeserializeOwned {
+    /// The protocol method name, e.g. `Page.loadEventFired`.
+    const METHOD: &'static str;
+}
+
+/// The response of commands which don't return anything.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct Empty {}
+
+#[derive(Debug)]
+pub enum Error {
+    /// The command or response could not be serialized or deserialized.
+    Json(serde_json::Error),
+    /// The command failed, with the error reported by the DevTools agent.
+    Protocol { code: i64, message: String },
+    /// The browser has no host, or CEF rejected the message.
+    Send,
+    /// The client was dropped or the DevTools agent detached before the result arrived.
+    Closed,
+    /// The response data is not valid base64.
+    Base64,
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Json(err) => write!(f, "invalid DevTools message: {err}"),
+            Self::Protocol { code, message } => write!(f, "DevTools error {code}: {message}"),
+            Self::Send => write!(f, "cannot send DevTools message"),
+            Self::Closed => write!(f, "DevTools client closed before the result arrived"),
+            Self::Base64 => write!(f, "invalid base64 data"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Json(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(value: serde_json::Error) -> Self {
+        Self::Json(value)
+    }
+}
+
+/// Message IDs are unique across clients, since every observer added to a browser receives the
+/// results of all methods sent to it.
+static NEXT_ID: AtomicI32 = AtomicI32::new(1);
+
+type Pending = Box<dyn FnOnce(Result<&[u8], Error>) + Send>;
+type Subscriber = Arc<dyn Fn(&[u8]) + Send + Sync>;
+type Parse<T> = fn(Result<&[u8], Error>) -> Result<T, Error>;
+
+#[derive(Default)]
+struct Inner {
+    pending: Mutex<HashMap<i32, Pending>>,
+    subscribers: Mutex<HashMap<String, Vec<(u64, Subscriber)>>>,
+    next_subscription: AtomicU64,
+}
+
+impl Inner {
+    fn on_method_result(&self, message_id: i32, success: bool, result: &[u8]) {
+        let Some(pending) = lock(&self.pending).remove(&message_id) else {
+            return;
+        };
+        if success {
+            pending(Ok(result));
+        } else {
+            pending(Err(protocol_error(result)));
+        }
+    }
+
+    fn on_event(&self, method: &str, params: &[u8]) {
+        let subscribers: Vec<_> = lock(&self.subscribers)
+            .get(method)
+            .map(|subscribers| subscribers.iter().map(|(_, f)| f.clone()).collect())
+            .unwrap_or_default();
+        for subscriber in subscribers {
+            subscriber(params);
+        }
+    }
+}
+
+fn protocol_error(result: &[u8]) -> Error {
+    #[derive(Deserialize)]
+    struct ProtocolError {
+        code: i64,
+        message: String,
+    }
+
+    match serde_json::from_slice::<ProtocolError>(result) {
+        Ok(ProtocolError { code, message }) => Error::Protocol { code, message },
+        Err(_) => Error::Protocol {
+            code: 0,
+            message: String::from_utf8_lossy(result).into_owned(),
+        },
+    }
+}
+
+fn encode<P: Serialize + ?Sized>(id: i32, method: &str, params: &P) -> Result<Vec<u8>, Error> {
+    #[derive(Serialize)]
+    struct Message<'a, P: ?Sized> {
+        id: i32,
+        method: &'a str,
+        params: &'a P,
+    }
+
+    Ok(serde_json::to_vec(&Message { id, method, params })?)
+}
+
+/// A DevTools protocol client for one browser. Dropping it removes the message observer and
+/// resolves the outstanding commands to [Error::Closed].
+pub struct Client {
+    host: BrowserHost,
+    inner: Arc<Inner>,
+    _registration: Registration,
+}
+
+impl Client {
+    /// Add a DevTools message observer to `browser`. Fails with [Error::Send] if the browser
+    /// has no host, i.e. it's a renderer process browser.
+    pub fn new(browser: &impl ImplBrowser) -> Result<Self, Error> {
+        let host = browser.get_host().ok_or(Error::Send)?;
+        let inner = Arc::new(Inner::default());
+
+        let on_result = Arc::downgrade(&inner);
+        let on_event = Arc::downgrade(&inner);
+        let on_detached = Arc::downgrade(&inner);
+        let mut observer = DevToolsMessageObserver::builder()
+            .on_dev_tools_method_result(move |_, message_id, success, result| {
+                if let Some(inner) = on_result.upgrade() {
+                    inner.on_method_result(message_id, success, result.unwrap_or_default());
+                }
+            })
+            .on_dev_tools_event(move |_, method, params| {
+                if let (Some(inner), Some(method)) = (on_event.upgrade(), method) {
+                    inner.on_event(&method.to_string(), params.unwrap_or_default());
+                }
+            })
+            .on_dev_tools_agent_detached(move |_| {
+                if let Some(inner) = on_detached.upgrade() {
+                    lock(&inner.pending).clear();
+                }
+            })
+            .build();
+        let registration = host
+            .add_dev_tools_message_observer(Some(&mut observer))
+            .ok_or(Error::Send)?;
+
+        Ok(Self {
+            host,
+            inner,
+            _registration: registration,
+        })
+    }
+
+    /// Send a typed command. May be called from any thread, the message is posted to the UI
+    /// thread if necessary.
+    pub fn call<C: Command>(&self, command: &C) -> CommandFuture<C::Response> {
+        self.send(C::METHOD, command, |result| {
+            result.and_then(|result| Ok(serde_json::from_slice(result)?))
+        })
+    }
+
+    /// Send a command by method name, for parts of the protocol which have no typed [Command].
+    pub fn call_raw(
+        &self,
+        method: &str,
+        params: serde_json::Value,
+    ) -> CommandFuture<serde_json::Value> {
+        self.send(method, &params, |result| {
+            result.and_then(|result| Ok(serde_json::from_slice(result)?))
+        })
+    }
+
+    fn send<P: Serialize + ?Sized, T: Send + 'static>(
+        &self,
+        method: &str,
+        params: &P,
+        parse: Parse<T>,
+    ) -> CommandFuture<T> {
+        let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+        let message = match encode(id, method, params) {
+            Ok(message) => message,
+            Err(err) => return CommandFuture(CallbackFuture::ready(Err(err))),
+        };
+
+        let (sender, receiver): (Sender<Result<T, Error>>, _) = future::channel();
+        lock(&self.inner.pending).insert(id, Box::new(move |result| sender.send(parse(result))));
+
+        let host = self.host.clone();
+        let inner = Arc::downgrade(&self.inner);
+        let send = move || {
+            if host.send_dev_tools_message(Some(&message)) {
+                return;
+            }
+            let pending = inner
+                .upgrade()
+                .and_then(|inner| lock(&inner.pending).remove(&id));
+            if let Some(pending) = pending {
+                pending(Err(Error::Send));
+            }
+        };
+        if currently_on(ThreadId::Ui) {
+            send();
+        } else if !task::post(ThreadId::Ui, send) {
+            lock(&self.inner.pending).remove(&id);
+            return CommandFuture(CallbackFuture::ready(Err(Error::Send)));
+        }
+
+        CommandFuture(receiver)
+    }
+
+    /// Invoke `f` with every event of type `E` until the returned [Subscription] is dropped.
+    /// Events are delivered on the UI thread; ones which fail to deserialize are logged and
+    /// skipped.
+    pub fn subscribe<E: Event>(&self, f: impl Fn(E) + Send + Sync + 'static) -> Subscription {
+        self.add_subscriber(
+            E::METHOD,
+            Arc::new(move |params| match serde_json::from_slice(params) {
+                Ok(event) => f(event),
+                Err(err) => eprintln!("invalid {} event: {err}", E::METHOD),
+            }),
+        )
+    }
+
+    /// Invoke `f` with the parameters of every event named `method` until the returned
+    /// [Subscription] is dropped.
+    pub fn subscribe_raw(
+        &self,
+        method: &str,
+        f: impl Fn(serde_json::Value) + Send + Sync + 'static,
+    ) -> Subscription {
+        let name = method.to_string();
+        self.add_subscriber(
+            method,
+            Arc::new(move |params| match serde_json::from_slice(params) {
+                Ok(params) => f(params),
+                Err(err) => eprintln!("invalid {name} event: {err}"),
+            }),
+        )
+    }
+
+    fn add_subscriber(&self, method: &str, subscriber: Subscriber) -> Subscription {
+        let id = self.inner.next_subscription.fetch_add(1, Ordering::Relaxed);
+        lock(&self.inner.subscribers)
+            .entry(method.to_string())
+            .or_default()
+            .push((id, subscriber));
+        Subscription {
+            inner: Arc::downgrade(&self.inner),
+            method: method.to_string(),
+            id,
+        }
+    }
+}
+
+/// The result of [Client::call]. Resolves to [Error::Closed] if the client is dropped before
+/// the result arrives.
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct CommandFuture<T>(CallbackFuture<Result<T, Error>>);
+
+impl<T> Future for CommandFuture<T> {
+    type Output = Result<T, Error>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        Pin::new(&mut self.0)
+            .poll(cx)
+            .map(|result| result.unwrap_or(Err(Error::Closed)))
+    }
+}
+
+/// An event subscription returned by [Client::subscribe]. Dropping it removes the callback.
+#[must_use = "the callback is removed when the subscription is dropped"]
+pub struct Subscription {
+    inner: Weak<Inner>,
+    method: String,
+    id: u64,
+}
+
+impl Drop for Subscription {
+    fn drop(&mut self) {
+        let Some(inner) = self.inner.upgrade() else {
+            return;
+        };
+        let mut subscribers = lock(&inner.subscribers);
+        if let Some(list) = subscribers.get_mut(&self.method) {
+            list.retain(|(id, _)| *id != self.id);
+            if list.is_empty() {
+                subscribers.remove(&self.method);
+            }
+        }
+    }
+}
+
+fn decode_base64(input: &str) -> Option<Vec<u8>> {
+    let input = input.trim_end_matches('=').as_bytes();
+    let mut output = Vec::with_capacity(input.len() * 3 / 4);
+    let mut buffer = 0u32;
+    let mut bits = 0;
+    for &byte in input {
+        let value = match byte {
+            b'A'..=b'Z' => byte - b'A',
+            b'a'..=b'z' => byte - b'a' + 26,
+            b'0'..=b'9' => byte - b'0' + 52,
+            b'+' => 62,
+            b'/' => 63,
+            _ => return None,
+        };
+        buffer = (buffer << 6) | u32::from(value);
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            output.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+    Some(output)
+}
+
+macro_rules! command {
+    ($name:ident, $method:literal, $response:ty) => {
+        impl Command for $name {
+            const METHOD: &'static str = $method;
+            type Response = $response;
+        }
+    };
+}
+
+macro_rules! event {
+    ($name:ident, $method:literal) => {
+        impl Event for $name {
+            const METHOD: &'static str = $method;
+        }
+    };
+}
+
+/// The `Page` domain.
+pub mod page {
+    use super::*;
+
+    /// Enable the `Page` domain events.
+    #[derive(Debug, Clone, Default, Serialize)]
+    pub struct Enable {}
+    command!(Enable, "Page.enable", Empty);
+
+    #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+    #[serde(rename_all = "lowercase")]
+    pub enum ImageFormat {
+        #[default]
+        Png,
+        Jpeg,
+        Webp,
+    }
+
+    /// A page region in CSS pixels.
+    #[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+    pub struct Viewport {
+        pub x: f64,
+        pub y: f64,
+        pub width: f64,
+        pub height: f64,
+        pub scale: f64,
+    }
+
+    /// Capture a screenshot of the page.
+    #[derive(Debug, Clone, Default, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct CaptureScreenshot {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub format: Option<ImageFormat>,
+        /// Compression quality from 0 to 100, for JPEG and WebP only.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub quality: Option<u8>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub clip: Option<Viewport>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub from_surface: Option<bool>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub capture_beyond_viewport: Option<bool>,
+    }
+    command!(CaptureScreenshot, "Page.captureScreenshot", Screenshot);
+
+    #[derive(Debug, Clone, Deserialize)]
+    pub struct Screenshot {
+        /// The base64-encoded image.
+        pub data: String,
+    }
+
+    impl Screenshot {
+        /// Decode the image [data](Screenshot::data).
+        pub fn decode(&self) -> Result<Vec<u8>, Error> {
+            decode_base64(&self.data).ok_or(Error::Base64)
+        }
+    }
+
+    /// Navigate the main frame to `url`.
+    #[derive(Debug, Clone, Default, Serialize)]
+    pub struct Navigate {
+        pub url: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub referrer: Option<String>,
+    }
+    command!(Navigate, "Page.navigate", NavigateResponse);
+
+    impl Navigate {
+        pub fn new(url: impl Into<String>) -> Self {
+            Self {
+                url: url.into(),
+                ..Default::default()
+            }
+        }
+    }
+
+    #[derive(Debug, Clone, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct NavigateResponse {
+        pub frame_id: String,
+        pub loader_id: Option<String>,
+        /// Set if the navigation failed.
+        pub error_text: Option<String>,
+    }
+
+    #[derive(Debug, Clone, Default, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Reload {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub ignore_cache: Option<bool>,
+    }
+    command!(Reload, "Page.reload", Empty);
+
+    #[derive(Debug, Clone, Deserialize)]
+    pub struct LoadEventFired {
+        pub timestamp: f64,
+    }
+    event!(LoadEventFired, "Page.loadEventFired");
+
+    #[derive(Debug, Clone, Deserialize)]
+    pub struct DomContentEventFired {
+        pub timestamp: f64,
+    }
+    event!(DomContentEventFired, "Page.domContentEventFired");
+}
+
+/// The `Runtime` domain.
+pub mod runtime {
+    use super::*;
+
+    /// Enable the `Runtime` domain events.
+    #[derive(Debug, Clone, Default, Serialize)]
+    pub struct Enable {}
+    command!(Enable, "Runtime.enable", Empty);
+
+    /// Evaluate a JavaScript expression in the main frame, or in the given execution context.
+    #[derive(Debug, Clone, Default, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Evaluate {
+        pub expression: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub context_id: Option<i64>,
+        /// Return the result by value instead of as a remote object reference.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub return_by_value: Option<bool>,
+        /// Wait for the returned promise to settle and return its result.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub await_promise: Option<bool>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub user_gesture: Option<bool>,
+    }
+    command!(Evaluate, "Runtime.evaluate", EvaluateResponse);
+
+    impl Evaluate {
+        /// Evaluate `expression`, awaiting a promise result and returning it by value.
+        pub fn new(expression: impl Into<String>) -> Self {
+            Self {
+                expression: expression.into(),
+                return_by_value: Some(true),
+                await_promise: Some(true),
+                ..Default::default()
+            }
+        }
+    }
+
+    #[derive(Debug, Clone, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct EvaluateResponse {
+        pub result: RemoteObject,
+        /// Set if the expression threw.
+        pub exception_details: Option<ExceptionDetails>,
+    }
+
+    impl EvaluateResponse {
+        /// The result value, or the exception as a [Error::Protocol].
+        pub fn into_value(self) -> Result<serde_json::Value, Error> {
+            match self.exception_details {
+                Some(details) => Err(Error::Protocol {
+                    code: details.exception_id,
+                    message: details
+                        .exception
+                        .and_then(|exception| exception.description)
+                        .unwrap_or(details.text),
+                }),
+                None => Ok(self.result.value.unwrap_or_default()),
+            }
+        }
+    }
+
+    /// A JavaScript value, either by value or as a reference to the object in the renderer.
+    #[derive(Debug, Clone, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct RemoteObject {
+        #[serde(rename = "type")]
+        pub kind: String,
+        pub subtype: Option<String>,
+        pub class_name: Option<String>,
+        pub value: Option<serde_json::Value>,
+        /// Set for values which can't be represented in JSON, e.g. `NaN` or `-0`.
+        pub unserializable_value: Option<String>,
+        pub description: Option<String>,
+        pub object_id: Option<String>,
+    }
+
+    #[derive(Debug, Clone, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct ExceptionDetails {
+        pub exception_id: i64,
+        pub text: String,
+        pub line_number: i64,
+        pub column_number: i64,
+        pub url: Option<String>,
+        pub exception: Option<RemoteObject>,
+    }
+
+    #[derive(Debug, Clone, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct ConsoleApiCalled {
+        #[serde(rename = "type")]
+        pub kind: String,
+        pub args: Vec<RemoteObject>,
+        pub execution_context_id: i64,
+        pub timestamp: f64,
+    }
+    event!(ConsoleApiCalled, "Runtime.consoleAPICalled");
+}
+
+/// The `Network` domain.
+pub mod network {
+    use super::*;
+
+    /// Enable the `Network` domain events.
+    #[derive(Debug, Clone, Default, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Enable {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub max_total_buffer_size: Option<i64>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub max_resource_buffer_size: Option<i64>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub max_post_data_size: Option<i64>,
+    }
+    command!(Enable, "Network.enable", Empty);
+
+    #[derive(Debug, Clone, Default, Serialize)]
+    pub struct Disable {}
+    command!(Disable, "Network.disable", Empty);
+
+    /// Get the body of a response received while the domain was enabled.
+    #[derive(Debug, Clone, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct GetResponseBody {
+        pub request_id: String,
+    }
+    command!(GetResponseBody, "Network.getResponseBody", ResponseBody);
+
+    #[derive(Debug, Clone, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct ResponseBody {
+        pub body: String,
+        pub base64_encoded: bool,
+    }
+
+    impl ResponseBody {
+        /// The body bytes, decoding it if it's base64-encoded.
+        pub fn decode(&self) -> Result<Vec<u8>, Error> {
+            if self.base64_encoded {
+                decode_base64(&self.body).ok_or(Error::Base64)
+            } else {
+                Ok(self.body.clone().into_bytes())
+            }
+        }
+    }
+
+    #[derive(Debug, Clone, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Request {
+        pub url: String,
+        pub method: String,
+        pub headers: HashMap<String, serde_json::Value>,
+    }
+
+    #[derive(Debug, Clone, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Response {
+        pub url: String,
+        pub status: i64,
+        pub status_text: String,
+        pub headers: HashMap<String, serde_json::Value>,
+        pub mime_type: String,
+    }
+
+    #[derive(Debug, Clone, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct RequestWillBeSent {
+        pub request_id: String,
+        pub loader_id: String,
+        pub document_url: String,
+        pub request: Request,
+        pub timestamp: f64,
+        #[serde(rename = "type")]
+        pub resource_type: Option<String>,
+    }
+    event!(RequestWillBeSent, "Network.requestWillBeSent");
+
+    #[derive(Debug, Clone, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct ResponseReceived {
+        pub request_id: String,
+        pub loader_id: String,
+        pub timestamp: f64,
+        #[serde(rename = "type")]
+        pub resource_type: String,
+        pub response: Response,
+    }
+    event!(ResponseReceived, "Network.responseReceived");
+
+    #[derive(Debug, Clone, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct LoadingFinished {
+        pub request_id: String,
+        pub timestamp: f64,
+        pub encoded_data_length: f64,
+    }
+    event!(LoadingFinished, "Network.loadingFinished");
+
+    #[derive(Debug, Clone, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct LoadingFailed {
+        pub request_id: String,
+        pub timestamp: f64,
+        pub error_text: String,
+        pub canceled: Option<bool>,
+    }
+    event!(LoadingFailed, "Network.loadingFailed");
+}
+
+/// The `Emulation` domain.
+pub mod emulation {
+    use super::*;
+
+    /// Override the screen and window size and the device scale factor.
+    #[derive(Debug, Clone, Default, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct SetDeviceMetricsOverride {
+        /// Width in pixels, or 0 to disable the override.
+        pub width: u32,
+        /// Height in pixels, or 0 to disable the override.
+        pub height: u32,
+        /// Device scale factor, or 0 to disable the override.
+        pub device_scale_factor: f64,
+        /// Emulate a mobile device, i.e. overlay scrollbars and the meta viewport tag.
+        pub mobile: bool,
+    }
+    command!(
+        SetDeviceMetricsOverride,
+        "Emulation.setDeviceMetricsOverride",
+        Empty
+    );
+
+    impl SetDeviceMetricsOverride {
+        pub fn new(width: u32, height: u32, device_scale_factor: f64) -> Self {
+            Self {
+                width,
+                height,
+                device_scale_factor,
+                mobile: false,
+            }
+        }
+    }
+
+    #[derive(Debug, Clone, Default, Serialize)]
+    pub struct ClearDeviceMetricsOverride {}
+    command!(
+        ClearDeviceMetricsOverride,
+        "Emulation.clearDeviceMetricsOverride",
+        Empty
+    );
+
+    #[derive(Debug, Clone, Default, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct SetUserAgentOverride {
+        pub user_agent: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub accept_language: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub platform: Option<String>,
+    }
+    command!(
+        SetUserAgentOverride,
+        "Emulation.setUserAgentOverride",
+        Empty
+    );
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn encodes_commands() {
+        let message = encode(
+            7,
+            emulation::SetDeviceMetricsOverride::METHOD,
+            &emulation::SetDeviceMetricsOverride::new(800, 600, 2.0),
+        )
+        .unwrap();
+        assert_eq!(
+            String::from_utf8(message).unwrap(),
+            r#"{"id":7,"method":"Emulation.setDeviceMetricsOverride","params":{"width":800,"height":600,"deviceScaleFactor":2.0,"mobile":false}}"#
+        );
+
+        let message = encode(8, page::Enable::METHOD, &page::Enable {}).unwrap();
+        assert_eq!(
+            String::from_utf8(message).unwrap(),
+            r#"{"id":8,"method":"Page.enable","params":{}}"#
+        );
+    }
+
+    #[test]
+    fn decodes_base64() {
+        assert_eq!(decode_base64("").unwrap(), b"");
+        assert_eq!(decode_base64("Zg==").unwrap(), b"f");
+        assert_eq!(decode_base64("Zm9vYg==").unwrap(), b"foob");
+        assert_eq!(decode_base64("Zm9vYmFy").unwrap(), b"foobar");
+        assert!(decode_base64("Zm9v!").is_none());
+    }
+}
@@ -0,0 +1,262 @@
+//! Expose a browser's DevTools protocol channel to external tools over a local pipe, without
+//! opening a `remote_debugging_port`.
+//!
+//! Frames are forwarded unmodified in both directions: JSON messages read from the client are
+//! passed to [ImplBrowserHost::send_dev_tools_message], and every message received by the
+//! [DevToolsMessageObserver] is written back. Like Chrome's `--remote-debugging-pipe`, each
+//! frame is terminated by a NUL byte, so clients which support the pipe transport can connect
+//! directly.
+//!
+//! ```no_run
+//! use cef::{devtools_bridge::Bridge, *};
+//!
+//! // Keep the bridge alive for as long as the browser should be reachable.
+//! # #[cfg(unix)]
+//! fn expose(browser: &Browser) -> std::io::Result<Bridge> {
+//!     Bridge::listen_unix(browser, "/tmp/cef-devtools.sock")
+//! }
+//! ```
+//!
+//! The observer receives the results of all DevTools methods sent to the browser, so a client
+//! may see responses to message IDs it didn't send, e.g. from a [cdp](crate::cdp) client on the
+//! same browser. Those should be ignored.
+
+use std::{
+    io::{self, BufRead, BufReader, Read, Write},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    thread,
+};
+
+#[cfg(unix)]
+use std::{
+    os::unix::net::{UnixListener, UnixStream},
+    path::{Path, PathBuf},
+};
+
+use crate::{rc::lock, *};
+
+/// The byte which terminates each frame.
+pub const FRAME_DELIMITER: u8 = 0;
+
+type Writer = Box<dyn Write + Send>;
+
+struct Shared {
+    host: BrowserHost,
+    writer: Mutex<Option<Writer>>,
+    closed: AtomicBool,
+    #[cfg(unix)]
+    stream: Mutex<Option<UnixStream>>,
+}
+
+impl Shared {
+    fn write(&self, message: &[u8]) {
+        let mut writer = lock(&self.writer);
+        let Some(output) = writer.as_mut() else {
+            return;
+        };
+        let result = output
+            .write_all(message)
+            .and_then(|_| output.write_all(&[FRAME_DELIMITER]))
+            .and_then(|_| output.flush());
+        if let Err(err) = result {
+            eprintln!("cannot write DevTools message: {err}");
+            *writer = None;
+        }
+    }
+
+    /// Forward frames from `reader` to the browser until it reaches the end of input or the
+    /// bridge is dropped.
+    fn serve(&self, reader: impl Read, writer: Writer) {
+        *lock(&self.writer) = Some(writer);
+        for frame in frames(reader) {
+            if self.closed.load(Ordering::Acquire) {
+                break;
+            }
+            let frame = match frame {
+                Ok(frame) => frame,
+                Err(err) => {
+                    eprintln!("cannot read DevTools message: {err}");
+                    break;
+                }
+            };
+
+            let host = self.host.clone();
+            task::post(ThreadId::Ui, move || {
+                if !host.send_dev_tools_message(Some(&frame)) {
+                    eprintln!("DevTools message rejected");
+                }
+            });
+        }
+        *lock(&self.writer) = None;
+    }
+}
+
+/// Split `reader` into frames terminated by [FRAME_DELIMITER], skipping empty frames. A
+/// trailing frame without delimiter is returned as well.
+fn frames(reader: impl Read) -> impl Iterator<Item = io::Result<Vec<u8>>> {
+    let mut reader = BufReader::new(reader);
+    std::iter::from_fn(move || loop {
+        let mut frame = Vec::new();
+        match reader.read_until(FRAME_DELIMITER, &mut frame) {
+            Ok(0) => return None,
+            Ok(_) => {
+                if frame.last() == Some(&FRAME_DELIMITER) {
+                    frame.pop();
+                }
+                if !frame.is_empty() {
+                    return Some(Ok(frame));
+                }
+            }
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+            Err(err) => return Some(Err(err)),
+        }
+    })
+}
+
+/// A DevTools channel exposed over a pipe. Dropping it removes the message observer and stops
+/// forwarding frames.
+pub struct Bridge {
+    shared: Arc<Shared>,
+    #[cfg(unix)]
+    path: Option<PathBuf>,
+    _registration: Registration,
+}
+
+impl Bridge {
+    /// Forward frames read from `reader` to `browser` and write its messages to `writer`, on a
+    /// background thread. Use this for transports other than stdio and Unix sockets, e.g.
+    /// inherited file descriptors.
+    pub fn new(
+        browser: &impl ImplBrowser,
+        reader: impl Read + Send + 'static,
+        writer: impl Write + Send + 'static,
+    ) -> io::Result<Self> {
+        let bridge = Self::attach(browser)?;
+        let shared = bridge.shared.clone();
+        thread::Builder::new()
+            .name("devtools-bridge".into())
+            .spawn(move || shared.serve(reader, Box::new(writer)))?;
+        Ok(bridge)
+    }
+
+    /// Forward frames between `browser` and the process's stdin and stdout. Nothing else may
+    /// write to stdout while the bridge is alive.
+    pub fn stdio(browser: &impl ImplBrowser) -> io::Result<Self> {
+        Self::new(browser, io::stdin(), io::stdout())
+    }
+
+    /// Listen for clients on a Unix socket at `path`, replacing any stale socket file. Other
+    /// files at `path` are left alone and make binding fail. Clients are served one at a time;
+    /// the socket file is removed when the bridge is dropped.
+    #[cfg(unix)]
+    pub fn listen_unix(browser: &impl ImplBrowser, path: impl AsRef<Path>) -> io::Result<Self> {
+        use std::os::unix::fs::FileTypeExt;
+
+        let path = path.as_ref().to_path_buf();
+        if std::fs::symlink_metadata(&path).is_ok_and(|metadata| metadata.file_type().is_socket()) {
+            std::fs::remove_file(&path)?;
+        }
+        let listener = UnixListener::bind(&path)?;
+
+        let mut bridge = Self::attach(browser)?;
+        bridge.path = Some(path);
+        let shared = bridge.shared.clone();
+        thread::Builder::new()
+            .name("devtools-bridge".into())
+            .spawn(move || {
+                for stream in listener.incoming() {
+                    if shared.closed.load(Ordering::Acquire) {
+                        break;
+                    }
+                    let stream = match stream {
+                        Ok(stream) => stream,
+                        Err(err) => {
+                            eprintln!("cannot accept DevTools client: {err}");
+                            continue;
+                        }
+                    };
+                    let (reader, writer) = match (stream.try_clone(), stream.try_clone()) {
+                        (Ok(reader), Ok(writer)) => (reader, writer),
+                        (Err(err), _) | (_, Err(err)) => {
+                            eprintln!("cannot accept DevTools client: {err}");
+                            continue;
+                        }
+                    };
+                    *lock(&shared.stream) = Some(stream);
+                    shared.serve(reader, Box::new(writer));
+                    *lock(&shared.stream) = None;
+                }
+            })?;
+        Ok(bridge)
+    }
+
+    fn attach(browser: &impl ImplBrowser) -> io::Result<Self> {
+        let host = browser
+            .get_host()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::Unsupported, "browser has no host"))?;
+        let shared = Arc::new(Shared {
+            host: host.clone(),
+            writer: Default::default(),
+            closed: Default::default(),
+            #[cfg(unix)]
+            stream: Default::default(),
+        });
+
+        let observer_shared = Arc::downgrade(&shared);
+        let mut observer = DevToolsMessageObserver::builder()
+            .on_dev_tools_message(move |_, message| {
+                if let (Some(shared), Some(message)) = (observer_shared.upgrade(), message) {
+                    shared.write(message);
+                }
+                true
+            })
+            .build();
+        let registration = host
+            .add_dev_tools_message_observer(Some(&mut observer))
+            .ok_or_else(|| io::Error::other("cannot add DevTools message observer"))?;
+
+        Ok(Self {
+            shared,
+            #[cfg(unix)]
+            path: None,
+            _registration: registration,
+        })
+    }
+}
+
+impl Drop for Bridge {
+    fn drop(&mut self) {
+        self.shared.closed.store(true, Ordering::Release);
+        *lock(&self.shared.writer) = None;
+
+        #[cfg(unix)]
+        {
+            if let Some(stream) = lock(&self.shared.stream).take() {
+                let _ = stream.shutdown(std::net::Shutdown::Both);
+            }
+            if let Some(path) = self.path.take() {
+                // Wake the listener thread so it sees the bridge is closed.
+                let _ = UnixStream::connect(&path);
+                let _ = std::fs::remove_file(&path);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn splits_frames() {
+        let input: &[u8] = b"{\"id\":1}\0\0{\"id\":2}\0{\"id\":3}";
+        let frames: Vec<_> = frames(input).map(Result::unwrap).collect();
+        assert_eq!(
+            frames,
+            [&b"{\"id\":1}"[..], &b"{\"id\":2}"[..], &b"{\"id\":3}"[..]]
+        );
+    }
+}
@@ -0,0 +1,62 @@
+//! Errors returned by the [Result](crate::Result) wrappers around CEF's process and browser
+//! lifecycle functions, so failures can be propagated with `?` instead of checking magic
+//! return values.
+
+use std::{
+    fmt::{self, Display, Formatter},
+    io,
+    path::PathBuf,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// [cef_load_library](crate::sys::cef_load_library) failed to load the CEF framework.
+    LoadLibrary(PathBuf),
+    /// [current_exe](std::env::current_exe) failed, so the CEF framework cannot be located
+    /// relative to the executable.
+    CurrentExe { kind: io::ErrorKind, reason: String },
+    /// [cef_execute_process](crate::sys::cef_execute_process) returned a negative value other
+    /// than `-1`.
+    ExecuteProcess(i32),
+    /// [cef_initialize](crate::sys::cef_initialize) failed, `exit_code` is the value of
+    /// [get_exit_code](crate::get_exit_code) which can be compared against
+    /// [Resultcode](crate::Resultcode).
+    Initialize { exit_code: i32 },
+    /// [browser_host_create_browser_sync](crate::browser_host_create_browser_sync) did not
+    /// return a browser.
+    CreateBrowser,
+    /// [register_scheme_handler_factory](crate::register_scheme_handler_factory) rejected the
+    /// factory for this scheme.
+    RegisterSchemeHandlerFactory { scheme_name: String },
+    /// A [settings](crate::settings) builder rejected the value of `field`.
+    InvalidSettings { field: &'static str, reason: String },
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::LoadLibrary(path) => write!(f, "cannot load library {}", path.display()),
+            Self::CurrentExe { reason, .. } => {
+                write!(f, "cannot find current executable: {reason}")
+            }
+            Self::ExecuteProcess(code) => {
+                write!(f, "cannot execute process, unexpected return value {code}")
+            }
+            Self::Initialize { exit_code } => {
+                write!(f, "cannot initialize CEF, exit code {exit_code}")
+            }
+            Self::CreateBrowser => write!(f, "cannot create browser"),
+            Self::RegisterSchemeHandlerFactory { scheme_name } => {
+                write!(
+                    f,
+                    "cannot register scheme handler factory for {scheme_name}"
+                )
+            }
+            Self::InvalidSettings { field, reason } => write!(f, "invalid {field}: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
@@ -0,0 +1,333 @@
+//! Runtime-agnostic [Future] adapters for CEF's one-shot callback interfaces.
+//!
+//! Each `*_async` method creates the corresponding callback object with a closure which sends
+//! the result over a oneshot channel, and returns a [CallbackFuture] for the receiving end. The
+//! futures don't depend on any particular executor, they are woken from whichever CEF thread
+//! invokes the callback.
+//!
+//! If CEF releases the callback without ever invoking it, e.g. because the browser was closed
+//! before the operation finished, the future resolves to [Err(Canceled)](Canceled).
+
+use std::{
+    error::Error,
+    fmt::{self, Display, Formatter},
+    future::Future,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll, Waker},
+};
+
+use crate::{rc::lock, *};
+
+/// The callback was released by CEF without being invoked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Canceled;
+
+impl Display for Canceled {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "CEF released the callback without invoking it")
+    }
+}
+
+impl Error for Canceled {}
+
+struct Shared<T> {
+    value: Option<T>,
+    waker: Option<Waker>,
+    complete: bool,
+}
+
+/// Sending half of the oneshot channel, owned by the callback closure. Dropping it without
+/// sending a value cancels the [CallbackFuture].
+pub(crate) struct Sender<T>(Arc<Mutex<Shared<T>>>);
+
+impl<T> Sender<T> {
+    pub(crate) fn send(&self, value: T) {
+        let mut shared = lock(&self.0);
+        if shared.complete {
+            return;
+        }
+
+        shared.value = Some(value);
+        shared.complete = true;
+        if let Some(waker) = shared.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        let mut shared = lock(&self.0);
+        shared.complete = true;
+        if let Some(waker) = shared.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+/// Receiving half of the oneshot channel returned by the `*_async` methods.
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct CallbackFuture<T>(Arc<Mutex<Shared<T>>>);
+
+impl<T> CallbackFuture<T> {
+    /// A future which is already resolved, e.g. because CEF rejected the request synchronously.
+    pub(crate) fn ready(value: T) -> Self {
+        let (sender, receiver) = channel();
+        sender.send(value);
+        receiver
+    }
+}
+
+impl<T> Future for CallbackFuture<T> {
+    type Output = Result<T, Canceled>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut shared = lock(&self.0);
+        if let Some(value) = shared.value.take() {
+            return Poll::Ready(Ok(value));
+        }
+        if shared.complete {
+            return Poll::Ready(Err(Canceled));
+        }
+
+        match &mut shared.waker {
+            Some(waker) if waker.will_wake(cx.waker()) => {}
+            waker => *waker = Some(cx.waker().clone()),
+        }
+        Poll::Pending
+    }
+}
+
+pub(crate) fn channel<T>() -> (Sender<T>, CallbackFuture<T>) {
+    let shared = Arc::new(Mutex::new(Shared {
+        value: None,
+        waker: None,
+        complete: false,
+    }));
+    (Sender(shared.clone()), CallbackFuture(shared))
+}
+
+/// The result of [BrowserHost::download_image_async].
+#[derive(Clone)]
+pub struct DownloadedImage {
+    pub image_url: String,
+    pub http_status_code: i32,
+    pub image: Option<Image>,
+}
+
+fn string_visitor() -> (CefStringVisitor, CallbackFuture<String>) {
+    let (sender, receiver) = channel();
+    let visitor = CefStringVisitor::builder()
+        .visit(move |string| {
+            sender.send(string.map(ToString::to_string).unwrap_or_default());
+        })
+        .build();
+    (visitor, receiver)
+}
+
+fn completion_callback() -> (CompletionCallback, CallbackFuture<()>) {
+    let (sender, receiver) = channel();
+    let callback = CompletionCallback::builder()
+        .on_complete(move || sender.send(()))
+        .build();
+    (callback, receiver)
+}
+
+impl Frame {
+    /// Async version of [ImplFrame::get_source].
+    pub fn get_source_async(&self) -> CallbackFuture<String> {
+        let (mut visitor, receiver) = string_visitor();
+        self.get_source(Some(&mut visitor));
+        receiver
+    }
+
+    /// Async version of [ImplFrame::get_text].
+    pub fn get_text_async(&self) -> CallbackFuture<String> {
+        let (mut visitor, receiver) = string_visitor();
+        self.get_text(Some(&mut visitor));
+        receiver
+    }
+}
+
+impl CookieManager {
+    /// Async version of [ImplCookieManager::set_cookie]. Resolves to `false` if the cookie
+    /// could not be set, including when the URL or cookie is rejected up front.
+    pub fn set_cookie_async(
+        &self,
+        url: Option<&CefString>,
+        cookie: Option<&Cookie>,
+    ) -> CallbackFuture<bool> {
+        let (sender, receiver) = channel();
+        let mut callback = SetCookieCallback::builder()
+            .on_complete(move |success| sender.send(success))
+            .build();
+        if !self.set_cookie(url, cookie, Some(&mut callback)) {
+            return CallbackFuture::ready(false);
+        }
+        receiver
+    }
+
+    /// Async version of [ImplCookieManager::delete_cookies]. Resolves to the number of cookies
+    /// which were deleted.
+    pub fn delete_cookies_async(
+        &self,
+        url: Option<&CefString>,
+        cookie_name: Option<&CefString>,
+    ) -> CallbackFuture<usize> {
+        let (sender, receiver) = channel();
+        let mut callback = DeleteCookiesCallback::builder()
+            .on_complete(move |num_deleted| {
+                sender.send(usize::try_from(num_deleted).unwrap_or_default())
+            })
+            .build();
+        if !self.delete_cookies(url, cookie_name, Some(&mut callback)) {
+            return CallbackFuture::ready(0);
+        }
+        receiver
+    }
+
+    /// Async version of [ImplCookieManager::flush_store].
+    pub fn flush_store_async(&self) -> CallbackFuture<()> {
+        let (mut callback, receiver) = completion_callback();
+        if !self.flush_store(Some(&mut callback)) {
+            return CallbackFuture::ready(());
+        }
+        receiver
+    }
+}
+
+impl RequestContext {
+    /// Async version of [ImplRequestContext::clear_certificate_exceptions].
+    pub fn clear_certificate_exceptions_async(&self) -> CallbackFuture<()> {
+        let (mut callback, receiver) = completion_callback();
+        self.clear_certificate_exceptions(Some(&mut callback));
+        receiver
+    }
+
+    /// Async version of [ImplRequestContext::clear_http_auth_credentials].
+    pub fn clear_http_auth_credentials_async(&self) -> CallbackFuture<()> {
+        let (mut callback, receiver) = completion_callback();
+        self.clear_http_auth_credentials(Some(&mut callback));
+        receiver
+    }
+
+    /// Async version of [ImplRequestContext::close_all_connections].
+    pub fn close_all_connections_async(&self) -> CallbackFuture<()> {
+        let (mut callback, receiver) = completion_callback();
+        self.close_all_connections(Some(&mut callback));
+        receiver
+    }
+
+    /// Async version of [ImplRequestContext::resolve_host]. Resolves to the list of IP
+    /// addresses, or the error code if the lookup failed.
+    pub fn resolve_host_async(
+        &self,
+        origin: Option<&CefString>,
+    ) -> CallbackFuture<Result<Vec<String>, Errorcode>> {
+        let (sender, receiver) = channel();
+        let mut callback = ResolveCallback::builder()
+            .on_resolve_completed(move |result, resolved_ips| {
+                if result != Errorcode::None {
+                    sender.send(Err(result));
+                    return;
+                }
+
+                let resolved_ips = resolved_ips
+                    .map(|resolved_ips| {
+                        let resolved_ips: *mut sys::_cef_string_list_t = resolved_ips.into();
+                        CefStringList::from(resolved_ips).into_iter().collect()
+                    })
+                    .unwrap_or_default();
+                sender.send(Ok(resolved_ips));
+            })
+            .build();
+        self.resolve_host(origin, Some(&mut callback));
+        receiver
+    }
+}
+
+impl BrowserHost {
+    /// Async version of [ImplBrowserHost::print_to_pdf]. Resolves to `true` if the PDF was
+    /// written successfully.
+    pub fn print_to_pdf_async(
+        &self,
+        path: Option<&CefString>,
+        settings: Option<&PdfPrintSettings>,
+    ) -> CallbackFuture<bool> {
+        let (sender, receiver) = channel();
+        let mut callback = PdfPrintCallback::builder()
+            .on_pdf_print_finished(move |_path, ok| sender.send(ok))
+            .build();
+        self.print_to_pdf(path, settings, Some(&mut callback));
+        receiver
+    }
+
+    /// Async version of [ImplBrowserHost::download_image].
+    pub fn download_image_async(
+        &self,
+        image_url: Option<&CefString>,
+        is_favicon: bool,
+        max_image_size: u32,
+        bypass_cache: bool,
+    ) -> CallbackFuture<DownloadedImage> {
+        let (sender, receiver) = channel();
+        let mut callback = DownloadImageCallback::builder()
+            .on_download_image_finished(move |image_url, http_status_code, image| {
+                sender.send(DownloadedImage {
+                    image_url: image_url.map(ToString::to_string).unwrap_or_default(),
+                    http_status_code,
+                    image: image.cloned(),
+                });
+            })
+            .build();
+        self.download_image(
+            image_url,
+            is_favicon,
+            max_image_size,
+            bypass_cache,
+            Some(&mut callback),
+        );
+        receiver
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn poll<T>(future: &mut CallbackFuture<T>) -> Poll<Result<T, Canceled>> {
+        let mut cx = Context::from_waker(Waker::noop());
+        Pin::new(future).poll(&mut cx)
+    }
+
+    #[test]
+    fn resolves_with_value() {
+        let (sender, mut receiver) = channel();
+        assert_eq!(poll(&mut receiver), Poll::Pending);
+        sender.send(42);
+        assert_eq!(poll(&mut receiver), Poll::Ready(Ok(42)));
+    }
+
+    #[test]
+    fn keeps_first_value() {
+        let (sender, mut receiver) = channel();
+        sender.send(1);
+        sender.send(2);
+        drop(sender);
+        assert_eq!(poll(&mut receiver), Poll::Ready(Ok(1)));
+    }
+
+    #[test]
+    fn canceled_when_dropped() {
+        let (sender, mut receiver) = channel::<()>();
+        drop(sender);
+        assert_eq!(poll(&mut receiver), Poll::Ready(Err(Canceled)));
+    }
+
+    #[test]
+    fn ready() {
+        let mut receiver = CallbackFuture::ready("done");
+        assert_eq!(poll(&mut receiver), Poll::Ready(Ok("done")));
+    }
+}
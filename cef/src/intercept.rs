@@ -0,0 +1,549 @@
+//! Intercept network requests with rules, to block or redirect them, change their headers,
+//! answer them with mocked responses or transform the response body.
+//!
+//! An [Interceptor] holds a list of [Rule]s, which match requests by URL and optionally by
+//! [ResourceType]. Every matching rule applies its header changes and body filter, the first
+//! matching rule which blocks, redirects or responds decides what happens to the request.
+//!
+//! ```no_run
+//! use cef::{intercept::*, *};
+//!
+//! let interceptor = Interceptor::new()
+//!     .rule(Rule::glob("*://ads.example.com/*").block())
+//!     .rule(Rule::glob("https://api.example.com/*").set_header("Authorization", "Bearer token"))
+//!     .rule(
+//!         Rule::glob("https://api.example.com/pose")
+//!             .resource_types([ResourceType::Xhr])
+//!             .respond(MockResponse::new(200).json(r#"{"x":0,"y":0}"#)),
+//!     )
+//!     .rule(Rule::glob("*.html").transform_body(|body| {
+//!         String::from_utf8_lossy(&body)
+//!             .replace("</body>", "<script>console.log('injected')</script></body>")
+//!             .into_bytes()
+//!     }));
+//!
+//! // Use it as the client's request handler, or call
+//! // Interceptor::resource_request_handler from an existing one.
+//! let mut request_handler = interceptor.request_handler();
+//! ```
+
+use std::{
+    borrow::Cow,
+    sync::{Arc, Mutex},
+};
+
+use crate::{rc::lock, scheme::StaticResponse, *};
+
+/// Matches the request URL of a [Rule].
+#[derive(Debug, Clone)]
+enum Pattern {
+    Any,
+    Glob(String),
+    #[cfg(feature = "regex")]
+    Regex(regex::Regex),
+}
+
+impl Pattern {
+    fn is_match(&self, url: &str) -> bool {
+        match self {
+            Self::Any => true,
+            Self::Glob(pattern) => glob_match(pattern, url),
+            #[cfg(feature = "regex")]
+            Self::Regex(regex) => regex.is_match(url),
+        }
+    }
+}
+
+/// Match `text` against a pattern where `*` matches any sequence of characters and `?` a single
+/// character.
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern = pattern.chars().collect::<Vec<_>>();
+    let text = text.chars().collect::<Vec<_>>();
+    let (mut p, mut t) = (0, 0);
+    let mut backtrack = None;
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, t));
+                p += 1;
+            }
+            Some(c) if *c == '?' || *c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match backtrack {
+                Some((star, start)) => {
+                    p = star + 1;
+                    t = start + 1;
+                    backtrack = Some((star, start + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+/// A canned response for [Rule::respond].
+#[derive(Debug, Clone)]
+pub struct MockResponse {
+    status: i32,
+    status_text: String,
+    mime_type: String,
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+}
+
+impl MockResponse {
+    pub fn new(status: i32) -> Self {
+        Self {
+            status,
+            status_text: String::new(),
+            mime_type: "text/plain".to_string(),
+            headers: vec![],
+            body: vec![],
+        }
+    }
+
+    pub fn status_text(mut self, status_text: &str) -> Self {
+        self.status_text = status_text.to_string();
+        self
+    }
+
+    pub fn mime_type(mut self, mime_type: &str) -> Self {
+        self.mime_type = mime_type.to_string();
+        self
+    }
+
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    pub fn body(mut self, body: impl Into<Vec<u8>>) -> Self {
+        self.body = body.into();
+        self
+    }
+
+    /// Set a JSON body and the `application/json` MIME type.
+    pub fn json(self, body: impl Into<Vec<u8>>) -> Self {
+        self.mime_type("application/json").body(body)
+    }
+
+    fn into_handler(self) -> ResourceHandler {
+        let range = 0..self.body.len();
+        StaticResponse {
+            status: self.status,
+            status_text: self.status_text.into(),
+            mime_type: self.mime_type.into(),
+            headers: self
+                .headers
+                .into_iter()
+                .map(|(name, value)| (Cow::Owned(name), value))
+                .collect(),
+            data: self.body.into(),
+            range,
+        }
+        .into_handler()
+    }
+}
+
+/// Transforms a response body as it is streamed, see [Rule::filter].
+pub trait BodyFilter: Send {
+    /// Transform the next chunk of the body, returning the output for it.
+    fn chunk(&mut self, data: &[u8]) -> Vec<u8>;
+
+    /// Called after the last chunk, returning any remaining output.
+    fn finish(&mut self) -> Vec<u8> {
+        vec![]
+    }
+}
+
+/// Buffers the whole body and transforms it in [BodyFilter::finish].
+struct TransformBody<F> {
+    body: Vec<u8>,
+    transform: Arc<F>,
+}
+
+impl<F> BodyFilter for TransformBody<F>
+where
+    F: Fn(Vec<u8>) -> Vec<u8> + Send + Sync,
+{
+    fn chunk(&mut self, data: &[u8]) -> Vec<u8> {
+        self.body.extend_from_slice(data);
+        vec![]
+    }
+
+    fn finish(&mut self) -> Vec<u8> {
+        (self.transform)(std::mem::take(&mut self.body))
+    }
+}
+
+type Respond = Arc<dyn Fn(&Request) -> MockResponse + Send + Sync>;
+type CreateFilter = Arc<dyn Fn() -> Box<dyn BodyFilter> + Send + Sync>;
+
+#[derive(Clone)]
+enum Action {
+    Continue,
+    Block,
+    Redirect(String),
+    Respond(Respond),
+}
+
+#[derive(Debug, Clone)]
+enum HeaderChange {
+    Set(String, String),
+    Remove(String),
+}
+
+/// Matches requests and describes what to do with them, see the [module](self) documentation.
+#[derive(Clone)]
+pub struct Rule {
+    pattern: Pattern,
+    resource_types: Vec<ResourceType>,
+    headers: Vec<HeaderChange>,
+    action: Action,
+    filter: Option<CreateFilter>,
+}
+
+impl Rule {
+    fn new(pattern: Pattern) -> Self {
+        Self {
+            pattern,
+            resource_types: vec![],
+            headers: vec![],
+            action: Action::Continue,
+            filter: None,
+        }
+    }
+
+    /// Match every request.
+    pub fn any() -> Self {
+        Self::new(Pattern::Any)
+    }
+
+    /// Match the whole URL against a pattern where `*` matches any sequence of characters and
+    /// `?` a single character, e.g. `https://example.com/api/*`.
+    pub fn glob(pattern: &str) -> Self {
+        Self::new(Pattern::Glob(pattern.to_string()))
+    }
+
+    /// Match the URL against a regular expression, enabled with the `regex` feature.
+    #[cfg(feature = "regex")]
+    pub fn regex(pattern: &str) -> std::result::Result<Self, regex::Error> {
+        Ok(Self::new(Pattern::Regex(regex::Regex::new(pattern)?)))
+    }
+
+    /// Only match requests for these resource types.
+    pub fn resource_types(
+        mut self,
+        resource_types: impl IntoIterator<Item = ResourceType>,
+    ) -> Self {
+        self.resource_types.extend(resource_types);
+        self
+    }
+
+    /// Cancel matching requests.
+    pub fn block(mut self) -> Self {
+        self.action = Action::Block;
+        self
+    }
+
+    /// Redirect matching requests to `url`.
+    pub fn redirect(mut self, url: &str) -> Self {
+        self.action = Action::Redirect(url.to_string());
+        self
+    }
+
+    /// Answer matching requests with `response` instead of sending them to the network.
+    pub fn respond(self, response: MockResponse) -> Self {
+        self.respond_with(move |_| response.clone())
+    }
+
+    /// Answer matching requests with the response returned by `respond`.
+    pub fn respond_with(
+        mut self,
+        respond: impl Fn(&Request) -> MockResponse + Send + Sync + 'static,
+    ) -> Self {
+        self.action = Action::Respond(Arc::new(respond));
+        self
+    }
+
+    /// Set a request header, replacing any existing values.
+    pub fn set_header(mut self, name: &str, value: &str) -> Self {
+        self.headers
+            .push(HeaderChange::Set(name.to_string(), value.to_string()));
+        self
+    }
+
+    /// Remove a request header.
+    pub fn remove_header(mut self, name: &str) -> Self {
+        self.headers.push(HeaderChange::Remove(name.to_string()));
+        self
+    }
+
+    /// Stream the response body through a new [BodyFilter] created for each response.
+    pub fn filter<F: BodyFilter + 'static>(
+        mut self,
+        filter: impl Fn() -> F + Send + Sync + 'static,
+    ) -> Self {
+        self.filter = Some(Arc::new(move || Box::new(filter())));
+        self
+    }
+
+    /// Transform the whole response body at once. The body is buffered until it is complete.
+    pub fn transform_body(
+        self,
+        transform: impl Fn(Vec<u8>) -> Vec<u8> + Send + Sync + 'static,
+    ) -> Self {
+        let transform = Arc::new(transform);
+        self.filter(move || TransformBody {
+            body: vec![],
+            transform: transform.clone(),
+        })
+    }
+
+    fn is_match(&self, url: &str, resource_type: ResourceType) -> bool {
+        (self.resource_types.is_empty() || self.resource_types.contains(&resource_type))
+            && self.pattern.is_match(url)
+    }
+}
+
+/// The rules which matched a request.
+struct Matched {
+    headers: Vec<HeaderChange>,
+    action: Action,
+    filter: Option<CreateFilter>,
+}
+
+/// A list of [Rule]s, see the [module](self) documentation.
+#[derive(Clone, Default)]
+pub struct Interceptor {
+    rules: Vec<Rule>,
+}
+
+impl Interceptor {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Add a rule, rules are matched in the order they were added.
+    pub fn rule(mut self, rule: Rule) -> Self {
+        self.rules.push(rule);
+        self
+    }
+
+    fn matches(&self, url: &str, resource_type: ResourceType) -> Option<Matched> {
+        let mut matched = None::<Matched>;
+        for rule in self
+            .rules
+            .iter()
+            .filter(|rule| rule.is_match(url, resource_type))
+        {
+            let matched = matched.get_or_insert_with(|| Matched {
+                headers: vec![],
+                action: Action::Continue,
+                filter: None,
+            });
+            matched.headers.extend(rule.headers.iter().cloned());
+            if matches!(matched.action, Action::Continue) {
+                matched.action = rule.action.clone();
+            }
+            if matched.filter.is_none() {
+                matched.filter = rule.filter.clone();
+            }
+        }
+        matched
+    }
+
+    /// Create the handler for a request, for use in
+    /// [ImplRequestHandler::get_resource_request_handler]. Returns `None` if no rule matches,
+    /// so the request is handled normally.
+    pub fn resource_request_handler(
+        &self,
+        request: Option<&mut impl ImplRequest>,
+    ) -> Option<ResourceRequestHandler> {
+        let request = request?;
+        let url = CefString::from(&request.get_url()).to_string();
+        let matched = Arc::new(self.matches(&url, request.get_resource_type())?);
+
+        let before_load = matched.clone();
+        let resource_handler = matched.clone();
+        let response_filter = matched;
+        Some(
+            ResourceRequestHandler::builder()
+                .on_before_resource_load(move |_, _, request, _| {
+                    let Some(request) = request else {
+                        return ReturnValue::Continue;
+                    };
+                    apply_headers(request, &before_load.headers);
+                    match &before_load.action {
+                        Action::Block => ReturnValue::Cancel,
+                        Action::Redirect(url) => {
+                            request.set_url(Some(&CefString::from(url.as_str())));
+                            ReturnValue::Continue
+                        }
+                        Action::Continue | Action::Respond(_) => ReturnValue::Continue,
+                    }
+                })
+                .get_resource_handler(move |_, _, request| match &resource_handler.action {
+                    Action::Respond(respond) => Some(respond(request?).into_handler()),
+                    _ => None,
+                })
+                .get_resource_response_filter(move |_, _, _, _| {
+                    response_filter
+                        .filter
+                        .as_ref()
+                        .map(|create| response_filter_for(create()))
+                })
+                .build(),
+        )
+    }
+
+    /// Create a [RequestHandler] which only intercepts requests with these rules.
+    pub fn request_handler(&self) -> RequestHandler {
+        let interceptor = self.clone();
+        RequestHandler::builder()
+            .get_resource_request_handler(move |_, _, request, _, _, _, _| {
+                interceptor.resource_request_handler(request)
+            })
+            .build()
+    }
+}
+
+fn apply_headers(request: &mut Request, changes: &[HeaderChange]) {
+    if changes.is_empty() {
+        return;
+    }
+
+    let mut headers = CefStringMultimap::new();
+    request.get_header_map(Some(&mut headers));
+    let mut headers = headers
+        .into_iter()
+        .flat_map(|(name, values)| values.into_iter().map(move |value| (name.clone(), value)))
+        .collect::<Vec<_>>();
+    for change in changes {
+        match change {
+            HeaderChange::Set(name, value) => {
+                headers.retain(|(key, _)| !key.eq_ignore_ascii_case(name));
+                headers.push((name.clone(), value.clone()));
+            }
+            HeaderChange::Remove(name) => {
+                headers.retain(|(key, _)| !key.eq_ignore_ascii_case(name));
+            }
+        }
+    }
+
+    let mut header_map = CefStringMultimap::new();
+    for (name, value) in &headers {
+        header_map.append(name, value);
+    }
+    request.set_header_map(Some(&mut header_map));
+}
+
+/// The output of a [BodyFilter] which hasn't been written yet.
+struct FilterState {
+    filter: Box<dyn BodyFilter>,
+    pending: Vec<u8>,
+    finished: bool,
+}
+
+fn response_filter_for(filter: Box<dyn BodyFilter>) -> ResponseFilter {
+    let state = Mutex::new(FilterState {
+        filter,
+        pending: vec![],
+        finished: false,
+    });
+    ResponseFilter::builder()
+        .init_filter(|| true)
+        .filter(move |data_in, data_in_read, data_out, data_out_written| {
+            let mut state = lock(&state);
+            let end_of_input = match data_in {
+                Some(data_in) => {
+                    let output = state.filter.chunk(data_in);
+                    state.pending.extend(output);
+                    if let Some(data_in_read) = data_in_read {
+                        *data_in_read = data_in.len();
+                    }
+                    false
+                }
+                None => {
+                    if !state.finished {
+                        state.finished = true;
+                        let output = state.filter.finish();
+                        state.pending.extend(output);
+                    }
+                    true
+                }
+            };
+
+            let written = match data_out {
+                Some(data_out) => {
+                    let count = data_out.len().min(state.pending.len());
+                    data_out[..count].copy_from_slice(&state.pending[..count]);
+                    state.pending.drain(..count);
+                    count
+                }
+                None => 0,
+            };
+            if let Some(data_out_written) = data_out_written {
+                *data_out_written = written;
+            }
+
+            if end_of_input && state.pending.is_empty() {
+                ResponseFilterStatus::Done
+            } else {
+                ResponseFilterStatus::NeedMoreData
+            }
+        })
+        .build()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn glob_patterns() {
+        assert!(glob_match(
+            "https://example.com/*",
+            "https://example.com/api/pose"
+        ));
+        assert!(glob_match(
+            "*://ads.*/*",
+            "http://ads.example.com/banner.js"
+        ));
+        assert!(glob_match("https://example.com/?", "https://example.com/a"));
+        assert!(glob_match("*.html", "app://localhost/index.html"));
+        assert!(!glob_match("*.html", "app://localhost/index.html?v=1"));
+        assert!(!glob_match(
+            "https://example.com/",
+            "https://example.com/api"
+        ));
+    }
+
+    #[test]
+    fn first_action_wins() {
+        let interceptor = Interceptor::new()
+            .rule(Rule::glob("https://example.com/*").set_header("X-Test", "1"))
+            .rule(Rule::glob("*/blocked").block())
+            .rule(Rule::any().redirect("https://example.com/"))
+            .rule(
+                Rule::any()
+                    .resource_types([ResourceType::Image])
+                    .remove_header("Cookie"),
+            );
+
+        let matched = interceptor
+            .matches("https://example.com/blocked", ResourceType::Xhr)
+            .unwrap();
+        assert!(matches!(matched.action, Action::Block));
+        assert_eq!(matched.headers.len(), 1);
+
+        let matched = interceptor
+            .matches("https://example.org/image.png", ResourceType::Image)
+            .unwrap();
+        assert!(matches!(matched.action, Action::Redirect(_)));
+        assert!(matches!(&matched.headers[..], [HeaderChange::Remove(_)]));
+    }
+}
@@ -0,0 +1,481 @@
+//! Typed inter-process messaging on top of [ProcessMessage], enabled with the `serde` feature.
+//!
+//! Message types implement [Message] with a unique name and are serialized as JSON. Events are
+//! sent with [Frame::send_typed], requests with [Router::request], which correlates the
+//! response with the request by ID. Payloads larger than [SHARED_MEMORY_THRESHOLD] are sent in
+//! a [SharedMemoryRegion] instead of the message argument list.
+//!
+//! The same [Router] type is used in the browser process, from
+//! [ImplClient::on_process_message_received], and in the renderer process, from
+//! [ImplRenderProcessHandler::on_process_message_received]. Pending requests are canceled from
+//! [ImplRequestHandler::on_before_browse], [ImplRequestHandler::on_render_process_terminated] and
+//! [ImplLifeSpanHandler::on_before_close] in the browser process:
+//!
+//! ```no_run
+//! use cef::{ipc::*, *};
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct GetTitle;
+//!
+//! impl Message for GetTitle {
+//!     const NAME: &'static str = "get-title";
+//! }
+//!
+//! impl Request for GetTitle {
+//!     type Response = String;
+//! }
+//!
+//! let router = Router::new();
+//! router.on_request(|frame: &Frame, _: GetTitle| {
+//!     CefString::from(&frame.get_url()).to_string()
+//! });
+//! ```
+
+use std::{
+    collections::HashMap,
+    ffi::c_int,
+    fmt::{self, Display, Formatter},
+    slice,
+    sync::{
+        atomic::{AtomicI32, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::{
+    pending::{self, Origin, Scope},
+    rc::{lock, ConvertReturnValue},
+    *,
+};
+
+/// Payloads larger than this many bytes are sent in a [SharedMemoryRegion].
+pub const SHARED_MEMORY_THRESHOLD: usize = 64 * 1024;
+
+/// A message type which can be sent between processes.
+pub trait Message: Serialize + DeserializeOwned {
+    /// The [ProcessMessage] name, which must be unique among the registered messages.
+    const NAME: &'static str;
+}
+
+/// A [Message] which is answered with a response.
+pub trait Request: Message {
+    type Response: Serialize + DeserializeOwned;
+}
+
+#[derive(Debug)]
+pub enum Error {
+    /// The payload could not be serialized or deserialized.
+    Json(serde_json::Error),
+    /// CEF could not create the process message or shared memory region.
+    CreateMessage,
+    /// The process message was not created by this module.
+    InvalidMessage,
+    /// The request was canceled before its response arrived, because its frame navigated, its
+    /// browser closed or its render process terminated.
+    Canceled,
+    /// The other process could not handle the request, e.g. because no handler is registered
+    /// for it or its payload is invalid.
+    Remote(String),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Json(err) => write!(f, "invalid message payload: {err}"),
+            Self::CreateMessage => write!(f, "cannot create process message"),
+            Self::InvalidMessage => write!(f, "not a typed process message"),
+            Self::Canceled => write!(f, "request canceled"),
+            Self::Remote(message) => write!(f, "request failed: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Json(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(value: serde_json::Error) -> Self {
+        Self::Json(value)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Event,
+    Request,
+    Response,
+    /// The response to a request which could not be handled, carrying the error message.
+    Error,
+}
+
+impl Kind {
+    fn from_i32(value: i32) -> Option<Self> {
+        match value {
+            0 => Some(Self::Event),
+            1 => Some(Self::Request),
+            2 => Some(Self::Response),
+            3 => Some(Self::Error),
+            _ => None,
+        }
+    }
+}
+
+/// Size of the `kind` and `id` header in front of payloads in shared memory.
+const HEADER_SIZE: usize = 5;
+
+/// A decoded process message.
+struct Envelope {
+    name: String,
+    kind: Kind,
+    id: i32,
+    payload: Vec<u8>,
+}
+
+impl Envelope {
+    fn decode(message: &impl ImplProcessMessage) -> Result<Self, Error> {
+        let name = CefString::from(&message.get_name()).to_string();
+
+        if let Some(region) = message
+            .get_shared_memory_region()
+            .filter(|region| region.is_valid())
+        {
+            let data = region.memory().cast::<u8>();
+            let size = region.size();
+            if data.is_null() || size < HEADER_SIZE {
+                return Err(Error::InvalidMessage);
+            }
+
+            let data = unsafe { slice::from_raw_parts(data, size) };
+            let kind = Kind::from_i32(data[0].into()).ok_or(Error::InvalidMessage)?;
+            let id = i32::from_le_bytes(data[1..HEADER_SIZE].try_into().unwrap());
+            return Ok(Self {
+                name,
+                kind,
+                id,
+                payload: data[HEADER_SIZE..].to_vec(),
+            });
+        }
+
+        let args = message.get_argument_list().ok_or(Error::InvalidMessage)?;
+        if args.get_size() != 3 {
+            return Err(Error::InvalidMessage);
+        }
+        let kind = Kind::from_i32(args.get_int(0)).ok_or(Error::InvalidMessage)?;
+        let payload = CefString::from(&args.get_string(2)).to_string();
+        Ok(Self {
+            name,
+            kind,
+            id: args.get_int(1),
+            payload: payload.into_bytes(),
+        })
+    }
+
+    fn send(&self, frame: &impl ImplFrame, target: ProcessId) -> Result<(), Error> {
+        let mut message = if self.payload.len() > SHARED_MEMORY_THRESHOLD {
+            self.to_shared_message()?
+        } else {
+            self.to_message()?
+        };
+        frame.send_process_message(target, Some(&mut message));
+        Ok(())
+    }
+
+    fn to_message(&self) -> Result<ProcessMessage, Error> {
+        let message = process_message_create(Some(&CefString::from(self.name.as_str())))
+            .ok_or(Error::CreateMessage)?;
+        let args = message.get_argument_list().ok_or(Error::CreateMessage)?;
+        // Payloads are always serialized with serde_json, so they are valid UTF-8.
+        let payload = std::str::from_utf8(&self.payload).map_err(|_| Error::InvalidMessage)?;
+        let stored = args.set_int(0, self.kind as i32)
+            && args.set_int(1, self.id)
+            && args.set_string(2, Some(&CefString::from(payload)));
+        if stored {
+            Ok(message)
+        } else {
+            Err(Error::CreateMessage)
+        }
+    }
+
+    fn to_shared_message(&self) -> Result<ProcessMessage, Error> {
+        let size = HEADER_SIZE + self.payload.len();
+        let builder =
+            shared_process_message_builder_create(Some(&CefString::from(self.name.as_str())), size)
+                .ok_or(Error::CreateMessage)?;
+        let memory = builder.memory().cast::<u8>();
+        if !builder.is_valid() || memory.is_null() {
+            return Err(Error::CreateMessage);
+        }
+
+        // The builder owns `size` bytes of writable memory until it is built.
+        let memory = unsafe { slice::from_raw_parts_mut(memory, size) };
+        memory[0] = self.kind as u8;
+        memory[1..HEADER_SIZE].copy_from_slice(&self.id.to_le_bytes());
+        memory[HEADER_SIZE..].copy_from_slice(&self.payload);
+
+        builder.build().ok_or(Error::CreateMessage)
+    }
+
+    /// Answer this request with `message` as an error, so the requester's callback is invoked
+    /// instead of waiting for a response that never arrives.
+    fn reply_error(&self, frame: &impl ImplFrame, target: ProcessId, message: impl Display) {
+        let result = serde_json::to_vec(&message.to_string())
+            .map_err(Error::from)
+            .and_then(|payload| {
+                Envelope {
+                    name: self.name.clone(),
+                    kind: Kind::Error,
+                    id: self.id,
+                    payload,
+                }
+                .send(frame, target)
+            });
+        if let Err(err) = result {
+            eprintln!("cannot respond to {} request: {err}", self.name);
+        }
+    }
+}
+
+impl Frame {
+    /// Serialize `message` and send it to the `target` process, where it is dispatched to the
+    /// handler registered with [Router::on].
+    pub fn send_typed<M: Message>(&self, target: ProcessId, message: &M) -> Result<(), Error> {
+        Envelope {
+            name: M::NAME.to_string(),
+            kind: Kind::Event,
+            id: 0,
+            payload: serde_json::to_vec(message)?,
+        }
+        .send(self, target)
+    }
+}
+
+type Handler = Arc<dyn Fn(&Frame, ProcessId, Envelope) + Send + Sync>;
+type Pending = Box<dyn FnOnce(Result<Vec<u8>, Error>) + Send>;
+
+fn browser_id(frame: &impl ImplFrame) -> c_int {
+    frame
+        .get_browser()
+        .map(|browser| browser.get_identifier())
+        .unwrap_or_default()
+}
+
+/// A request which is waiting for its response.
+struct PendingRequest {
+    name: &'static str,
+    callback: Pending,
+}
+
+/// Dispatches typed process messages to handlers registered by message name, and responses to
+/// the callbacks of pending requests.
+#[derive(Default)]
+pub struct Router {
+    handlers: Mutex<HashMap<&'static str, Handler>>,
+    pending: Mutex<pending::Pending<i32, PendingRequest>>,
+    next_id: AtomicI32,
+}
+
+impl Router {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Register the handler for messages of type `M` sent with [Frame::send_typed]. Replaces any
+    /// handler previously registered for the same name.
+    pub fn on<M: Message>(&self, handler: impl Fn(&Frame, M) + Send + Sync + 'static) -> &Self {
+        self.register(
+            M::NAME,
+            Arc::new(move |frame, source_process, envelope| {
+                if envelope.kind == Kind::Request {
+                    envelope.reply_error(frame, source_process, "not a request");
+                    return;
+                }
+                if envelope.kind != Kind::Event {
+                    return;
+                }
+                match serde_json::from_slice(&envelope.payload) {
+                    Ok(message) => handler(frame, message),
+                    Err(err) => eprintln!("invalid {} message: {err}", M::NAME),
+                }
+            }),
+        )
+    }
+
+    /// Register the handler for requests of type `R` sent with [Router::request]. The returned
+    /// response is sent back to the requesting process.
+    pub fn on_request<R: Request>(
+        &self,
+        handler: impl Fn(&Frame, R) -> R::Response + Send + Sync + 'static,
+    ) -> &Self {
+        self.register(
+            R::NAME,
+            Arc::new(move |frame, source_process, envelope| {
+                if envelope.kind != Kind::Request {
+                    return;
+                }
+                let payload = match serde_json::from_slice(&envelope.payload)
+                    .and_then(|request| serde_json::to_vec(&handler(frame, request)))
+                {
+                    Ok(payload) => payload,
+                    Err(err) => {
+                        envelope.reply_error(frame, source_process, Error::from(err));
+                        return;
+                    }
+                };
+                let result = Envelope {
+                    name: envelope.name,
+                    kind: Kind::Response,
+                    id: envelope.id,
+                    payload,
+                }
+                .send(frame, source_process);
+                if let Err(err) = result {
+                    eprintln!("cannot respond to {} request: {err}", R::NAME);
+                }
+            }),
+        )
+    }
+
+    fn register(&self, name: &'static str, handler: Handler) -> &Self {
+        lock(&self.handlers).insert(name, handler);
+        self
+    }
+
+    /// Send `request` to the `target` process. `callback` is invoked with the response once
+    /// [Router::dispatch] receives it in this process, with [Error::Remote] if the `target`
+    /// process could not handle it, or with [Error::Canceled] if the request is canceled first.
+    pub fn request<R: Request>(
+        &self,
+        frame: &impl ImplFrame,
+        target: ProcessId,
+        request: &R,
+        callback: impl FnOnce(Result<R::Response, Error>) + Send + 'static,
+    ) -> Result<(), Error> {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let envelope = Envelope {
+            name: R::NAME.to_string(),
+            kind: Kind::Request,
+            id,
+            payload: serde_json::to_vec(request)?,
+        };
+
+        lock(&self.pending).insert(
+            id,
+            Origin::new(browser_id(frame), frame),
+            PendingRequest {
+                name: R::NAME,
+                callback: Box::new(move |payload| {
+                    callback(payload.and_then(|payload| Ok(serde_json::from_slice(&payload)?)))
+                }),
+            },
+        );
+        envelope.send(frame, target).inspect_err(|_| {
+            lock(&self.pending).remove(&id);
+        })
+    }
+
+    /// Dispatch a received process message. Returns `true` if it was a typed message with a
+    /// registered handler, a request which was answered with an error because no handler is
+    /// registered for it, or a response to a pending request. Otherwise the message should be
+    /// handled elsewhere.
+    pub fn dispatch(
+        &self,
+        frame: Option<&mut impl ImplFrame>,
+        source_process: ProcessId,
+        message: Option<&mut impl ImplProcessMessage>,
+    ) -> bool {
+        let (Some(frame), Some(message)) = (frame, message) else {
+            return false;
+        };
+        let Ok(envelope) = Envelope::decode(message) else {
+            return false;
+        };
+
+        if matches!(envelope.kind, Kind::Response | Kind::Error) {
+            let pending = {
+                let mut pending = lock(&self.pending);
+                match pending.get(&envelope.id) {
+                    Some(request) if request.name == envelope.name => pending.remove(&envelope.id),
+                    _ => None,
+                }
+            };
+            let Some(request) = pending else {
+                return false;
+            };
+            let result = if envelope.kind == Kind::Error {
+                serde_json::from_slice(&envelope.payload)
+                    .map_err(Error::from)
+                    .and_then(|message| Err(Error::Remote(message)))
+            } else {
+                Ok(envelope.payload)
+            };
+            (request.callback)(result);
+            return true;
+        }
+
+        let handler = lock(&self.handlers).get(envelope.name.as_str()).cloned();
+        let Some(handler) = handler else {
+            if envelope.kind != Kind::Request {
+                return false;
+            }
+            envelope.reply_error(frame, source_process, "no handler registered");
+            return true;
+        };
+
+        let frame: Frame = unsafe {
+            frame.add_ref();
+            frame.get_raw().wrap_result()
+        };
+        handler(&frame, source_process, envelope);
+        true
+    }
+
+    /// Cancel the pending requests sent to `frame`, or to the whole browser if it is the main
+    /// frame.
+    pub fn cancel_frame(&self, frame: &impl ImplFrame) {
+        self.cancel(&Scope::frame(browser_id(frame), frame));
+    }
+
+    /// Cancel the pending requests sent to any frame of `browser`.
+    pub fn cancel_browser(&self, browser: &impl ImplBrowser) {
+        self.cancel(&Scope::browser(Some(browser)));
+    }
+
+    /// Cancel the pending requests of a frame which is navigating.
+    pub fn on_before_browse(&self, frame: Option<&mut impl ImplFrame>) {
+        if let Some(frame) = frame {
+            self.cancel_frame(frame);
+        }
+    }
+
+    /// Cancel the pending requests of a browser whose render process terminated.
+    pub fn on_render_process_terminated(&self, browser: Option<&mut impl ImplBrowser>) {
+        if let Some(browser) = browser {
+            self.cancel_browser(browser);
+        }
+    }
+
+    /// Cancel the pending requests of a browser which is closing.
+    pub fn on_before_close(&self, browser: Option<&mut impl ImplBrowser>) {
+        if let Some(browser) = browser {
+            self.cancel_browser(browser);
+        }
+    }
+
+    /// Remove the pending requests in `scope` and invoke their callbacks with
+    /// [Error::Canceled].
+    fn cancel(&self, scope: &Scope) {
+        let canceled = lock(&self.pending).cancel(scope);
+        for request in canceled {
+            (request.callback)(Err(Error::Canceled));
+        }
+    }
+}
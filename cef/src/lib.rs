@@ -1,15 +1,45 @@
 #![doc = include_str!("../README.md")]
 
 pub mod args;
+pub mod browser;
+pub mod devtools_bridge;
+pub mod error;
+pub mod future;
+pub mod intercept;
+pub mod message_router;
+pub mod osr;
+pub mod process;
 pub mod rc;
+pub mod runtime;
 pub mod sandbox_info;
+pub mod scheme;
+pub mod settings;
 pub mod string;
+pub mod task;
+pub mod v8;
+
+mod pending;
 
 #[cfg(target_os = "macos")]
 pub mod library_loader;
 
+#[cfg(feature = "serde")]
+pub mod cdp;
+#[cfg(feature = "serde")]
+pub mod ipc;
+#[cfg(feature = "serde")]
+pub mod value;
+
 #[rustfmt::skip]
 mod bindings;
 pub use bindings::*;
 
+pub use error::{Error, Result};
+pub use process::{run, ExecuteProcess};
+pub use runtime::{launch, launch_helper, Runtime};
+
 pub use cef_dll_sys as sys;
+pub use cef_macros::{include_dir, wrap};
+
+// Lets `#[wrap]`, which refers to `::cef`, be used inside this crate.
+extern crate self as cef;
@@ -1,4 +1,4 @@
-use crate::{load_library, unload_library};
+use crate::{load_library, unload_library, Error, Result};
 
 pub struct LibraryLoader {
     path: std::path::PathBuf,
@@ -24,8 +24,12 @@ impl LibraryLoader {
     }
 
     // See [cef_load_library] for more documentation.
-    pub fn load(&self) -> bool {
-        Self::load_library(&self.path)
+    pub fn load(&self) -> Result<()> {
+        if Self::load_library(&self.path) {
+            Ok(())
+        } else {
+            Err(Error::LoadLibrary(self.path.clone()))
+        }
     }
 
     fn load_library(name: &std::path::Path) -> bool {
@@ -33,13 +37,13 @@ impl LibraryLoader {
         let Ok(name) = std::ffi::CString::new(name.as_os_str().as_bytes()) else {
             return false;
         };
-        unsafe { load_library(Some(&*name.as_ptr().cast())) == 1 }
+        unsafe { load_library(Some(&*name.as_ptr().cast())) }
     }
 }
 
 impl Drop for LibraryLoader {
     fn drop(&mut self) {
-        if unload_library() != 1 {
+        if !unload_library() {
             eprintln!("cannot unload framework {}", self.path.display());
         }
     }
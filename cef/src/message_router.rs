@@ -0,0 +1,955 @@
+//! A port of the message router from CEF's C++ wrapper (`cef_message_router.h`), which routes
+//! asynchronous queries from JavaScript in the renderer process to Rust handlers in the browser
+//! process.
+//!
+//! [RendererSide] installs `window.cefQuery` and `window.cefQueryCancel` in every V8 context.
+//! A query is either sent with callbacks, which returns an ID for `cefQueryCancel`:
+//!
+//! ```js
+//! const id = window.cefQuery({
+//!     request: "get-pose",
+//!     persistent: false,
+//!     onSuccess: (response) => {},
+//!     onFailure: (errorCode, errorMessage) => {},
+//! });
+//! ```
+//!
+//! or without callbacks, which returns a `Promise` that is rejected with an `Error` on failure:
+//!
+//! ```js
+//! const response = await window.cefQuery("get-pose");
+//! ```
+//!
+//! [BrowserSide] forwards each query to the registered [Handler]s until one of them accepts it.
+//! Pending queries are canceled when the frame navigates, the V8 context is released or the
+//! browser closes, in which case [Handler::on_query_canceled] is called.
+//!
+//! ```no_run
+//! use cef::message_router::*;
+//!
+//! let router = BrowserSide::new(Config::default());
+//! router.add_handler(|query: &Query, callback: QueryCallback| {
+//!     if query.request() != "get-pose" {
+//!         return false;
+//!     }
+//!     callback.success("{\"x\":0,\"y\":0}");
+//!     true
+//! });
+//! ```
+//!
+//! The renderer side is driven from [ImplRenderProcessHandler::on_context_created],
+//! [ImplRenderProcessHandler::on_context_released] and
+//! [ImplRenderProcessHandler::on_process_message_received]. The browser side is driven from
+//! [ImplClient::on_process_message_received], [ImplRequestHandler::on_before_browse],
+//! [ImplRequestHandler::on_render_process_terminated] and [ImplLifeSpanHandler::on_before_close].
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use crate::{
+    pending::{Origin, Pending, Scope},
+    rc::{lock, ConvertReturnValue},
+    v8::{CallContext, FromV8, IntoV8},
+    *,
+};
+
+/// Error code passed to `onFailure` when no [Handler] accepted the query.
+pub const UNHANDLED_ERROR_CODE: i32 = -1;
+
+/// Names of the JavaScript functions, which must be the same in both processes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Config {
+    /// Name of the query function on the global object, `cefQuery` by default.
+    pub js_query_function: String,
+    /// Name of the cancel function on the global object, `cefQueryCancel` by default.
+    pub js_cancel_function: String,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            js_query_function: "cefQuery".to_string(),
+            js_cancel_function: "cefQueryCancel".to_string(),
+        }
+    }
+}
+
+impl Config {
+    fn query_message_name(&self) -> String {
+        format!("{}Msg", self.js_query_function)
+    }
+
+    fn cancel_message_name(&self) -> String {
+        format!("{}Msg", self.js_cancel_function)
+    }
+}
+
+/// Cancel all queries of a context instead of a single request.
+const ALL_REQUESTS: i32 = -1;
+
+/// A process message argument. The messages are encoded as lists of these, so the encoding
+/// doesn't need libcef.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Arg {
+    Int(i32),
+    Bool(bool),
+    String(String),
+}
+
+impl Arg {
+    fn as_int(&self) -> Option<i32> {
+        match self {
+            Self::Int(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    fn as_bool(&self) -> Option<bool> {
+        match self {
+            Self::Bool(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::String(value) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+/// Read the arguments of `message`, which must only hold ints, bools and strings.
+fn read_args(message: &impl ImplProcessMessage) -> Option<Vec<Arg>> {
+    let list = message.get_argument_list()?;
+    (0..list.get_size())
+        .map(|index| match list.get_type(index) {
+            ValueType::Int => Some(Arg::Int(list.get_int(index))),
+            ValueType::Bool => Some(Arg::Bool(list.get_bool(index))),
+            ValueType::String => Some(Arg::String(
+                CefString::from(&list.get_string(index)).to_string(),
+            )),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Send a message named `name` with `args` to the `target` process.
+fn send_message(frame: &impl ImplFrame, target: ProcessId, name: &str, args: &[Arg]) -> bool {
+    let Some(mut message) = process_message_create(Some(&CefString::from(name))) else {
+        return false;
+    };
+    let Some(list) = message.get_argument_list() else {
+        return false;
+    };
+    let stored = args.iter().enumerate().all(|(index, arg)| match arg {
+        Arg::Int(value) => list.set_int(index, *value),
+        Arg::Bool(value) => list.set_bool(index, *value),
+        Arg::String(value) => list.set_string(index, Some(&CefString::from(value.as_str()))),
+    });
+    if stored {
+        frame.send_process_message(target, Some(&mut message));
+    }
+    stored
+}
+
+/// A query sent from the renderer to the browser process.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct QueryMessage {
+    context_id: i32,
+    request_id: i32,
+    request: String,
+    persistent: bool,
+}
+
+impl QueryMessage {
+    fn encode(&self) -> Vec<Arg> {
+        vec![
+            Arg::Int(self.context_id),
+            Arg::Int(self.request_id),
+            Arg::String(self.request.clone()),
+            Arg::Bool(self.persistent),
+        ]
+    }
+
+    fn decode(args: &[Arg]) -> Option<Self> {
+        let [context_id, request_id, request, persistent, ..] = args else {
+            return None;
+        };
+        Some(Self {
+            context_id: context_id.as_int()?,
+            request_id: request_id.as_int()?,
+            request: request.as_str()?.to_string(),
+            persistent: persistent.as_bool()?,
+        })
+    }
+}
+
+/// The response to a query, sent from the browser to the renderer process with the same
+/// message name as the query. Failures carry an error code and message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ResponseMessage {
+    context_id: i32,
+    request_id: i32,
+    result: Result<String, (i32, String)>,
+}
+
+impl ResponseMessage {
+    fn encode(&self) -> Vec<Arg> {
+        let mut args = vec![Arg::Int(self.context_id), Arg::Int(self.request_id)];
+        match &self.result {
+            Ok(response) => args.extend([Arg::Bool(true), Arg::String(response.clone())]),
+            Err((error_code, error_message)) => args.extend([
+                Arg::Bool(false),
+                Arg::Int(*error_code),
+                Arg::String(error_message.clone()),
+            ]),
+        }
+        args
+    }
+
+    fn decode(args: &[Arg]) -> Option<Self> {
+        let [context_id, request_id, success, rest @ ..] = args else {
+            return None;
+        };
+        let result = match (success.as_bool()?, rest) {
+            (true, [response, ..]) => Ok(response.as_str()?.to_string()),
+            (false, [error_code, error_message, ..]) => {
+                Err((error_code.as_int()?, error_message.as_str()?.to_string()))
+            }
+            _ => return None,
+        };
+        Some(Self {
+            context_id: context_id.as_int()?,
+            request_id: request_id.as_int()?,
+            result,
+        })
+    }
+}
+
+/// Cancels a query, or all queries of a context with [ALL_REQUESTS], sent from the renderer to
+/// the browser process.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct CancelMessage {
+    context_id: i32,
+    request_id: i32,
+}
+
+impl CancelMessage {
+    fn encode(&self) -> Vec<Arg> {
+        vec![Arg::Int(self.context_id), Arg::Int(self.request_id)]
+    }
+
+    fn decode(args: &[Arg]) -> Option<Self> {
+        let [context_id, request_id, ..] = args else {
+            return None;
+        };
+        Some(Self {
+            context_id: context_id.as_int()?,
+            request_id: request_id.as_int()?,
+        })
+    }
+
+    /// Whether this cancels the query `request_id` of the context `context_id`.
+    fn matches(&self, context_id: i32, request_id: i32) -> bool {
+        self.context_id == context_id
+            && (self.request_id == ALL_REQUESTS || self.request_id == request_id)
+    }
+}
+
+/// A query which is waiting for its response in the renderer process.
+struct PendingQuery {
+    persistent: bool,
+    on_success: Option<V8Value>,
+    on_failure: Option<V8Value>,
+    promise: Option<V8Value>,
+}
+
+#[derive(Default)]
+struct RendererState {
+    next_context_id: i32,
+    next_request_id: i32,
+    contexts: HashMap<i32, V8Context>,
+    pending: HashMap<(i32, i32), PendingQuery>,
+}
+
+/// The renderer process half of the message router, see the [module](self) documentation.
+#[derive(Clone)]
+pub struct RendererSide {
+    config: Arc<Config>,
+    state: Arc<Mutex<RendererState>>,
+}
+
+impl RendererSide {
+    pub fn new(config: Config) -> Self {
+        Self {
+            config: Arc::new(config),
+            state: Default::default(),
+        }
+    }
+
+    /// Install the query and cancel functions in a new V8 context.
+    pub fn on_context_created(
+        &self,
+        context: Option<&mut impl ImplV8Context>,
+    ) -> Result<(), v8::Error> {
+        let Some(context) = context else {
+            return Ok(());
+        };
+        let context: V8Context = unsafe {
+            context.add_ref();
+            context.get_raw().wrap_result()
+        };
+
+        let context_id = {
+            let mut state = lock(&self.state);
+            state.next_context_id += 1;
+            let context_id = state.next_context_id;
+            state.contexts.insert(context_id, context.clone());
+            context_id
+        };
+
+        let router = self.clone();
+        v8::bind(
+            &context,
+            &self.config.js_query_function,
+            move |(query,): (V8Value,), call| router.query(context_id, &query, call),
+        )?;
+        let router = self.clone();
+        v8::bind(
+            &context,
+            &self.config.js_cancel_function,
+            move |(request_id,): (i32,), call| {
+                Ok::<_, String>(router.cancel(context_id, request_id, call))
+            },
+        )?;
+        Ok(())
+    }
+
+    /// Cancel the pending queries of a V8 context which is being released.
+    pub fn on_context_released(
+        &self,
+        frame: Option<&mut impl ImplFrame>,
+        context: Option<&mut impl ImplV8Context>,
+    ) {
+        let Some(context) = context else {
+            return;
+        };
+        let context_id = {
+            let mut state = lock(&self.state);
+            let Some(context_id) = state
+                .contexts
+                .iter()
+                .find(|(_, value)| context.is_same(Some(&mut (*value).clone())))
+                .map(|(context_id, _)| *context_id)
+            else {
+                return;
+            };
+            state.contexts.remove(&context_id);
+            state.pending.retain(|(id, _), _| *id != context_id);
+            context_id
+        };
+
+        if let Some(frame) = frame {
+            self.send_cancel(frame, context_id, ALL_REQUESTS);
+        }
+    }
+
+    /// Handle a response from the browser process. Returns `true` if the message was handled
+    /// by the router.
+    pub fn on_process_message_received(
+        &self,
+        source_process: ProcessId,
+        message: Option<&mut impl ImplProcessMessage>,
+    ) -> bool {
+        let Some(message) = message else {
+            return false;
+        };
+        if source_process != ProcessId::Browser
+            || CefString::from(&message.get_name()).to_string() != self.config.query_message_name()
+        {
+            return false;
+        }
+        let Some(ResponseMessage {
+            context_id,
+            request_id,
+            result,
+        }) = read_args(message).and_then(|args| ResponseMessage::decode(&args))
+        else {
+            return false;
+        };
+
+        let success = result.is_ok();
+        let (context, pending) = {
+            let mut state = lock(&self.state);
+            let Some(context) = state.contexts.get(&context_id).cloned() else {
+                return true;
+            };
+            let key = (context_id, request_id);
+            let pending = match state.pending.get(&key) {
+                Some(pending) if pending.persistent && success => PendingQuery {
+                    persistent: true,
+                    on_success: pending.on_success.clone(),
+                    on_failure: pending.on_failure.clone(),
+                    promise: None,
+                },
+                Some(_) => state.pending.remove(&key).unwrap(),
+                None => return true,
+            };
+            (context, pending)
+        };
+
+        if !context.enter() {
+            return true;
+        }
+        match result {
+            Ok(response) => {
+                if let Ok(mut response) = response.into_v8() {
+                    if let Some(on_success) = &pending.on_success {
+                        on_success.execute_function(None::<&mut V8Value>, Some(&[Some(response)]));
+                    } else if let Some(promise) = &pending.promise {
+                        promise.resolve_promise(Some(&mut response));
+                    }
+                }
+            }
+            Err((error_code, error_message)) => {
+                if let Some(on_failure) = &pending.on_failure {
+                    if let (Ok(error_code), Ok(error_message)) =
+                        (error_code.into_v8(), error_message.into_v8())
+                    {
+                        on_failure.execute_function(
+                            None::<&mut V8Value>,
+                            Some(&[Some(error_code), Some(error_message)]),
+                        );
+                    }
+                } else if let Some(promise) = &pending.promise {
+                    promise.reject_promise(Some(&CefString::from(error_message.as_str())));
+                }
+            }
+        }
+        context.exit();
+        true
+    }
+
+    fn query(
+        &self,
+        context_id: i32,
+        query: &V8Value,
+        call: &CallContext,
+    ) -> Result<V8Value, String> {
+        let (request, pending) = if query.is_string() {
+            (String::from_v8(query).map_err(|err| err.to_string())?, None)
+        } else if query.is_object() {
+            let get = |key: &str| query.get_value_bykey(Some(&CefString::from(key)));
+            let request = get("request")
+                .ok_or("missing request")
+                .and_then(|request| String::from_v8(&request).map_err(|_| "invalid request"))?;
+            let persistent = get("persistent")
+                .map(|persistent| Option::<bool>::from_v8(&persistent))
+                .transpose()
+                .map_err(|_| "invalid persistent")?
+                .flatten()
+                .unwrap_or_default();
+            let on_success = get("onSuccess").filter(|value| value.is_function());
+            let on_failure = get("onFailure").filter(|value| value.is_function());
+            let pending = (on_success.is_some() || on_failure.is_some()).then_some(PendingQuery {
+                persistent,
+                on_success,
+                on_failure,
+                promise: None,
+            });
+            if pending.is_none() && persistent {
+                return Err("persistent queries require callbacks".to_string());
+            }
+            (request, pending)
+        } else {
+            return Err("expected a request string or query object".to_string());
+        };
+
+        let (pending, promise) = match pending {
+            Some(pending) => (pending, None),
+            None => {
+                let promise = v8_value_create_promise().ok_or("cannot create promise")?;
+                let pending = PendingQuery {
+                    persistent: false,
+                    on_success: None,
+                    on_failure: None,
+                    promise: Some(promise.clone()),
+                };
+                (pending, Some(promise))
+            }
+        };
+        let persistent = pending.persistent;
+
+        let frame = call
+            .context()
+            .and_then(|context| context.get_frame())
+            .ok_or("cannot get frame")?;
+        let request_id = {
+            let mut state = lock(&self.state);
+            state.next_request_id += 1;
+            let request_id = state.next_request_id;
+            state.pending.insert((context_id, request_id), pending);
+            request_id
+        };
+
+        let query = QueryMessage {
+            context_id,
+            request_id,
+            request,
+            persistent,
+        };
+        if !send_message(
+            &frame,
+            ProcessId::Browser,
+            &self.config.query_message_name(),
+            &query.encode(),
+        ) {
+            lock(&self.state).pending.remove(&(context_id, request_id));
+            return Err("cannot send query".to_string());
+        }
+
+        match promise {
+            Some(promise) => Ok(promise),
+            None => request_id.into_v8().map_err(|err| err.to_string()),
+        }
+    }
+
+    fn cancel(&self, context_id: i32, request_id: i32, call: &CallContext) -> bool {
+        if lock(&self.state)
+            .pending
+            .remove(&(context_id, request_id))
+            .is_none()
+        {
+            return false;
+        }
+        if let Some(frame) = call.context().and_then(|context| context.get_frame()) {
+            self.send_cancel(&frame, context_id, request_id);
+        }
+        true
+    }
+
+    fn send_cancel(&self, frame: &impl ImplFrame, context_id: i32, request_id: i32) {
+        let cancel = CancelMessage {
+            context_id,
+            request_id,
+        };
+        send_message(
+            frame,
+            ProcessId::Browser,
+            &self.config.cancel_message_name(),
+            &cancel.encode(),
+        );
+    }
+}
+
+/// A query received by the browser process.
+pub struct Query {
+    id: i64,
+    browser: Browser,
+    frame: Frame,
+    request: String,
+    persistent: bool,
+}
+
+impl Query {
+    /// Unique ID of this query in the browser process.
+    pub fn id(&self) -> i64 {
+        self.id
+    }
+
+    /// The browser which sent the query.
+    pub fn browser(&self) -> &Browser {
+        &self.browser
+    }
+
+    /// The frame which sent the query.
+    pub fn frame(&self) -> &Frame {
+        &self.frame
+    }
+
+    /// The `request` string passed to the query function.
+    pub fn request(&self) -> &str {
+        &self.request
+    }
+
+    /// Persistent queries can receive any number of successful responses, until they fail or
+    /// are canceled.
+    pub fn persistent(&self) -> bool {
+        self.persistent
+    }
+}
+
+/// Handles queries in the browser process. Implemented for closures which only handle
+/// [Handler::on_query].
+pub trait Handler: Send + Sync {
+    /// Return `true` to accept the query and answer it through `callback`, either immediately
+    /// or later from any thread. Return `false` to pass it on to the next handler.
+    fn on_query(&self, query: &Query, callback: QueryCallback) -> bool;
+
+    /// Called when an accepted query is canceled before it was completed.
+    fn on_query_canceled(&self, query: &Query) {
+        let _ = query;
+    }
+}
+
+impl<F> Handler for F
+where
+    F: Fn(&Query, QueryCallback) -> bool + Send + Sync,
+{
+    fn on_query(&self, query: &Query, callback: QueryCallback) -> bool {
+        self(query, callback)
+    }
+}
+
+struct PendingRequest {
+    query: Arc<Query>,
+    context_id: i32,
+    request_id: i32,
+    handler: Option<Arc<dyn Handler>>,
+}
+
+#[derive(Default)]
+struct BrowserState {
+    next_query_id: i64,
+    handlers: Vec<Arc<dyn Handler>>,
+    pending: Pending<i64, PendingRequest>,
+}
+
+/// Sends the response of a [Query] to the renderer process. Responses to queries which were
+/// already completed or canceled are ignored.
+#[derive(Clone)]
+pub struct QueryCallback {
+    config: Arc<Config>,
+    state: Arc<Mutex<BrowserState>>,
+    query_id: i64,
+}
+
+impl QueryCallback {
+    /// Send a successful response. Completes the query unless it is persistent.
+    pub fn success(&self, response: &str) {
+        let request = {
+            let mut state = lock(&self.state);
+            match state.pending.get(&self.query_id) {
+                Some(request) if request.query.persistent => Some((
+                    request.query.clone(),
+                    request.context_id,
+                    request.request_id,
+                )),
+                Some(_) => state
+                    .pending
+                    .remove(&self.query_id)
+                    .map(|request| (request.query, request.context_id, request.request_id)),
+                None => None,
+            }
+        };
+        let Some((query, context_id, request_id)) = request else {
+            return;
+        };
+        let response = ResponseMessage {
+            context_id,
+            request_id,
+            result: Ok(response.to_string()),
+        };
+        send_message(
+            &query.frame,
+            ProcessId::Renderer,
+            &self.config.query_message_name(),
+            &response.encode(),
+        );
+    }
+
+    /// Send a failure response, which always completes the query.
+    pub fn failure(&self, error_code: i32, error_message: &str) {
+        let request = lock(&self.state).pending.remove(&self.query_id);
+        if let Some(request) = request {
+            send_failure(
+                &self.config,
+                &request.query.frame,
+                request.context_id,
+                request.request_id,
+                error_code,
+                error_message,
+            );
+        }
+    }
+}
+
+fn send_failure(
+    config: &Config,
+    frame: &impl ImplFrame,
+    context_id: i32,
+    request_id: i32,
+    error_code: i32,
+    error_message: &str,
+) {
+    let response = ResponseMessage {
+        context_id,
+        request_id,
+        result: Err((error_code, error_message.to_string())),
+    };
+    send_message(
+        frame,
+        ProcessId::Renderer,
+        &config.query_message_name(),
+        &response.encode(),
+    );
+}
+
+/// The browser process half of the message router, see the [module](self) documentation.
+#[derive(Clone)]
+pub struct BrowserSide {
+    config: Arc<Config>,
+    state: Arc<Mutex<BrowserState>>,
+}
+
+impl BrowserSide {
+    pub fn new(config: Config) -> Self {
+        Self {
+            config: Arc::new(config),
+            state: Default::default(),
+        }
+    }
+
+    /// Add a handler, handlers are asked to accept queries in the order they were added.
+    pub fn add_handler(&self, handler: impl Handler + 'static) -> &Self {
+        lock(&self.state).handlers.push(Arc::new(handler));
+        self
+    }
+
+    /// The number of queries which are waiting for a response.
+    pub fn pending_query_count(&self) -> usize {
+        lock(&self.state).pending.len()
+    }
+
+    /// Handle a query or cancel message from the renderer process. Returns `true` if the
+    /// message was handled by the router.
+    pub fn on_process_message_received(
+        &self,
+        browser: Option<&mut impl ImplBrowser>,
+        frame: Option<&mut impl ImplFrame>,
+        source_process: ProcessId,
+        message: Option<&mut impl ImplProcessMessage>,
+    ) -> bool {
+        let (Some(browser), Some(frame), Some(message)) = (browser, frame, message) else {
+            return false;
+        };
+        if source_process != ProcessId::Renderer {
+            return false;
+        }
+        let name = CefString::from(&message.get_name()).to_string();
+        let Some(args) = read_args(message) else {
+            return false;
+        };
+
+        if name == self.config.cancel_message_name() {
+            let Some(cancel) = CancelMessage::decode(&args) else {
+                return false;
+            };
+            let browser_id = browser.get_identifier();
+            let canceled = lock(&self.state).pending.remove_where(|origin, request| {
+                origin.browser_id == browser_id
+                    && cancel.matches(request.context_id, request.request_id)
+            });
+            Self::notify_canceled(canceled);
+            return true;
+        }
+        let Some(QueryMessage {
+            context_id,
+            request_id,
+            request,
+            persistent,
+        }) = (name == self.config.query_message_name())
+            .then(|| QueryMessage::decode(&args))
+            .flatten()
+        else {
+            return false;
+        };
+
+        let (browser, frame): (Browser, Frame) = unsafe {
+            browser.add_ref();
+            frame.add_ref();
+            (
+                browser.get_raw().wrap_result(),
+                frame.get_raw().wrap_result(),
+            )
+        };
+        let (query_id, handlers) = {
+            let mut state = lock(&self.state);
+            state.next_query_id += 1;
+            (state.next_query_id, state.handlers.clone())
+        };
+        let query = Arc::new(Query {
+            id: query_id,
+            browser,
+            frame,
+            request,
+            persistent,
+        });
+        lock(&self.state).pending.insert(
+            query_id,
+            Origin::new(query.browser.get_identifier(), &query.frame),
+            PendingRequest {
+                query: query.clone(),
+                context_id,
+                request_id,
+                handler: None,
+            },
+        );
+
+        let callback = QueryCallback {
+            config: self.config.clone(),
+            state: self.state.clone(),
+            query_id,
+        };
+        for handler in handlers {
+            // Set the handler first, so a cancellation from inside on_query reaches it.
+            if let Some(request) = lock(&self.state).pending.get_mut(&query_id) {
+                request.handler = Some(handler.clone());
+            }
+            if handler.on_query(&query, callback.clone()) {
+                return true;
+            }
+        }
+
+        if lock(&self.state).pending.remove(&query_id).is_some() {
+            send_failure(
+                &self.config,
+                &query.frame,
+                context_id,
+                request_id,
+                UNHANDLED_ERROR_CODE,
+                "Unhandled request",
+            );
+        }
+        true
+    }
+
+    /// Cancel the pending queries of `frame`, or of the whole browser if it is the main frame.
+    pub fn on_before_browse(
+        &self,
+        browser: Option<&mut impl ImplBrowser>,
+        frame: Option<&mut impl ImplFrame>,
+    ) {
+        let (Some(browser), Some(frame)) = (browser, frame) else {
+            return;
+        };
+        self.cancel(&Scope::frame(browser.get_identifier(), frame));
+    }
+
+    /// Cancel the pending queries of a browser whose render process terminated.
+    pub fn on_render_process_terminated(&self, browser: Option<&mut impl ImplBrowser>) {
+        self.cancel_pending(browser);
+    }
+
+    /// Cancel the pending queries of a browser which is closing.
+    pub fn on_before_close(&self, browser: Option<&mut impl ImplBrowser>) {
+        self.cancel_pending(browser);
+    }
+
+    /// Cancel the pending queries of `browser`, or of all browsers if it's `None`.
+    pub fn cancel_pending(&self, browser: Option<&mut impl ImplBrowser>) {
+        self.cancel(&Scope::browser(browser.as_deref()));
+    }
+
+    fn cancel(&self, scope: &Scope) {
+        let canceled = lock(&self.state).pending.cancel(scope);
+        Self::notify_canceled(canceled);
+    }
+
+    fn notify_canceled(canceled: Vec<PendingRequest>) {
+        for request in canceled {
+            if let Some(handler) = request.handler {
+                handler.on_query_canceled(&request.query);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn message_names() {
+        let config = Config {
+            js_query_function: "petQuery".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(config.query_message_name(), "petQueryMsg");
+        assert_eq!(config.cancel_message_name(), "cefQueryCancelMsg");
+    }
+
+    #[test]
+    fn encodes_queries() {
+        let query = QueryMessage {
+            context_id: 1,
+            request_id: 2,
+            request: "get-pose".to_string(),
+            persistent: true,
+        };
+        let args = query.encode();
+        assert_eq!(
+            args,
+            [
+                Arg::Int(1),
+                Arg::Int(2),
+                Arg::String("get-pose".to_string()),
+                Arg::Bool(true)
+            ]
+        );
+        assert_eq!(QueryMessage::decode(&args), Some(query));
+        assert_eq!(QueryMessage::decode(&args[..3]), None);
+        assert_eq!(
+            QueryMessage::decode(&[Arg::Int(1), Arg::Int(2), Arg::Int(3), Arg::Bool(true)]),
+            None
+        );
+    }
+
+    #[test]
+    fn encodes_responses() {
+        let success = ResponseMessage {
+            context_id: 1,
+            request_id: 2,
+            result: Ok("{}".to_string()),
+        };
+        assert_eq!(ResponseMessage::decode(&success.encode()), Some(success));
+
+        let failure = ResponseMessage {
+            context_id: 1,
+            request_id: 2,
+            result: Err((UNHANDLED_ERROR_CODE, "Unhandled request".to_string())),
+        };
+        let args = failure.encode();
+        assert_eq!(args.len(), 5);
+        assert_eq!(ResponseMessage::decode(&args), Some(failure));
+
+        // A failure without its error message.
+        assert_eq!(ResponseMessage::decode(&args[..4]), None);
+    }
+
+    #[test]
+    fn cancels_queries() {
+        let cancel = CancelMessage {
+            context_id: 1,
+            request_id: 2,
+        };
+        assert_eq!(
+            CancelMessage::decode(&cancel.encode()),
+            Some(cancel.clone())
+        );
+        assert!(cancel.matches(1, 2));
+        assert!(!cancel.matches(1, 3));
+        assert!(!cancel.matches(2, 2));
+
+        let all = CancelMessage {
+            context_id: 1,
+            request_id: ALL_REQUESTS,
+        };
+        assert!(all.matches(1, 2));
+        assert!(all.matches(1, 3));
+        assert!(!all.matches(2, 2));
+    }
+}
@@ -0,0 +1,202 @@
+//! Dispatch the [InputEvent]s sent by a frame stream consumer to a windowless browser.
+//!
+//! Consumers send coordinates in pixels of the streamed frames. [InputDispatcher] scales them to
+//! the view coordinates CEF expects, using the size of the last painted frame and the rect
+//! returned by [ImplRenderHandler::get_view_rect](crate::ImplRenderHandler::get_view_rect).
+//!
+//! ```no_run
+//! use cef::{
+//!     osr::{input::InputDispatcher, stream::FrameStream},
+//!     *,
+//! };
+//! use std::sync::Arc;
+//!
+//! fn forward_input(stream: &FrameStream, browser: &impl ImplBrowser) -> std::io::Result<()> {
+//!     let dispatcher = Arc::new(InputDispatcher::new(browser)?);
+//!     dispatcher.set_view_size(1200, 600);
+//!     dispatcher.set_frame_size(2400, 1200);
+//!     stream.set_input_handler(move |event| dispatcher.dispatch(event));
+//!     Ok(())
+//! }
+//! ```
+
+use std::{
+    io,
+    sync::{Mutex, MutexGuard},
+};
+
+pub use cef_osr_stream::input::*;
+
+use crate::{rc::lock, *};
+
+/// The sizes which map frame pixels to view coordinates.
+#[derive(Debug, Clone, Copy)]
+struct Scale {
+    view_width: i32,
+    view_height: i32,
+    frame_width: u32,
+    frame_height: u32,
+}
+
+impl Scale {
+    fn apply(&self, x: f32, y: f32) -> (f32, f32) {
+        let factor = |view: i32, frame: u32| {
+            if view > 0 && frame > 0 {
+                view as f32 / frame as f32
+            } else {
+                1.0
+            }
+        };
+        (
+            x * factor(self.view_width, self.frame_width),
+            y * factor(self.view_height, self.frame_height),
+        )
+    }
+}
+
+/// Sends input events to a browser on the UI thread.
+pub struct InputDispatcher {
+    host: BrowserHost,
+    scale: Mutex<Scale>,
+}
+
+impl InputDispatcher {
+    pub fn new(browser: &impl ImplBrowser) -> io::Result<Self> {
+        let host = browser
+            .get_host()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::Unsupported, "browser has no host"))?;
+        Ok(Self {
+            host,
+            scale: Mutex::new(Scale {
+                view_width: 0,
+                view_height: 0,
+                frame_width: 0,
+                frame_height: 0,
+            }),
+        })
+    }
+
+    fn scale(&self) -> MutexGuard<'_, Scale> {
+        lock(&self.scale)
+    }
+
+    /// Set the view size returned by
+    /// [ImplRenderHandler::get_view_rect](crate::ImplRenderHandler::get_view_rect).
+    pub fn set_view_size(&self, width: i32, height: i32) {
+        let mut scale = self.scale();
+        scale.view_width = width;
+        scale.view_height = height;
+    }
+
+    /// Set the size of the frames seen by the consumer, usually the size passed to
+    /// [ImplRenderHandler::on_paint](crate::ImplRenderHandler::on_paint). Coordinates aren't
+    /// scaled until both sizes are known.
+    pub fn set_frame_size(&self, width: u32, height: u32) {
+        let mut scale = self.scale();
+        scale.frame_width = width;
+        scale.frame_height = height;
+    }
+
+    /// Scale the coordinates of `event` to the view and send it to the browser. Can be called
+    /// on any thread, the event is posted to the UI thread.
+    pub fn dispatch(&self, event: InputEvent) {
+        let scale = *self.scale();
+        let event = event.map_position(|x, y| scale.apply(x, y));
+        if currently_on(ThreadId::Ui) {
+            send_input(&self.host, event);
+        } else {
+            let host = self.host.clone();
+            task::post(ThreadId::Ui, move || send_input(&host, event));
+        }
+    }
+}
+
+fn mouse_event(x: f32, y: f32, modifiers: u32) -> MouseEvent {
+    MouseEvent {
+        x: x.round() as i32,
+        y: y.round() as i32,
+        modifiers,
+    }
+}
+
+fn send_input(host: &BrowserHost, event: InputEvent) {
+    match event {
+        InputEvent::MouseMove {
+            x,
+            y,
+            modifiers,
+            leave,
+        } => host.send_mouse_move_event(Some(&mouse_event(x, y, modifiers)), leave),
+        InputEvent::MouseButton {
+            x,
+            y,
+            modifiers,
+            button,
+            up,
+            click_count,
+        } => {
+            let button = match button {
+                MouseButton::Left => MouseButtonType::Left,
+                MouseButton::Middle => MouseButtonType::Middle,
+                MouseButton::Right => MouseButtonType::Right,
+            };
+            host.send_mouse_click_event(
+                Some(&mouse_event(x, y, modifiers)),
+                button,
+                up,
+                click_count.into(),
+            );
+        }
+        InputEvent::MouseWheel {
+            x,
+            y,
+            modifiers,
+            delta_x,
+            delta_y,
+        } => host.send_mouse_wheel_event(Some(&mouse_event(x, y, modifiers)), delta_x, delta_y),
+        InputEvent::Key(key) => {
+            let event = KeyEvent {
+                type_: match key.kind {
+                    KeyEventKind::RawKeyDown => KeyEventType::Rawkeydown,
+                    KeyEventKind::KeyDown => KeyEventType::Keydown,
+                    KeyEventKind::KeyUp => KeyEventType::Keyup,
+                    KeyEventKind::Char => KeyEventType::Char,
+                },
+                modifiers: key.modifiers,
+                windows_key_code: key.windows_key_code,
+                native_key_code: key.native_key_code,
+                is_system_key: key.is_system_key.into(),
+                character: key.character,
+                unmodified_character: key.unmodified_character,
+                ..Default::default()
+            };
+            host.send_key_event(Some(&event));
+        }
+        InputEvent::Touch(touch) => {
+            let event = TouchEvent {
+                id: touch.id,
+                x: touch.x,
+                y: touch.y,
+                radius_x: touch.radius_x,
+                radius_y: touch.radius_y,
+                rotation_angle: touch.rotation_angle,
+                pressure: touch.pressure,
+                type_: match touch.kind {
+                    TouchEventKind::Released => TouchEventType::Released,
+                    TouchEventKind::Pressed => TouchEventType::Pressed,
+                    TouchEventKind::Moved => TouchEventType::Moved,
+                    TouchEventKind::Cancelled => TouchEventType::Cancelled,
+                },
+                modifiers: touch.modifiers,
+                pointer_type: match touch.pointer {
+                    PointerKind::Touch => PointerType::TypeTouch,
+                    PointerKind::Mouse => PointerType::TypeMouse,
+                    PointerKind::Pen => PointerType::TypePen,
+                    PointerKind::Eraser => PointerType::TypeEraser,
+                    PointerKind::Unknown => PointerType::TypeUnknown,
+                },
+            };
+            host.send_touch_event(Some(&event));
+        }
+    }
+}
@@ -0,0 +1,6 @@
+//! Helpers for windowless (offscreen) rendering, where CEF paints into a buffer passed to
+//! [ImplRenderHandler::on_paint](crate::ImplRenderHandler::on_paint) instead of a native window.
+
+pub mod input;
+pub mod stream;
+pub mod view;
@@ -0,0 +1,343 @@
+//! Stream painted frames to a consumer in another process.
+//!
+//! The wire protocol, the consumer side [Reader] and the [Transport]s are implemented in the
+//! `cef-osr-stream` crate, which is re-exported here. Consumers can depend on that crate
+//! directly, without linking CEF.
+//!
+//! [FrameStream] accepts consumers on a background thread and sends them the frames passed to
+//! [FrameStream::on_paint]. Painting never blocks on the consumer: if it falls behind, frames
+//! which haven't been sent yet are replaced by newer ones. Only the dirty regions of a frame are
+//! sent where the transport supports it, with a full keyframe every
+//! [keyframe interval](FrameStream::set_keyframe_interval).
+//!
+//! ```no_run
+//! use cef::{
+//!     osr::stream::{Endpoint, FrameStream},
+//!     *,
+//! };
+//! use std::sync::Arc;
+//!
+//! #[wrap(RenderHandler)]
+//! struct StreamingRenderHandler {
+//!     stream: Arc<FrameStream>,
+//! }
+//!
+//! #[wrap]
+//! impl ImplRenderHandler for StreamingRenderHandler {
+//!     fn on_paint(
+//!         &self,
+//!         _browser: Option<&mut impl ImplBrowser>,
+//!         type_: PaintElementType,
+//!         dirty_rects: Option<&[Rect]>,
+//!         buffer: Option<&[u8]>,
+//!         width: ::std::os::raw::c_int,
+//!         height: ::std::os::raw::c_int,
+//!     ) {
+//!         self.stream.on_paint(type_, dirty_rects, buffer, width, height);
+//!     }
+//! }
+//!
+//! let endpoint: Endpoint = "tcp:127.0.0.1:7878".parse()?;
+//! let stream = Arc::new(FrameStream::new(endpoint.listen()?)?);
+//! let _handler = StreamingRenderHandler::new(stream);
+//! # Ok::<(), std::io::Error>(())
+//! ```
+
+use std::{
+    io,
+    os::raw::c_int,
+    sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError},
+    thread,
+    time::Duration,
+};
+
+pub use cef_osr_stream::*;
+
+use crate::{rc::lock, PaintElementType};
+
+/// How long to wait before accepting again after the transport failed.
+const ACCEPT_RETRY_DELAY: Duration = Duration::from_secs(1);
+
+/// Send a full frame at least this often by default.
+const DEFAULT_KEYFRAME_INTERVAL: u32 = 60;
+
+type InputHandler = Arc<dyn Fn(InputEvent) + Send + Sync>;
+
+/// A frame waiting to be sent, with its own copy of the pixels.
+struct PendingFrame {
+    width: u32,
+    height: u32,
+    stride: u32,
+    format: PixelFormat,
+    data: Vec<u8>,
+    /// The regions which changed since the last frame sent, or `None` if all of it did.
+    dirty: Option<Vec<Rect>>,
+}
+
+impl PendingFrame {
+    fn as_frame(&self) -> Frame<'_> {
+        Frame {
+            width: self.width,
+            height: self.height,
+            stride: self.stride,
+            format: self.format,
+            data: &self.data,
+        }
+    }
+}
+
+struct State {
+    pending: Option<PendingFrame>,
+    /// The buffer of the last frame sent, reused for the next one.
+    spare: Vec<u8>,
+    keyframe_interval: u32,
+    codec: Codec,
+    input_handler: Option<InputHandler>,
+    connected: bool,
+    closed: bool,
+}
+
+struct Shared {
+    state: Mutex<State>,
+    ready: Condvar,
+}
+
+impl Shared {
+    fn lock(&self) -> MutexGuard<'_, State> {
+        lock(&self.state)
+    }
+
+    fn run(self: Arc<Self>, mut transport: Box<dyn Transport>) {
+        while !self.lock().closed {
+            let mut sink = match transport.accept() {
+                Ok(sink) => sink,
+                Err(err) => {
+                    eprintln!("cannot accept frame stream consumer: {err}");
+                    thread::sleep(ACCEPT_RETRY_DELAY);
+                    continue;
+                }
+            };
+            self.lock().connected = true;
+            if let Some(input) = sink.take_input() {
+                let shared = self.clone();
+                let spawned = thread::Builder::new()
+                    .name("osr-frame-input".into())
+                    .spawn(move || shared.receive_input(input));
+                if let Err(err) = spawned {
+                    eprintln!("cannot receive frame stream input: {err}");
+                }
+            }
+
+            // Frames sent since the last keyframe.
+            let mut since_keyframe: u32 = 0;
+            while let Some(frame) = self.next_frame() {
+                let (keyframe_interval, codec) = {
+                    let state = self.lock();
+                    (state.keyframe_interval, state.codec)
+                };
+                sink.set_codec(codec);
+                let result = match &frame.dirty {
+                    Some(dirty) if since_keyframe.saturating_add(1) < keyframe_interval => {
+                        since_keyframe += 1;
+                        sink.send_dirty(&frame.as_frame(), dirty)
+                    }
+                    _ => {
+                        since_keyframe = 0;
+                        sink.send(&frame.as_frame())
+                    }
+                };
+                let mut state = self.lock();
+                state.spare = frame.data;
+                match result {
+                    Ok(()) => {}
+                    Err(err) if err.kind() == io::ErrorKind::InvalidInput => {
+                        eprintln!("cannot send frame to the frame stream consumer: {err}");
+                        // The consumer missed the changes of this frame.
+                        since_keyframe = u32::MAX;
+                    }
+                    Err(err) => {
+                        eprintln!("frame stream consumer disconnected: {err}");
+                        state.connected = false;
+                        state.pending = None;
+                        break;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Pass the consumer's input to the input handler until it disconnects.
+    fn receive_input(&self, mut input: Box<dyn InputReceiver>) {
+        while let Ok(event) = input.recv() {
+            let state = self.lock();
+            if state.closed {
+                break;
+            }
+            let handler = state.input_handler.clone();
+            drop(state);
+            if let Some(handler) = handler {
+                handler(event);
+            }
+        }
+    }
+
+    /// Wait for the next frame, or return `None` once the stream is closed.
+    fn next_frame(&self) -> Option<PendingFrame> {
+        let mut state = self.lock();
+        loop {
+            if state.closed {
+                return None;
+            }
+            if let Some(frame) = state.pending.take() {
+                return Some(frame);
+            }
+            state = self
+                .ready
+                .wait(state)
+                .unwrap_or_else(PoisonError::into_inner);
+        }
+    }
+}
+
+/// Sends frames to the consumers accepted by a [Transport], one at a time.
+pub struct FrameStream {
+    shared: Arc<Shared>,
+}
+
+impl FrameStream {
+    /// Start accepting consumers on a background thread. The thread keeps waiting for the next
+    /// consumer while the stream is alive; it may stay blocked in [Transport::accept] after the
+    /// stream is dropped.
+    pub fn new(transport: Box<dyn Transport>) -> io::Result<Self> {
+        let shared = Arc::new(Shared {
+            state: Mutex::new(State {
+                pending: None,
+                spare: Vec::new(),
+                keyframe_interval: DEFAULT_KEYFRAME_INTERVAL,
+                codec: Codec::None,
+                input_handler: None,
+                connected: false,
+                closed: false,
+            }),
+            ready: Condvar::new(),
+        });
+        let thread_shared = shared.clone();
+        thread::Builder::new()
+            .name("osr-frame-stream".into())
+            .spawn(move || thread_shared.run(transport))?;
+        Ok(Self { shared })
+    }
+
+    /// Whether a consumer is connected. Frames sent while no consumer is connected are
+    /// dropped.
+    pub fn is_connected(&self) -> bool {
+        self.shared.lock().connected
+    }
+
+    /// Send a full frame at least every `interval` frames, so a consumer recovers from dirty
+    /// regions which were reported incorrectly. Defaults to 60; 0 or 1 only sends full frames.
+    pub fn set_keyframe_interval(&self, interval: u32) {
+        self.shared.lock().keyframe_interval = interval;
+    }
+
+    /// Compress frames with `codec` for consumers which support it, see [Codec]. Frames are
+    /// sent uncompressed by default.
+    pub fn set_codec(&self, codec: Codec) {
+        self.shared.lock().codec = codec;
+    }
+
+    /// Call `handler` with the input events sent by consumers, on a thread of the stream. See
+    /// [InputDispatcher](crate::osr::input::InputDispatcher) for sending them to a browser.
+    /// Transports which can't carry input, like shared memory, never call it.
+    pub fn set_input_handler(&self, handler: impl Fn(InputEvent) + Send + Sync + 'static) {
+        self.shared.lock().input_handler = Some(Arc::new(handler));
+    }
+
+    /// Queue a copy of `frame` for the connected consumer, replacing the frame which is still
+    /// waiting to be sent, if any.
+    pub fn send(&self, frame: &Frame) -> io::Result<()> {
+        self.queue(frame, None)
+    }
+
+    /// Like [FrameStream::send], for a frame of which only the `dirty` regions changed since
+    /// the previous one.
+    pub fn send_dirty(&self, frame: &Frame, dirty: &[Rect]) -> io::Result<()> {
+        self.queue(frame, Some(dirty))
+    }
+
+    fn queue(&self, frame: &Frame, dirty: Option<&[Rect]>) -> io::Result<()> {
+        frame.validate()?;
+        let mut state = self.shared.lock();
+        if !state.connected {
+            return Ok(());
+        }
+
+        // A replaced frame was never sent, so its changes have to be sent with this one.
+        let (mut data, replaced) = match state.pending.take() {
+            Some(pending) => (pending.data, pending.dirty),
+            None => (std::mem::take(&mut state.spare), Some(Vec::new())),
+        };
+        let dirty = delta::merge_dirty(replaced, dirty);
+        data.clear();
+        data.extend_from_slice(frame.data);
+        state.pending = Some(PendingFrame {
+            width: frame.width,
+            height: frame.height,
+            stride: frame.stride,
+            format: frame.format,
+            data,
+            dirty,
+        });
+        self.shared.ready.notify_one();
+        Ok(())
+    }
+
+    /// Send the BGRA buffer passed to
+    /// [ImplRenderHandler::on_paint](crate::ImplRenderHandler::on_paint). Only the
+    /// `dirty_rects` are sent, or the whole frame if there are none. Popup paints are ignored.
+    pub fn on_paint(
+        &self,
+        type_: PaintElementType,
+        dirty_rects: Option<&[crate::Rect]>,
+        buffer: Option<&[u8]>,
+        width: c_int,
+        height: c_int,
+    ) {
+        let (Some(buffer), Ok(width), Ok(height)) =
+            (buffer, u32::try_from(width), u32::try_from(height))
+        else {
+            return;
+        };
+        if type_ != PaintElementType::View {
+            return;
+        }
+
+        let frame = Frame::new(width, height, PixelFormat::Bgra8, buffer);
+        let result = match dirty_rects {
+            Some(dirty_rects) if !dirty_rects.is_empty() => {
+                let dirty: Vec<_> = dirty_rects.iter().map(stream_rect).collect();
+                self.send_dirty(&frame, &dirty)
+            }
+            _ => self.send(&frame),
+        };
+        if let Err(err) = result {
+            eprintln!("cannot send frame: {err}");
+        }
+    }
+}
+
+/// Convert a CEF rect to a stream [Rect], dropping the parts left of or above the origin.
+pub fn stream_rect(rect: &crate::Rect) -> Rect {
+    let x = rect.x.max(0);
+    let y = rect.y.max(0);
+    let right = rect.x.saturating_add(rect.width).max(x);
+    let bottom = rect.y.saturating_add(rect.height).max(y);
+    Rect::new(x as u32, y as u32, (right - x) as u32, (bottom - y) as u32)
+}
+
+impl Drop for FrameStream {
+    fn drop(&mut self) {
+        self.shared.lock().closed = true;
+        self.shared.ready.notify_all();
+    }
+}
@@ -0,0 +1,193 @@
+//! The size and device scale factor of a windowless browser, which can change at runtime.
+//!
+//! CEF asks the [RenderHandler] for the view size and screen info. [OsrView] answers from its
+//! current state, and tells the browser to ask again after [OsrView::resize] and
+//! [OsrView::set_scale].
+//!
+//! ```no_run
+//! use cef::{osr::view::OsrView, *};
+//! use std::{os::raw::c_int, sync::Arc};
+//!
+//! #[wrap(RenderHandler)]
+//! struct ViewRenderHandler {
+//!     view: Arc<OsrView>,
+//! }
+//!
+//! #[wrap]
+//! impl ImplRenderHandler for ViewRenderHandler {
+//!     fn get_view_rect(&self, _browser: Option<&mut impl ImplBrowser>, rect: Option<&mut Rect>) {
+//!         self.view.get_view_rect(rect);
+//!     }
+//!
+//!     fn get_screen_info(
+//!         &self,
+//!         _browser: Option<&mut impl ImplBrowser>,
+//!         screen_info: Option<&mut ScreenInfo>,
+//!     ) -> bool {
+//!         self.view.get_screen_info(screen_info)
+//!     }
+//!
+//!     fn get_screen_point(
+//!         &self,
+//!         _browser: Option<&mut impl ImplBrowser>,
+//!         view_x: c_int,
+//!         view_y: c_int,
+//!         screen_x: Option<&mut c_int>,
+//!         screen_y: Option<&mut c_int>,
+//!     ) -> bool {
+//!         self.view.get_screen_point(view_x, view_y, screen_x, screen_y)
+//!     }
+//! }
+//!
+//! let view = Arc::new(OsrView::new(1280, 720, 2.0));
+//! let _handler = ViewRenderHandler::new(view.clone());
+//! // Once the browser is created:
+//! // view.set_browser(&browser);
+//! // view.resize(1920, 1080);
+//! ```
+
+use std::{os::raw::c_int, sync::Mutex};
+
+use crate::{rc::lock, *};
+
+#[derive(Debug, Clone, Copy)]
+struct State {
+    width: c_int,
+    height: c_int,
+    scale: f32,
+}
+
+/// The size and device scale factor of a windowless browser.
+pub struct OsrView {
+    state: Mutex<State>,
+    host: Mutex<Option<BrowserHost>>,
+}
+
+fn is_valid_scale(scale: f32) -> bool {
+    scale.is_finite() && scale > 0.0
+}
+
+impl OsrView {
+    /// A view of `width` by `height` device independent pixels, painted at `scale` device
+    /// pixels per DIP.
+    pub fn new(width: c_int, height: c_int, scale: f32) -> Self {
+        Self {
+            state: Mutex::new(State {
+                width: width.max(1),
+                height: height.max(1),
+                scale: if is_valid_scale(scale) { scale } else { 1.0 },
+            }),
+            host: Default::default(),
+        }
+    }
+
+    /// Notify `browser` of later changes. Until then, changes only apply to browsers created
+    /// afterwards.
+    pub fn set_browser(&self, browser: &impl ImplBrowser) {
+        *lock(&self.host) = browser.get_host();
+    }
+
+    /// The view size in DIPs.
+    pub fn size(&self) -> (c_int, c_int) {
+        let state = *lock(&self.state);
+        (state.width, state.height)
+    }
+
+    /// The device scale factor.
+    pub fn scale(&self) -> f32 {
+        lock(&self.state).scale
+    }
+
+    /// The size of the painted frames in device pixels.
+    pub fn frame_size(&self) -> (u32, u32) {
+        let state = *lock(&self.state);
+        let pixels = |dips: c_int| (dips as f32 * state.scale).round() as u32;
+        (pixels(state.width), pixels(state.height))
+    }
+
+    /// Resize the view to `width` by `height` DIPs. Sizes below 1 are raised to 1.
+    pub fn resize(&self, width: c_int, height: c_int) {
+        {
+            let mut state = lock(&self.state);
+            state.width = width.max(1);
+            state.height = height.max(1);
+        }
+        self.with_host(|host| host.was_resized());
+    }
+
+    /// Change the device scale factor. Factors which aren't finite and positive are ignored.
+    pub fn set_scale(&self, scale: f32) {
+        if !is_valid_scale(scale) {
+            return;
+        }
+        lock(&self.state).scale = scale;
+        self.with_host(|host| {
+            host.notify_screen_info_changed();
+            host.was_resized();
+        });
+    }
+
+    /// Call `f` with the browser host on the UI thread.
+    fn with_host(&self, f: impl FnOnce(&BrowserHost) + Send + 'static) {
+        let Some(host) = lock(&self.host).clone() else {
+            return;
+        };
+        if currently_on(ThreadId::Ui) {
+            f(&host);
+        } else {
+            task::post(ThreadId::Ui, move || f(&host));
+        }
+    }
+
+    /// Implements [ImplRenderHandler::get_view_rect].
+    pub fn get_view_rect(&self, rect: Option<&mut Rect>) {
+        if let Some(rect) = rect {
+            let (width, height) = self.size();
+            *rect = Rect {
+                x: 0,
+                y: 0,
+                width,
+                height,
+            };
+        }
+    }
+
+    /// Implements [ImplRenderHandler::get_screen_info]. The screen has the size of the view.
+    pub fn get_screen_info(&self, screen_info: Option<&mut ScreenInfo>) -> bool {
+        let Some(screen_info) = screen_info else {
+            return false;
+        };
+        let state = *lock(&self.state);
+        let rect = Rect {
+            x: 0,
+            y: 0,
+            width: state.width,
+            height: state.height,
+        };
+        screen_info.device_scale_factor = state.scale;
+        screen_info.depth = 32;
+        screen_info.depth_per_component = 8;
+        screen_info.is_monochrome = 0;
+        screen_info.rect = rect.clone();
+        screen_info.available_rect = rect;
+        true
+    }
+
+    /// Implements [ImplRenderHandler::get_screen_point]. The view is at the origin of the
+    /// screen, so this only scales DIPs to device pixels.
+    pub fn get_screen_point(
+        &self,
+        view_x: c_int,
+        view_y: c_int,
+        screen_x: Option<&mut c_int>,
+        screen_y: Option<&mut c_int>,
+    ) -> bool {
+        let (Some(screen_x), Some(screen_y)) = (screen_x, screen_y) else {
+            return false;
+        };
+        let scale = self.scale();
+        *screen_x = (view_x as f32 * scale).round() as c_int;
+        *screen_y = (view_y as f32 * scale).round() as c_int;
+        true
+    }
+}
@@ -0,0 +1,169 @@
+//! Requests waiting for a response from another process, shared by [ipc](crate::ipc) and
+//! [message_router](crate::message_router). They are canceled when their frame navigates or
+//! their browser goes away, since the response would never arrive.
+
+use std::{collections::HashMap, ffi::c_int, hash::Hash};
+
+use crate::*;
+
+fn frame_id(frame: &impl ImplFrame) -> String {
+    CefString::from(&frame.get_identifier()).to_string()
+}
+
+/// The frame a pending request belongs to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Origin {
+    pub browser_id: c_int,
+    pub frame_id: String,
+}
+
+impl Origin {
+    pub fn new(browser_id: c_int, frame: &impl ImplFrame) -> Self {
+        Self {
+            browser_id,
+            frame_id: frame_id(frame),
+        }
+    }
+}
+
+/// The pending requests to cancel.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Scope {
+    All,
+    Browser(c_int),
+    Frame(Origin),
+}
+
+impl Scope {
+    /// The requests of `frame` in the browser `browser_id`. A main frame navigation replaces
+    /// all frames, so it covers the whole browser.
+    pub fn frame(browser_id: c_int, frame: &impl ImplFrame) -> Self {
+        if frame.is_main() {
+            Self::Browser(browser_id)
+        } else {
+            Self::Frame(Origin::new(browser_id, frame))
+        }
+    }
+
+    /// The requests of `browser`, or of all browsers if it's `None`.
+    pub fn browser(browser: Option<&impl ImplBrowser>) -> Self {
+        browser.map_or(Self::All, |browser| Self::Browser(browser.get_identifier()))
+    }
+
+    fn contains(&self, origin: &Origin) -> bool {
+        match self {
+            Self::All => true,
+            Self::Browser(browser_id) => origin.browser_id == *browser_id,
+            Self::Frame(frame) => origin == frame,
+        }
+    }
+}
+
+/// Pending requests by ID, along with the frame each one belongs to.
+pub(crate) struct Pending<K, V> {
+    requests: HashMap<K, (Origin, V)>,
+}
+
+impl<K, V> Default for Pending<K, V> {
+    fn default() -> Self {
+        Self {
+            requests: HashMap::new(),
+        }
+    }
+}
+
+impl<K: Copy + Eq + Hash, V> Pending<K, V> {
+    pub fn insert(&mut self, id: K, origin: Origin, request: V) {
+        self.requests.insert(id, (origin, request));
+    }
+
+    pub fn get(&self, id: &K) -> Option<&V> {
+        self.requests.get(id).map(|(_, request)| request)
+    }
+
+    pub fn get_mut(&mut self, id: &K) -> Option<&mut V> {
+        self.requests.get_mut(id).map(|(_, request)| request)
+    }
+
+    pub fn remove(&mut self, id: &K) -> Option<V> {
+        self.requests.remove(id).map(|(_, request)| request)
+    }
+
+    pub fn len(&self) -> usize {
+        self.requests.len()
+    }
+
+    /// Remove the requests in `scope`. They are returned instead of being canceled here, so
+    /// the caller can release its lock before running their callbacks.
+    pub fn cancel(&mut self, scope: &Scope) -> Vec<V> {
+        self.remove_where(|origin, _| scope.contains(origin))
+    }
+
+    /// Remove the requests matching `predicate`, see [Pending::cancel].
+    pub fn remove_where(&mut self, predicate: impl Fn(&Origin, &V) -> bool) -> Vec<V> {
+        let ids = self
+            .requests
+            .iter()
+            .filter(|(_, (origin, request))| predicate(origin, request))
+            .map(|(id, _)| *id)
+            .collect::<Vec<_>>();
+        ids.into_iter().filter_map(|id| self.remove(&id)).collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn origin(browser_id: c_int, frame_id: &str) -> Origin {
+        Origin {
+            browser_id,
+            frame_id: frame_id.to_string(),
+        }
+    }
+
+    fn pending() -> Pending<i32, &'static str> {
+        let mut pending = Pending::default();
+        pending.insert(1, origin(1, "main"), "a");
+        pending.insert(2, origin(1, "child"), "b");
+        pending.insert(3, origin(2, "main"), "c");
+        pending.insert(4, origin(2, "child"), "d");
+        pending
+    }
+
+    fn sorted(mut requests: Vec<&'static str>) -> Vec<&'static str> {
+        requests.sort();
+        requests
+    }
+
+    #[test]
+    fn cancels_by_frame() {
+        let mut pending = pending();
+        assert_eq!(pending.cancel(&Scope::Frame(origin(1, "child"))), ["b"]);
+        // The same frame ID in another browser is a different frame.
+        assert!(pending.cancel(&Scope::Frame(origin(3, "main"))).is_empty());
+        assert_eq!(pending.len(), 3);
+        assert_eq!(pending.get(&1), Some(&"a"));
+        assert_eq!(pending.get(&2), None);
+    }
+
+    #[test]
+    fn cancels_by_browser() {
+        let mut pending = pending();
+        assert_eq!(sorted(pending.cancel(&Scope::Browser(2))), ["c", "d"]);
+        assert_eq!(pending.len(), 2);
+        assert_eq!(sorted(pending.cancel(&Scope::All)), ["a", "b"]);
+        assert_eq!(pending.len(), 0);
+    }
+
+    #[test]
+    fn removes_matching_requests() {
+        let mut pending = pending();
+        *pending.get_mut(&4).unwrap() = "e";
+        let removed =
+            pending.remove_where(|origin, request| origin.frame_id == "child" && *request != "b");
+        assert_eq!(removed, ["e"]);
+        assert_eq!(pending.remove(&2), Some("b"));
+        assert_eq!(pending.len(), 2);
+    }
+}
@@ -0,0 +1,60 @@
+//! [Result] returning wrappers for starting CEF processes.
+
+use crate::{args::Args, sandbox_info::SandboxInfo, *};
+
+/// The outcome of [execute_process].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExecuteProcess {
+    /// This is the browser process, continue with [initialize].
+    BrowserProcess,
+    /// This was a secondary process which has finished, exit with this code.
+    Subprocess(i32),
+}
+
+/// See [crate::execute_process] for more documentation.
+pub fn execute_process(
+    args: &Args,
+    app: Option<&mut impl ImplApp>,
+    sandbox_info: &SandboxInfo,
+) -> Result<ExecuteProcess> {
+    match crate::execute_process(Some(args.as_main_args()), app, sandbox_info.as_mut_ptr()) {
+        -1 => Ok(ExecuteProcess::BrowserProcess),
+        exit_code if exit_code >= 0 => Ok(ExecuteProcess::Subprocess(exit_code)),
+        code => Err(Error::ExecuteProcess(code)),
+    }
+}
+
+/// See [crate::initialize] for more documentation. On failure the error contains the value of
+/// [get_exit_code].
+pub fn initialize(
+    args: &Args,
+    settings: &Settings,
+    app: Option<&mut impl ImplApp>,
+    sandbox_info: &SandboxInfo,
+) -> Result<()> {
+    if crate::initialize(
+        Some(args.as_main_args()),
+        Some(settings),
+        app,
+        sandbox_info.as_mut_ptr(),
+    ) {
+        Ok(())
+    } else {
+        Err(Error::Initialize {
+            exit_code: get_exit_code(),
+        })
+    }
+}
+
+/// Initialize CEF in the browser process, run the message loop until
+/// [quit_message_loop] is called and shut down again.
+///
+/// Secondary processes should be dispatched with [execute_process] before calling this.
+pub fn run(app: &mut impl ImplApp, settings: &Settings) -> Result<()> {
+    let args = Args::new();
+    let sandbox_info = SandboxInfo::new();
+    initialize(&args, settings, Some(app), &sandbox_info)?;
+    run_message_loop();
+    shutdown();
+    Ok(())
+}
@@ -9,11 +9,20 @@ use std::{
     mem,
     ops::Deref,
     ptr::{self, NonNull},
-    sync::atomic::{fence, AtomicUsize, Ordering},
+    sync::{
+        atomic::{fence, AtomicUsize, Ordering},
+        Mutex, MutexGuard, PoisonError,
+    },
 };
 
 use cef_dll_sys::cef_base_ref_counted_t;
 
+/// Lock `mutex`, ignoring poisoning. A panic in a callback must not take down every later CEF
+/// callback which shares the same state.
+pub(crate) fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
 /// Reference counted trait for types has [`cef_base_ref_counted_t`].
 pub trait Rc {
     /// Increase the reference count by 1.
@@ -341,40 +350,64 @@ impl<T, I> RcImpl<T, I> {
     }
 }
 
-extern "C" fn add_ref<T, I>(this: *mut cef_base_ref_counted_t) {
-    let obj = RcImpl::<T, I>::get(this.cast());
+/// Declares functions with the calling convention of `CEF_CALLBACK`, which is `stdcall` on
+/// 32-bit Windows and `C` everywhere else.
+#[cfg(all(target_os = "windows", target_arch = "x86"))]
+macro_rules! cef_callback {
+    ($(
+        $vis:vis fn $name:ident<$($generic:ident),*>($($args:tt)*) $(-> $output:ty)? $body:block
+    )*) => {
+        $($vis extern "stdcall" fn $name<$($generic),*>($($args)*) $(-> $output)? $body)*
+    };
+}
 
-    obj.ref_count.fetch_add(1, Ordering::Relaxed);
+/// Declares functions with the calling convention of `CEF_CALLBACK`, which is `stdcall` on
+/// 32-bit Windows and `C` everywhere else.
+#[cfg(not(all(target_os = "windows", target_arch = "x86")))]
+macro_rules! cef_callback {
+    ($(
+        $vis:vis fn $name:ident<$($generic:ident),*>($($args:tt)*) $(-> $output:ty)? $body:block
+    )*) => {
+        $($vis extern "C" fn $name<$($generic),*>($($args)*) $(-> $output)? $body)*
+    };
 }
 
-extern "C" fn has_one_ref<T, I>(this: *mut cef_base_ref_counted_t) -> i32 {
-    let obj = RcImpl::<T, I>::get(this.cast());
+cef_callback! {
+    fn add_ref<T, I>(this: *mut cef_base_ref_counted_t) {
+        let obj = RcImpl::<T, I>::get(this.cast());
 
-    if obj.ref_count.load(Ordering::Relaxed) == 1 {
-        1
-    } else {
-        0
+        obj.ref_count.fetch_add(1, Ordering::Relaxed);
     }
-}
 
-extern "C" fn has_at_least_one_ref<T, I>(this: *mut cef_base_ref_counted_t) -> i32 {
-    let obj = RcImpl::<T, I>::get(this.cast());
+    fn has_one_ref<T, I>(this: *mut cef_base_ref_counted_t) -> i32 {
+        let obj = RcImpl::<T, I>::get(this.cast());
 
-    if obj.ref_count.load(Ordering::Relaxed) >= 1 {
-        1
-    } else {
-        0
+        if obj.ref_count.load(Ordering::Relaxed) == 1 {
+            1
+        } else {
+            0
+        }
     }
-}
 
-pub extern "C" fn release<T, I>(this: *mut cef_base_ref_counted_t) -> i32 {
-    let obj = RcImpl::<T, I>::get(this.cast());
+    fn has_at_least_one_ref<T, I>(this: *mut cef_base_ref_counted_t) -> i32 {
+        let obj = RcImpl::<T, I>::get(this.cast());
 
-    if obj.ref_count.fetch_sub(1, Ordering::Release) != 1 {
-        0
-    } else {
-        fence(Ordering::Acquire);
-        let _: Box<RcImpl<T, I>> = unsafe { Box::from_raw(this.cast()) };
-        1
+        if obj.ref_count.load(Ordering::Relaxed) >= 1 {
+            1
+        } else {
+            0
+        }
+    }
+
+    pub fn release<T, I>(this: *mut cef_base_ref_counted_t) -> i32 {
+        let obj = RcImpl::<T, I>::get(this.cast());
+
+        if obj.ref_count.fetch_sub(1, Ordering::Release) != 1 {
+            0
+        } else {
+            fence(Ordering::Acquire);
+            let _: Box<RcImpl<T, I>> = unsafe { Box::from_raw(this.cast()) };
+            1
+        }
     }
 }
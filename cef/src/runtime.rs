@@ -0,0 +1,166 @@
+//! Bootstrap CEF's multi-process architecture with a single call.
+//!
+//! [launch] loads the CEF framework on macOS, dispatches secondary processes with
+//! [execute_process](crate::process::execute_process) and initializes CEF in the browser
+//! process. The returned [Runtime] shuts CEF down again when it is dropped.
+//!
+//! ```no_run
+//! # fn main() -> cef::Result<()> {
+//! let mut app = cef::App::builder().build();
+//! let settings = cef::Settings::default();
+//!
+//! let runtime = cef::launch(&mut app, &settings)?;
+//! if runtime.is_browser_process() {
+//!     runtime.run_message_loop();
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::{
+    args::Args,
+    process::{execute_process, initialize, ExecuteProcess},
+    sandbox_info::SandboxInfo,
+    *,
+};
+
+#[cfg(target_os = "macos")]
+use crate::library_loader::LibraryLoader;
+
+/// A launched CEF process. In the browser process CEF stays initialized until this is dropped.
+pub struct Runtime {
+    process: ExecuteProcess,
+    initialized: bool,
+    process_type: Option<String>,
+    _args: Args,
+    _sandbox_info: SandboxInfo,
+    #[cfg(target_os = "macos")]
+    _sandbox_context: SandboxContext,
+    // Dropped last, CEF must be shut down before the framework is unloaded.
+    #[cfg(target_os = "macos")]
+    _loader: LibraryLoader,
+}
+
+impl Runtime {
+    /// Returns `true` if this is the browser process.
+    pub fn is_browser_process(&self) -> bool {
+        self.process == ExecuteProcess::BrowserProcess
+    }
+
+    /// The exit code of a secondary process, or `None` in the browser process.
+    pub fn exit_code(&self) -> Option<i32> {
+        match self.process {
+            ExecuteProcess::BrowserProcess => None,
+            ExecuteProcess::Subprocess(exit_code) => Some(exit_code),
+        }
+    }
+
+    /// The value of the `type` command line switch, e.g. `renderer` or `gpu-process`. The
+    /// browser process doesn't have a type.
+    pub fn process_type(&self) -> Option<&str> {
+        self.process_type.as_deref()
+    }
+
+    /// Run the CEF message loop until [quit_message_loop] is called. Does nothing outside of the
+    /// browser process.
+    pub fn run_message_loop(&self) {
+        if self.initialized {
+            run_message_loop();
+        }
+    }
+}
+
+impl Drop for Runtime {
+    fn drop(&mut self) {
+        if self.initialized {
+            shutdown();
+        }
+    }
+}
+
+/// Destroys the macOS sandbox context when dropped, including when [bootstrap] fails.
+#[cfg(target_os = "macos")]
+struct SandboxContext(*mut u8);
+
+#[cfg(target_os = "macos")]
+impl Drop for SandboxContext {
+    fn drop(&mut self) {
+        if !self.0.is_null() {
+            sandbox_destroy(self.0);
+        }
+    }
+}
+
+/// Launch the main executable. Secondary processes run to completion before this returns, the
+/// browser process is initialized with `settings`.
+///
+/// On macOS secondary processes are started from a separate helper bundle, which should call
+/// [launch_helper] instead.
+pub fn launch(app: &mut impl ImplApp, settings: &Settings) -> Result<Runtime> {
+    bootstrap(Some(app), Some(settings), false)
+}
+
+/// Launch a secondary process from the macOS helper bundle, loading the framework relative to
+/// the helper and initializing the sandbox. On other platforms this behaves like [launch] for a
+/// secondary process.
+pub fn launch_helper(app: Option<&mut impl ImplApp>) -> Result<Runtime> {
+    bootstrap(app, None, true)
+}
+
+fn bootstrap(
+    mut app: Option<&mut impl ImplApp>,
+    settings: Option<&Settings>,
+    helper: bool,
+) -> Result<Runtime> {
+    #[cfg(target_os = "macos")]
+    let loader = {
+        let exe = std::env::current_exe().map_err(|err| Error::CurrentExe {
+            kind: err.kind(),
+            reason: err.to_string(),
+        })?;
+        let loader = LibraryLoader::new(&exe, helper);
+        loader.load()?;
+        loader
+    };
+    #[cfg(not(target_os = "macos"))]
+    let _ = helper;
+
+    let _ = api_hash(sys::CEF_API_VERSION_LAST, 0);
+
+    let args = Args::new();
+    let sandbox_info = SandboxInfo::new();
+
+    let switch = CefString::from("type");
+    let process_type = args
+        .as_cmd_line()
+        .filter(|cmd| cmd.has_switch(Some(&switch)))
+        .map(|cmd| CefString::from(&cmd.get_switch_value(Some(&switch))).to_string());
+
+    #[cfg(target_os = "macos")]
+    let sandbox_context = SandboxContext(if helper {
+        let main_args = args.as_main_args();
+        sandbox_initialize(main_args.argc, main_args.argv).cast()
+    } else {
+        std::ptr::null_mut()
+    });
+
+    let process = execute_process(&args, app.as_deref_mut(), &sandbox_info)?;
+    let mut runtime = Runtime {
+        process,
+        initialized: false,
+        process_type,
+        _args: args,
+        _sandbox_info: sandbox_info,
+        #[cfg(target_os = "macos")]
+        _sandbox_context: sandbox_context,
+        #[cfg(target_os = "macos")]
+        _loader: loader,
+    };
+
+    if let (ExecuteProcess::BrowserProcess, Some(settings)) = (process, settings) {
+        initialize(&runtime._args, settings, app, &runtime._sandbox_info)?;
+        runtime.initialized = true;
+    }
+
+    Ok(runtime)
+}
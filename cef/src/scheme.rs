@@ -0,0 +1,494 @@
+//! Helpers for serving custom schemes.
+//!
+//! [StaticDir] serves the files of a directory, or files embedded with
+//! [include_dir](crate::include_dir), so a web UI can be loaded from e.g.
+//! `app://localhost/index.html` without an HTTP server.
+//! The scheme must be registered in every process from [ImplApp::on_register_custom_schemes]
+//! with [StaticDir::add_custom_scheme], and the handler factory in the browser process with
+//! [StaticDir::register]:
+//!
+//! ```no_run
+//! # fn main() -> cef::Result<()> {
+//! use cef::{scheme::StaticDir, *};
+//!
+//! static ASSETS: &[(&str, &[u8])] = &[("index.html", b"<h1>Hello</h1>")];
+//!
+//! let assets = StaticDir::embedded("app", ASSETS).domain("localhost");
+//!
+//! // In ImplApp::on_register_custom_schemes, in all processes:
+//! # let registrar: SchemeRegistrar = unimplemented!();
+//! assets.add_custom_scheme(&registrar);
+//!
+//! // In the browser process, after CEF is initialized:
+//! assets.register()?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::{
+    borrow::Cow,
+    path::{Component, Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+use crate::{rc::lock, *};
+
+/// See [crate::register_scheme_handler_factory] for more documentation.
+pub fn register_scheme_handler_factory(
+    scheme_name: &str,
+    domain_name: Option<&str>,
+    factory: &mut impl ImplSchemeHandlerFactory,
+) -> Result<()> {
+    let domain_name = domain_name.map(CefString::from);
+    if crate::register_scheme_handler_factory(
+        Some(&CefString::from(scheme_name)),
+        domain_name.as_ref(),
+        Some(factory),
+    ) {
+        Ok(())
+    } else {
+        Err(Error::RegisterSchemeHandlerFactory {
+            scheme_name: scheme_name.to_string(),
+        })
+    }
+}
+
+/// The files served by a [StaticDir].
+#[derive(Debug, Clone)]
+enum Files {
+    Dir(PathBuf),
+    Embedded(&'static [(&'static str, &'static [u8])]),
+}
+
+impl Files {
+    fn get(&self, path: &str) -> Option<Cow<'static, [u8]>> {
+        match self {
+            Self::Dir(root) => {
+                let path = root.join(path);
+                if !path.is_file() {
+                    return None;
+                }
+                std::fs::read(path).ok().map(Cow::Owned)
+            }
+            Self::Embedded(files) => files
+                .iter()
+                .find(|(name, _)| *name == path)
+                .map(|(_, data)| Cow::Borrowed(*data)),
+        }
+    }
+}
+
+/// Serves static files through a custom scheme, with MIME type detection, `Range` requests and
+/// `404 Not Found` responses for missing files.
+#[derive(Debug, Clone)]
+pub struct StaticDir {
+    scheme_name: String,
+    domain_name: Option<String>,
+    index: String,
+    options: SchemeOptions,
+    files: Files,
+}
+
+impl StaticDir {
+    /// Serve the files below `root` on disk. Paths containing `..` are rejected.
+    pub fn new(scheme_name: &str, root: impl Into<PathBuf>) -> Self {
+        Self::with_files(scheme_name, Files::Dir(root.into()))
+    }
+
+    /// Serve files embedded in the binary, usually with [include_dir](crate::include_dir).
+    pub fn embedded(scheme_name: &str, files: &'static [(&'static str, &'static [u8])]) -> Self {
+        Self::with_files(scheme_name, Files::Embedded(files))
+    }
+
+    fn with_files(scheme_name: &str, files: Files) -> Self {
+        Self {
+            scheme_name: scheme_name.to_string(),
+            domain_name: None,
+            index: "index.html".to_string(),
+            options: SchemeOptions::STANDARD
+                | SchemeOptions::SECURE
+                | SchemeOptions::CORS_ENABLED
+                | SchemeOptions::FETCH_ENABLED,
+            files,
+        }
+    }
+
+    /// Only handle requests for this domain, e.g. `app://localhost/`.
+    pub fn domain(mut self, domain_name: &str) -> Self {
+        self.domain_name = Some(domain_name.to_string());
+        self
+    }
+
+    /// The file served for directory paths, `index.html` by default.
+    pub fn index(mut self, index: &str) -> Self {
+        self.index = index.to_string();
+        self
+    }
+
+    /// The options passed to [ImplSchemeRegistrar::add_custom_scheme], a standard, secure scheme
+    /// with CORS and fetch enabled by default.
+    pub fn options(mut self, options: SchemeOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Register the scheme, this must be called from [ImplApp::on_register_custom_schemes] in
+    /// every process.
+    pub fn add_custom_scheme(&self, registrar: &impl ImplSchemeRegistrar) -> bool {
+        registrar.add_custom_scheme(
+            Some(&CefString::from(self.scheme_name.as_str())),
+            self.options.bits() as _,
+        )
+    }
+
+    /// Register the handler factory for the scheme in the browser process.
+    pub fn register(&self) -> Result<()> {
+        register_scheme_handler_factory(
+            &self.scheme_name,
+            self.domain_name.as_deref(),
+            &mut self.factory(),
+        )
+    }
+
+    /// Create the handler factory, for use with [register_scheme_handler_factory] or
+    /// [ImplRequestContext::register_scheme_handler_factory].
+    pub fn factory(&self) -> SchemeHandlerFactory {
+        let dir = self.clone();
+        SchemeHandlerFactory::builder()
+            .create(move |_, _, _, request| {
+                let request = request?;
+                let url = CefString::from(&request.get_url()).to_string();
+                let method = CefString::from(&request.get_method()).to_string();
+                let range =
+                    CefString::from(&request.get_header_by_name(Some(&CefString::from("Range"))))
+                        .to_string();
+                Some(dir.respond(&url, &method, &range).into_handler())
+            })
+            .build()
+    }
+
+    fn respond(&self, url: &str, method: &str, range: &str) -> StaticResponse {
+        if method != "GET" && method != "HEAD" {
+            return StaticResponse::error(405, "Method Not Allowed");
+        }
+        let Some(path) = url_path(url, &self.scheme_name) else {
+            return StaticResponse::error(404, "Not Found");
+        };
+        let path = if path.is_empty() || path.ends_with('/') {
+            format!("{path}{}", self.index)
+        } else {
+            path
+        };
+        let Some(data) = self.files.get(&path) else {
+            return StaticResponse::error(404, "Not Found");
+        };
+
+        let mut response = StaticResponse {
+            status: 200,
+            status_text: "OK".into(),
+            mime_type: mime_type(&path).into(),
+            headers: vec![("Accept-Ranges".into(), "bytes".to_string())],
+            range: 0..data.len(),
+            data,
+        };
+        if !range.is_empty() {
+            let length = response.data.len();
+            match parse_range(range, length) {
+                ByteRange::Ignored => {}
+                ByteRange::Unsatisfiable => {
+                    let mut response = StaticResponse::error(416, "Range Not Satisfiable");
+                    response
+                        .headers
+                        .push(("Content-Range".into(), format!("bytes */{length}")));
+                    return response;
+                }
+                ByteRange::Partial(range) => {
+                    response.status = 206;
+                    response.status_text = "Partial Content".into();
+                    response.headers.push((
+                        "Content-Range".into(),
+                        format!("bytes {}-{}/{length}", range.start, range.end - 1),
+                    ));
+                    response.range = range;
+                }
+            }
+        }
+        if method == "HEAD" {
+            response.range = response.range.start..response.range.start;
+        }
+        response
+    }
+}
+
+/// Extract the percent-decoded path relative to the root of a URL of `scheme_name`, without
+/// the leading `/`. Returns `None` for other schemes and for paths which leave the root.
+fn url_path(url: &str, scheme_name: &str) -> Option<String> {
+    let rest = url
+        .split_once(':')
+        .filter(|(scheme, _)| scheme.eq_ignore_ascii_case(scheme_name))?
+        .1;
+    let rest = match rest.strip_prefix("//") {
+        Some(rest) => rest.find('/').map(|start| &rest[start..]).unwrap_or(""),
+        None => rest,
+    };
+    let end = rest.find(['?', '#']).unwrap_or(rest.len());
+    let path = percent_decode(&rest[..end])?;
+    let path = path.trim_start_matches('/');
+
+    let is_normal = Path::new(path)
+        .components()
+        .all(|component| matches!(component, Component::Normal(_)));
+    (is_normal && !path.contains('\\')).then(|| path.to_string())
+}
+
+fn percent_decode(value: &str) -> Option<String> {
+    let mut bytes = Vec::with_capacity(value.len());
+    let mut iter = value.bytes();
+    while let Some(byte) = iter.next() {
+        if byte == b'%' {
+            let hex = [iter.next()?, iter.next()?];
+            let hex = std::str::from_utf8(&hex).ok()?;
+            bytes.push(u8::from_str_radix(hex, 16).ok()?);
+        } else {
+            bytes.push(byte);
+        }
+    }
+    String::from_utf8(bytes).ok()
+}
+
+/// The outcome of a `Range` header.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ByteRange {
+    /// The header is malformed or not a single `bytes=` range, so the full body is served.
+    Ignored,
+    /// The range starts past the end of the body.
+    Unsatisfiable,
+    Partial(std::ops::Range<usize>),
+}
+
+/// Parse a single `bytes=` range of a `Range` header. Per RFC 9110, headers which aren't
+/// understood are ignored rather than rejected.
+fn parse_range(range: &str, length: usize) -> ByteRange {
+    let Some((start, end)) = range
+        .trim()
+        .strip_prefix("bytes=")
+        .and_then(|range| range.split_once('-'))
+    else {
+        return ByteRange::Ignored;
+    };
+    let (start, end) = (start.trim(), end.trim());
+    let number = |value: &str| {
+        (!value.is_empty() && value.bytes().all(|byte| byte.is_ascii_digit()))
+            .then(|| value.parse::<usize>().unwrap_or(usize::MAX))
+    };
+    if start.is_empty() {
+        return match number(end) {
+            Some(0) => ByteRange::Unsatisfiable,
+            Some(_) if length == 0 => ByteRange::Unsatisfiable,
+            Some(suffix) => ByteRange::Partial(length.saturating_sub(suffix)..length),
+            None => ByteRange::Ignored,
+        };
+    }
+    let Some(start) = number(start) else {
+        return ByteRange::Ignored;
+    };
+    let end = if end.is_empty() {
+        length
+    } else {
+        match number(end) {
+            Some(end) if end >= start => end.saturating_add(1).min(length),
+            _ => return ByteRange::Ignored,
+        }
+    };
+    if start >= length {
+        ByteRange::Unsatisfiable
+    } else {
+        ByteRange::Partial(start..end)
+    }
+}
+
+/// Guess the MIME type from the file extension.
+fn mime_type(path: &str) -> &'static str {
+    let extension = path
+        .rsplit_once('.')
+        .map(|(_, extension)| extension.to_ascii_lowercase())
+        .unwrap_or_default();
+    match extension.as_str() {
+        "html" | "htm" => "text/html",
+        "js" | "mjs" => "text/javascript",
+        "css" => "text/css",
+        "json" | "map" => "application/json",
+        "txt" => "text/plain",
+        "xml" => "application/xml",
+        "svg" => "image/svg+xml",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "avif" => "image/avif",
+        "ico" => "image/x-icon",
+        "wasm" => "application/wasm",
+        "woff" => "font/woff",
+        "woff2" => "font/woff2",
+        "ttf" => "font/ttf",
+        "otf" => "font/otf",
+        "mp3" => "audio/mpeg",
+        "ogg" => "audio/ogg",
+        "wav" => "audio/wav",
+        "mp4" => "video/mp4",
+        "webm" => "video/webm",
+        "pdf" => "application/pdf",
+        _ => "application/octet-stream",
+    }
+}
+
+/// A response which is prepared before the [ResourceHandler] is created, used by [StaticDir]
+/// and for the mocked responses of [intercept](crate::intercept).
+pub(crate) struct StaticResponse {
+    pub(crate) status: i32,
+    pub(crate) status_text: Cow<'static, str>,
+    pub(crate) mime_type: Cow<'static, str>,
+    pub(crate) headers: Vec<(Cow<'static, str>, String)>,
+    pub(crate) data: Cow<'static, [u8]>,
+    pub(crate) range: std::ops::Range<usize>,
+}
+
+impl StaticResponse {
+    fn error(status: i32, status_text: &'static str) -> Self {
+        let data = Cow::Borrowed(status_text.as_bytes());
+        Self {
+            status,
+            status_text: status_text.into(),
+            mime_type: "text/plain".into(),
+            headers: vec![],
+            range: 0..data.len(),
+            data,
+        }
+    }
+
+    pub(crate) fn into_handler(self) -> ResourceHandler {
+        let response = Arc::new(Mutex::new(self));
+        let headers = response.clone();
+        let skip = response.clone();
+        let read = response.clone();
+
+        ResourceHandler::builder()
+            .open(|_, handle_request, _| {
+                if let Some(handle_request) = handle_request {
+                    *handle_request = 1;
+                }
+                true
+            })
+            .get_response_headers(move |response, response_length, _| {
+                let headers = lock(&headers);
+                if let Some(response) = response {
+                    response.set_status(headers.status);
+                    response.set_status_text(Some(&CefString::from(headers.status_text.as_ref())));
+                    response.set_mime_type(Some(&CefString::from(headers.mime_type.as_ref())));
+                    if headers.mime_type.starts_with("text/")
+                        || headers.mime_type == "application/json"
+                    {
+                        response.set_charset(Some(&CefString::from("utf-8")));
+                    }
+                    for (name, value) in &headers.headers {
+                        response.set_header_by_name(
+                            Some(&CefString::from(name.as_ref())),
+                            Some(&CefString::from(value.as_str())),
+                            true,
+                        );
+                    }
+                }
+                if let Some(response_length) = response_length {
+                    *response_length = headers.range.len() as i64;
+                }
+            })
+            .skip(move |bytes_to_skip, bytes_skipped, _| {
+                let mut skip = lock(&skip);
+                let count = usize::try_from(bytes_to_skip)
+                    .unwrap_or_default()
+                    .min(skip.range.len());
+                skip.range.start += count;
+                if let Some(bytes_skipped) = bytes_skipped {
+                    *bytes_skipped = count as i64;
+                }
+                count > 0
+            })
+            .read(move |data_out, _, bytes_read, _| {
+                let mut read = lock(&read);
+                let count = match data_out {
+                    Some(data_out) => {
+                        let count = data_out.len().min(read.range.len());
+                        let start = read.range.start;
+                        data_out[..count].copy_from_slice(&read.data[start..start + count]);
+                        read.range.start += count;
+                        count
+                    }
+                    None => 0,
+                };
+                if let Some(bytes_read) = bytes_read {
+                    *bytes_read = count as i32;
+                }
+                count > 0
+            })
+            .build()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn url_path_rejects_traversal() {
+        assert_eq!(
+            url_path("app://localhost/assets/app%20main.js?v=1#top", "app").as_deref(),
+            Some("assets/app main.js")
+        );
+        assert_eq!(url_path("app://localhost", "app").as_deref(), Some(""));
+        assert_eq!(
+            url_path("app:index.html", "app").as_deref(),
+            Some("index.html")
+        );
+        assert_eq!(url_path("https://localhost/index.html", "app"), None);
+        assert_eq!(url_path("app://localhost/../secret", "app"), None);
+        assert_eq!(
+            url_path("app://localhost/a/%2e%2e/%2e%2e/secret", "app"),
+            None
+        );
+    }
+
+    #[test]
+    fn range_requests() {
+        use ByteRange::*;
+        assert_eq!(parse_range("bytes=0-99", 1000), Partial(0..100));
+        assert_eq!(parse_range("bytes=900-", 1000), Partial(900..1000));
+        assert_eq!(parse_range("bytes=-100", 1000), Partial(900..1000));
+        assert_eq!(parse_range("bytes=-2000", 1000), Partial(0..1000));
+        assert_eq!(parse_range("bytes=990-2000", 1000), Partial(990..1000));
+        assert_eq!(parse_range("bytes=1000-", 1000), Unsatisfiable);
+        assert_eq!(parse_range("bytes=-0", 1000), Unsatisfiable);
+        assert_eq!(parse_range("bytes=-5", 0), Unsatisfiable);
+        assert_eq!(parse_range("items=0-1", 1000), Ignored);
+        assert_eq!(parse_range("bytes=0-1,5-6", 1000), Ignored);
+        assert_eq!(parse_range("bytes=5-2", 1000), Ignored);
+        assert_eq!(parse_range("bytes=+1-2", 1000), Ignored);
+        assert_eq!(parse_range("bytes=-", 1000), Ignored);
+
+        let dir = StaticDir::embedded("app", &[("index.html", b"0123456789")]);
+        let response = dir.respond("app://localhost/", "GET", "bytes=2-4");
+        assert_eq!(response.status, 206);
+        assert_eq!(response.mime_type, "text/html");
+        assert_eq!(&response.data[response.range], b"234");
+        assert_eq!(
+            dir.respond("app://localhost/missing.js", "GET", "").status,
+            404
+        );
+        assert_eq!(
+            dir.respond("app://localhost/", "GET", "bytes=20-").status,
+            416
+        );
+        let response = dir.respond("app://localhost/", "GET", "bytes=0-1,5-6");
+        assert_eq!(response.status, 200);
+        assert_eq!(&response.data[response.range], b"0123456789");
+    }
+}
@@ -0,0 +1,520 @@
+//! Typed builders for [Settings], [BrowserSettings] and [RequestContextSettings].
+//!
+//! The builders use `bool`, [PathBuf], [Color] and [Option] instead of the `c_int` flags and
+//! [CefString] fields of the raw structs, check the values CEF would otherwise reject or ignore
+//! in `build`, and implement [Debug] for logging the effective configuration.
+//!
+//! ```no_run
+//! # fn main() -> cef::Result<()> {
+//! let settings = cef::Settings::builder()
+//!     .windowless_rendering_enabled(true)
+//!     .root_cache_path("/tmp/cefsimple")
+//!     .cache_path("/tmp/cefsimple/default")
+//!     .build()?;
+//!
+//! let browser_settings = cef::BrowserSettings::builder()
+//!     .windowless_frame_rate(60)
+//!     .build()?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::{ffi::c_int, ops::RangeInclusive, path::PathBuf};
+
+use crate::*;
+
+/// Valid range for [BrowserSettingsBuilder::windowless_frame_rate].
+pub const WINDOWLESS_FRAME_RATE: RangeInclusive<u32> = 1..=240;
+
+/// Valid range for [SettingsBuilder::remote_debugging_port].
+pub const REMOTE_DEBUGGING_PORT: RangeInclusive<u16> = 1024..=65535;
+
+macro_rules! setters {
+    ($($name:ident: $kind:ident),* $(,)?) => {
+        $(setters!(@$kind $name);)*
+    };
+    (@bool $name:ident) => {
+        pub fn $name(mut self, value: bool) -> Self {
+            self.$name = value;
+            self
+        }
+    };
+    (@path $name:ident) => {
+        pub fn $name(mut self, value: impl Into<PathBuf>) -> Self {
+            self.$name = Some(value.into());
+            self
+        }
+    };
+    (@string $name:ident) => {
+        pub fn $name(mut self, value: impl Into<String>) -> Self {
+            self.$name = Some(value.into());
+            self
+        }
+    };
+    (@state $name:ident) => {
+        /// Leave unset to use the default [State].
+        pub fn $name(mut self, enabled: bool) -> Self {
+            self.$name = Some(enabled);
+            self
+        }
+    };
+}
+
+fn invalid(field: &'static str, reason: impl Into<String>) -> Error {
+    Error::InvalidSettings {
+        field,
+        reason: reason.into(),
+    }
+}
+
+/// Convert a count or size to the `int` of the raw struct, where 0 means unset.
+fn int(field: &'static str, value: Option<u32>) -> Result<c_int> {
+    value.map_or(Ok(0), |value| {
+        c_int::try_from(value)
+            .map_err(|_| invalid(field, format!("{value} is larger than {}", c_int::MAX)))
+    })
+}
+
+fn path(value: &Option<PathBuf>) -> CefString {
+    value
+        .as_deref()
+        .map(|value| CefString::from(value.to_string_lossy().as_ref()))
+        .unwrap_or_default()
+}
+
+fn string(value: &Option<String>) -> CefString {
+    value.as_deref().map(CefString::from).unwrap_or_default()
+}
+
+fn state(value: Option<bool>) -> State {
+    match value {
+        None => State::Default,
+        Some(true) => State::Enabled,
+        Some(false) => State::Disabled,
+    }
+}
+
+fn check_absolute(field: &'static str, value: &Option<PathBuf>) -> Result<()> {
+    match value {
+        Some(value) if !value.is_absolute() => Err(invalid(
+            field,
+            format!("{} is not an absolute path", value.display()),
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Builder for [Settings], see [Settings::builder].
+#[derive(Debug, Clone, Default)]
+pub struct SettingsBuilder {
+    no_sandbox: bool,
+    browser_subprocess_path: Option<PathBuf>,
+    framework_dir_path: Option<PathBuf>,
+    main_bundle_path: Option<PathBuf>,
+    multi_threaded_message_loop: bool,
+    external_message_pump: bool,
+    windowless_rendering_enabled: bool,
+    command_line_args_disabled: bool,
+    cache_path: Option<PathBuf>,
+    root_cache_path: Option<PathBuf>,
+    persist_session_cookies: bool,
+    user_agent: Option<String>,
+    user_agent_product: Option<String>,
+    locale: Option<String>,
+    log_file: Option<PathBuf>,
+    log_severity: LogSeverity,
+    log_items: LogItems,
+    javascript_flags: Option<String>,
+    resources_dir_path: Option<PathBuf>,
+    locales_dir_path: Option<PathBuf>,
+    remote_debugging_port: Option<u16>,
+    uncaught_exception_stack_size: u32,
+    background_color: Option<Color>,
+    accept_language_list: Option<String>,
+    cookieable_schemes_list: Option<String>,
+    cookieable_schemes_exclude_defaults: bool,
+    chrome_policy_id: Option<String>,
+    chrome_app_icon_id: i32,
+    disable_signal_handlers: bool,
+}
+
+impl Settings {
+    pub fn builder() -> SettingsBuilder {
+        Default::default()
+    }
+}
+
+impl SettingsBuilder {
+    setters! {
+        no_sandbox: bool,
+        browser_subprocess_path: path,
+        framework_dir_path: path,
+        main_bundle_path: path,
+        multi_threaded_message_loop: bool,
+        external_message_pump: bool,
+        windowless_rendering_enabled: bool,
+        command_line_args_disabled: bool,
+        cache_path: path,
+        root_cache_path: path,
+        persist_session_cookies: bool,
+        user_agent: string,
+        user_agent_product: string,
+        locale: string,
+        log_file: path,
+        javascript_flags: string,
+        resources_dir_path: path,
+        locales_dir_path: path,
+        accept_language_list: string,
+        cookieable_schemes_list: string,
+        cookieable_schemes_exclude_defaults: bool,
+        chrome_policy_id: string,
+        disable_signal_handlers: bool,
+    }
+
+    pub fn log_severity(mut self, value: LogSeverity) -> Self {
+        self.log_severity = value;
+        self
+    }
+
+    pub fn log_items(mut self, value: LogItems) -> Self {
+        self.log_items = value;
+        self
+    }
+
+    /// Must be in [REMOTE_DEBUGGING_PORT], remote debugging is disabled if unset.
+    pub fn remote_debugging_port(mut self, value: u16) -> Self {
+        self.remote_debugging_port = Some(value);
+        self
+    }
+
+    /// Set to `0` to disable uncaught exception stack traces.
+    pub fn uncaught_exception_stack_size(mut self, value: u32) -> Self {
+        self.uncaught_exception_stack_size = value;
+        self
+    }
+
+    /// Defaults to opaque white if unset.
+    pub fn background_color(mut self, value: Color) -> Self {
+        self.background_color = Some(value);
+        self
+    }
+
+    pub fn chrome_app_icon_id(mut self, value: i32) -> Self {
+        self.chrome_app_icon_id = value;
+        self
+    }
+
+    /// Validate the configuration and convert it to [Settings].
+    ///
+    /// `cache_path` and `root_cache_path` must be absolute, and `cache_path` must be equal to or
+    /// a child of `root_cache_path` if both are set.
+    pub fn build(&self) -> Result<Settings> {
+        check_absolute("cache_path", &self.cache_path)?;
+        check_absolute("root_cache_path", &self.root_cache_path)?;
+        if let (Some(cache_path), Some(root_cache_path)) = (&self.cache_path, &self.root_cache_path)
+        {
+            if !cache_path.starts_with(root_cache_path) {
+                return Err(invalid(
+                    "cache_path",
+                    format!(
+                        "{} is not inside root_cache_path {}",
+                        cache_path.display(),
+                        root_cache_path.display()
+                    ),
+                ));
+            }
+        }
+
+        if let Some(port) = self.remote_debugging_port {
+            if !REMOTE_DEBUGGING_PORT.contains(&port) {
+                return Err(invalid(
+                    "remote_debugging_port",
+                    format!("{port} is not in {REMOTE_DEBUGGING_PORT:?}"),
+                ));
+            }
+        }
+
+        Ok(Settings {
+            no_sandbox: self.no_sandbox.into(),
+            browser_subprocess_path: path(&self.browser_subprocess_path),
+            framework_dir_path: path(&self.framework_dir_path),
+            main_bundle_path: path(&self.main_bundle_path),
+            multi_threaded_message_loop: self.multi_threaded_message_loop.into(),
+            external_message_pump: self.external_message_pump.into(),
+            windowless_rendering_enabled: self.windowless_rendering_enabled.into(),
+            command_line_args_disabled: self.command_line_args_disabled.into(),
+            cache_path: path(&self.cache_path),
+            root_cache_path: path(&self.root_cache_path),
+            persist_session_cookies: self.persist_session_cookies.into(),
+            user_agent: string(&self.user_agent),
+            user_agent_product: string(&self.user_agent_product),
+            locale: string(&self.locale),
+            log_file: path(&self.log_file),
+            log_severity: self.log_severity,
+            log_items: self.log_items,
+            javascript_flags: string(&self.javascript_flags),
+            resources_dir_path: path(&self.resources_dir_path),
+            locales_dir_path: path(&self.locales_dir_path),
+            remote_debugging_port: self.remote_debugging_port.map_or(0, Into::into),
+            uncaught_exception_stack_size: int(
+                "uncaught_exception_stack_size",
+                Some(self.uncaught_exception_stack_size),
+            )?,
+            background_color: self.background_color.unwrap_or_default(),
+            accept_language_list: string(&self.accept_language_list),
+            cookieable_schemes_list: string(&self.cookieable_schemes_list),
+            cookieable_schemes_exclude_defaults: self.cookieable_schemes_exclude_defaults.into(),
+            chrome_policy_id: string(&self.chrome_policy_id),
+            chrome_app_icon_id: self.chrome_app_icon_id,
+            disable_signal_handlers: self.disable_signal_handlers.into(),
+            ..Default::default()
+        })
+    }
+}
+
+/// Builder for [BrowserSettings], see [BrowserSettings::builder].
+#[derive(Debug, Clone, Default)]
+pub struct BrowserSettingsBuilder {
+    windowless_frame_rate: Option<u32>,
+    standard_font_family: Option<String>,
+    fixed_font_family: Option<String>,
+    serif_font_family: Option<String>,
+    sans_serif_font_family: Option<String>,
+    cursive_font_family: Option<String>,
+    fantasy_font_family: Option<String>,
+    default_font_size: Option<u32>,
+    default_fixed_font_size: Option<u32>,
+    minimum_font_size: Option<u32>,
+    minimum_logical_font_size: Option<u32>,
+    default_encoding: Option<String>,
+    remote_fonts: Option<bool>,
+    javascript: Option<bool>,
+    javascript_close_windows: Option<bool>,
+    javascript_access_clipboard: Option<bool>,
+    javascript_dom_paste: Option<bool>,
+    image_loading: Option<bool>,
+    image_shrink_standalone_to_fit: Option<bool>,
+    text_area_resize: Option<bool>,
+    tab_to_links: Option<bool>,
+    local_storage: Option<bool>,
+    databases: Option<bool>,
+    webgl: Option<bool>,
+    background_color: Option<Color>,
+    chrome_status_bubble: Option<bool>,
+    chrome_zoom_bubble: Option<bool>,
+}
+
+impl BrowserSettings {
+    pub fn builder() -> BrowserSettingsBuilder {
+        Default::default()
+    }
+}
+
+impl BrowserSettingsBuilder {
+    setters! {
+        standard_font_family: string,
+        fixed_font_family: string,
+        serif_font_family: string,
+        sans_serif_font_family: string,
+        cursive_font_family: string,
+        fantasy_font_family: string,
+        default_encoding: string,
+        remote_fonts: state,
+        javascript: state,
+        javascript_close_windows: state,
+        javascript_access_clipboard: state,
+        javascript_dom_paste: state,
+        image_loading: state,
+        image_shrink_standalone_to_fit: state,
+        text_area_resize: state,
+        tab_to_links: state,
+        local_storage: state,
+        databases: state,
+        webgl: state,
+        chrome_status_bubble: state,
+        chrome_zoom_bubble: state,
+    }
+
+    /// Frame rate for windowless rendering, must be in [WINDOWLESS_FRAME_RATE]. Defaults to 30
+    /// if unset.
+    pub fn windowless_frame_rate(mut self, value: u32) -> Self {
+        self.windowless_frame_rate = Some(value);
+        self
+    }
+
+    pub fn default_font_size(mut self, value: u32) -> Self {
+        self.default_font_size = Some(value);
+        self
+    }
+
+    pub fn default_fixed_font_size(mut self, value: u32) -> Self {
+        self.default_fixed_font_size = Some(value);
+        self
+    }
+
+    pub fn minimum_font_size(mut self, value: u32) -> Self {
+        self.minimum_font_size = Some(value);
+        self
+    }
+
+    pub fn minimum_logical_font_size(mut self, value: u32) -> Self {
+        self.minimum_logical_font_size = Some(value);
+        self
+    }
+
+    /// Defaults to [SettingsBuilder::background_color] if unset.
+    pub fn background_color(mut self, value: Color) -> Self {
+        self.background_color = Some(value);
+        self
+    }
+
+    /// Validate the configuration and convert it to [BrowserSettings].
+    pub fn build(&self) -> Result<BrowserSettings> {
+        if let Some(frame_rate) = self.windowless_frame_rate {
+            if !WINDOWLESS_FRAME_RATE.contains(&frame_rate) {
+                return Err(invalid(
+                    "windowless_frame_rate",
+                    format!("{frame_rate} is not in {WINDOWLESS_FRAME_RATE:?}"),
+                ));
+            }
+        }
+
+        Ok(BrowserSettings {
+            windowless_frame_rate: int("windowless_frame_rate", self.windowless_frame_rate)?,
+            standard_font_family: string(&self.standard_font_family),
+            fixed_font_family: string(&self.fixed_font_family),
+            serif_font_family: string(&self.serif_font_family),
+            sans_serif_font_family: string(&self.sans_serif_font_family),
+            cursive_font_family: string(&self.cursive_font_family),
+            fantasy_font_family: string(&self.fantasy_font_family),
+            default_font_size: int("default_font_size", self.default_font_size)?,
+            default_fixed_font_size: int("default_fixed_font_size", self.default_fixed_font_size)?,
+            minimum_font_size: int("minimum_font_size", self.minimum_font_size)?,
+            minimum_logical_font_size: int(
+                "minimum_logical_font_size",
+                self.minimum_logical_font_size,
+            )?,
+            default_encoding: string(&self.default_encoding),
+            remote_fonts: state(self.remote_fonts),
+            javascript: state(self.javascript),
+            javascript_close_windows: state(self.javascript_close_windows),
+            javascript_access_clipboard: state(self.javascript_access_clipboard),
+            javascript_dom_paste: state(self.javascript_dom_paste),
+            image_loading: state(self.image_loading),
+            image_shrink_standalone_to_fit: state(self.image_shrink_standalone_to_fit),
+            text_area_resize: state(self.text_area_resize),
+            tab_to_links: state(self.tab_to_links),
+            local_storage: state(self.local_storage),
+            databases: state(self.databases),
+            webgl: state(self.webgl),
+            background_color: self.background_color.unwrap_or_default(),
+            chrome_status_bubble: state(self.chrome_status_bubble),
+            chrome_zoom_bubble: state(self.chrome_zoom_bubble),
+            ..Default::default()
+        })
+    }
+}
+
+/// Builder for [RequestContextSettings], see [RequestContextSettings::builder].
+#[derive(Debug, Clone, Default)]
+pub struct RequestContextSettingsBuilder {
+    cache_path: Option<PathBuf>,
+    persist_session_cookies: bool,
+    accept_language_list: Option<String>,
+    cookieable_schemes_list: Option<String>,
+    cookieable_schemes_exclude_defaults: bool,
+}
+
+impl RequestContextSettings {
+    pub fn builder() -> RequestContextSettingsBuilder {
+        Default::default()
+    }
+}
+
+impl RequestContextSettingsBuilder {
+    setters! {
+        cache_path: path,
+        persist_session_cookies: bool,
+        accept_language_list: string,
+        cookieable_schemes_list: string,
+        cookieable_schemes_exclude_defaults: bool,
+    }
+
+    /// Validate the configuration and convert it to [RequestContextSettings]. `cache_path` must
+    /// be absolute, an unset `cache_path` creates an incognito context.
+    pub fn build(&self) -> Result<RequestContextSettings> {
+        check_absolute("cache_path", &self.cache_path)?;
+
+        Ok(RequestContextSettings {
+            cache_path: path(&self.cache_path),
+            persist_session_cookies: self.persist_session_cookies.into(),
+            accept_language_list: string(&self.accept_language_list),
+            cookieable_schemes_list: string(&self.cookieable_schemes_list),
+            cookieable_schemes_exclude_defaults: self.cookieable_schemes_exclude_defaults.into(),
+            ..Default::default()
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn windowless_frame_rate_range() {
+        let err = BrowserSettings::builder()
+            .windowless_frame_rate(90000)
+            .build()
+            .err();
+        assert!(matches!(
+            err,
+            Some(Error::InvalidSettings {
+                field: "windowless_frame_rate",
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn cache_path_inside_root_cache_path() {
+        let root = std::env::temp_dir().join("cef");
+        let err = Settings::builder()
+            .root_cache_path(&root)
+            .cache_path(std::env::temp_dir().join("other"))
+            .build()
+            .err();
+        assert!(matches!(
+            err,
+            Some(Error::InvalidSettings {
+                field: "cache_path",
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn int_range() {
+        let err = BrowserSettings::builder()
+            .default_font_size(u32::MAX)
+            .build()
+            .err();
+        assert!(matches!(
+            err,
+            Some(Error::InvalidSettings {
+                field: "default_font_size",
+                ..
+            })
+        ));
+        let err = Settings::builder()
+            .uncaught_exception_stack_size(c_int::MAX as u32 + 1)
+            .build()
+            .err();
+        assert!(matches!(
+            err,
+            Some(Error::InvalidSettings {
+                field: "uncaught_exception_stack_size",
+                ..
+            })
+        ));
+    }
+}
@@ -0,0 +1,87 @@
+//! Post Rust closures to CEF threads without declaring a [Task] type for each one.
+
+use std::{sync::Mutex, time::Duration};
+
+use crate::{rc::lock, *};
+
+/// Post a closure for execution on the specified thread. Returns `false` if the task could not
+/// be posted, e.g. because the thread has already shut down.
+///
+/// ```no_run
+/// use cef::ThreadId;
+///
+/// cef::task::post(ThreadId::Ui, move || {
+///     cef::assert_on_thread!(ThreadId::Ui);
+/// });
+/// ```
+pub fn post(thread_id: ThreadId, f: impl FnOnce() + Send + 'static) -> bool {
+    let mut task = new_task(f);
+    post_task(thread_id, Some(&mut task))
+}
+
+/// Post a closure for delayed execution on the specified thread. Returns `false` if the task
+/// could not be posted.
+pub fn post_delayed(
+    thread_id: ThreadId,
+    delay: Duration,
+    f: impl FnOnce() + Send + 'static,
+) -> bool {
+    let mut task = new_task(f);
+    post_delayed_task(thread_id, Some(&mut task), delay_ms(delay))
+}
+
+impl TaskRunner {
+    /// Post a closure for execution on the thread associated with this task runner. Returns
+    /// `false` if the task could not be posted.
+    pub fn post_fn(&self, f: impl FnOnce() + Send + 'static) -> bool {
+        let mut task = new_task(f);
+        self.post_task(Some(&mut task))
+    }
+
+    /// Post a closure for delayed execution on the thread associated with this task runner.
+    /// Returns `false` if the task could not be posted.
+    pub fn post_delayed_fn(&self, delay: Duration, f: impl FnOnce() + Send + 'static) -> bool {
+        let mut task = new_task(f);
+        self.post_delayed_task(Some(&mut task), delay_ms(delay))
+    }
+}
+
+/// Panic if the current thread is not the specified CEF thread.
+///
+/// ```no_run
+/// use cef::ThreadId;
+///
+/// cef::assert_on_thread!(ThreadId::Io, "cookies must be read on IO");
+/// ```
+#[macro_export]
+macro_rules! assert_on_thread {
+    ($thread_id:expr $(,)?) => {{
+        let thread_id: $crate::ThreadId = $thread_id;
+        assert!(
+            $crate::currently_on(thread_id),
+            "expected to be running on CEF thread {thread_id:?}"
+        );
+    }};
+    ($thread_id:expr, $($arg:tt)+) => {{
+        let thread_id: $crate::ThreadId = $thread_id;
+        assert!($crate::currently_on(thread_id), $($arg)+);
+    }};
+}
+
+/// Wrap a [FnOnce] in a [Task]. CEF only executes a task once, but the closure is still
+/// guarded so that a second call through the [ImplTask] interface does nothing.
+fn new_task(f: impl FnOnce() + Send + 'static) -> Task {
+    let f = Mutex::new(Some(f));
+    Task::builder()
+        .execute(move || {
+            let f = lock(&f).take();
+            if let Some(f) = f {
+                f();
+            }
+        })
+        .build()
+}
+
+fn delay_ms(delay: Duration) -> i64 {
+    i64::try_from(delay.as_millis()).unwrap_or(i64::MAX)
+}
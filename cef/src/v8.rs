@@ -0,0 +1,738 @@
+//! Expose Rust functions to JavaScript in the renderer process.
+//!
+//! [bind] installs a function at a dotted path on the global object of a [V8Context], creating
+//! any missing intermediate objects. Arguments are converted from [V8Value] with [FromArgs],
+//! the return value is converted back with [IntoV8]. If the conversion or the function itself
+//! fails, the error message is thrown as a JavaScript exception.
+//!
+//! Numbers, strings, booleans, arrays ([Vec]), objects ([HashMap] and [BTreeMap]) and
+//! `ArrayBuffer`s ([ArrayBuffer]) are converted automatically, `null` and `undefined` map to
+//! [None]. With the `serde` feature [serde_json::Value] can be used for dynamically typed
+//! values.
+//!
+//! ```no_run
+//! use cef::*;
+//!
+//! fn on_context_created(context: &V8Context) -> Result<(), v8::Error> {
+//!     v8::bind(context, "petplay.sendPose", |(x, y): (f64, f64), _| {
+//!         if x.is_finite() && y.is_finite() {
+//!             Ok(format!("{x},{y}"))
+//!         } else {
+//!             Err("pose must be finite")
+//!         }
+//!     })?;
+//!     Ok(())
+//! }
+//! ```
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::{self, Display, Formatter},
+    slice,
+    sync::Arc,
+};
+
+use crate::{rc::ConvertReturnValue, *};
+
+/// Failed to convert between a [V8Value] and a Rust type, or to install a function.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Error(String);
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl Error {
+    fn expected(what: &str) -> Self {
+        Self(format!("expected {what}"))
+    }
+}
+
+/// Convert a [V8Value] to a Rust type.
+pub trait FromV8: Sized {
+    fn from_v8(value: &V8Value) -> Result<Self, Error>;
+
+    /// Convert a missing argument. Only types which accept `undefined` should override this.
+    fn from_missing() -> Result<Self, Error> {
+        Err(Error("missing".to_string()))
+    }
+}
+
+/// Convert a Rust type to a new [V8Value]. Must be called while a [V8Context] is entered.
+pub trait IntoV8 {
+    fn into_v8(self) -> Result<V8Value, Error>;
+}
+
+/// Convert the arguments of a JavaScript function call, implemented for tuples of [FromV8]
+/// types. Missing arguments are converted with [FromV8::from_missing], extra arguments are
+/// ignored.
+pub trait FromArgs: Sized {
+    fn from_args(args: &[Option<V8Value>]) -> Result<Self, Error>;
+}
+
+/// The contents of a JavaScript `ArrayBuffer`, which is copied in both directions.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ArrayBuffer(pub Vec<u8>);
+
+impl From<Vec<u8>> for ArrayBuffer {
+    fn from(value: Vec<u8>) -> Self {
+        Self(value)
+    }
+}
+
+impl From<ArrayBuffer> for Vec<u8> {
+    fn from(value: ArrayBuffer) -> Self {
+        value.0
+    }
+}
+
+/// Information about the current call which is passed to a bound function.
+pub struct CallContext {
+    name: String,
+    this: Option<V8Value>,
+}
+
+impl CallContext {
+    /// The name of the function which was called, the last segment of the bound path.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The `this` object of the call.
+    pub fn this(&self) -> Option<&V8Value> {
+        self.this.as_ref()
+    }
+
+    /// The [V8Context] the function is running in.
+    pub fn context(&self) -> Option<V8Context> {
+        v8_context_get_current_context()
+    }
+}
+
+/// Install `f` as a JavaScript function at `path` in `context`, e.g. `"petplay.sendPose"`
+/// creates `window.petplay` if it doesn't exist yet and sets its `sendPose` property. Returns
+/// the function object.
+///
+/// If `f` returns an error, its message is thrown as an exception in JavaScript.
+pub fn bind<A, R, E, F>(context: &impl ImplV8Context, path: &str, f: F) -> Result<V8Value, Error>
+where
+    A: FromArgs,
+    R: IntoV8,
+    E: Display,
+    F: Fn(A, &CallContext) -> Result<R, E> + 'static,
+{
+    let (parents, name) = split_path(path)?;
+    if !context.enter() {
+        return Err(Error("cannot enter V8 context".to_string()));
+    }
+    let result = (|| {
+        let mut object = context
+            .get_global()
+            .ok_or_else(|| Error("cannot get global object".to_string()))?;
+        for key in parents {
+            let key = CefString::from(key);
+            object = match object
+                .get_value_bykey(Some(&key))
+                .filter(|value| value.is_object())
+            {
+                Some(value) => value,
+                None => {
+                    let mut value = new_object()?;
+                    set(&object, &key, &mut value)?;
+                    value
+                }
+            };
+        }
+
+        let mut handler = Handler::new(Arc::new(
+            move |args: &[Option<V8Value>], call: &CallContext| {
+                let args = A::from_args(args)?;
+                f(args, call)
+                    .map_err(|err| Error(err.to_string()))?
+                    .into_v8()
+            },
+        ));
+        let name = CefString::from(name);
+        let mut function = v8_value_create_function(Some(&name), Some(&mut handler))
+            .ok_or_else(|| Error("cannot create function".to_string()))?;
+        set(&object, &name, &mut function)?;
+        Ok(function)
+    })();
+    context.exit();
+    result
+}
+
+/// Split a dotted path into the keys of the parent objects and the function name.
+fn split_path(path: &str) -> Result<(Vec<&str>, &str), Error> {
+    let mut keys = path.split('.').collect::<Vec<_>>();
+    let name = keys.pop().unwrap_or_default();
+    if name.is_empty() || keys.iter().any(|key| key.is_empty()) {
+        return Err(Error(format!("invalid path {path:?}")));
+    }
+    Ok((keys, name))
+}
+
+fn new_object() -> Result<V8Value, Error> {
+    v8_value_create_object(None::<&mut V8Accessor>, None::<&mut V8Interceptor>)
+        .ok_or_else(|| Error("cannot create object".to_string()))
+}
+
+fn set(object: &V8Value, key: &CefString, value: &mut V8Value) -> Result<(), Error> {
+    if object.set_value_bykey(Some(key), Some(value), V8Propertyattribute::NONE) {
+        Ok(())
+    } else {
+        Err(Error(format!("cannot set property {key}")))
+    }
+}
+
+fn undefined() -> Result<V8Value, Error> {
+    v8_value_create_undefined().ok_or_else(|| Error("cannot create undefined".to_string()))
+}
+
+fn to_v8_value(value: &mut impl ImplV8Value) -> V8Value {
+    unsafe {
+        value.add_ref();
+        value.get_raw().wrap_result()
+    }
+}
+
+type Callback = dyn Fn(&[Option<V8Value>], &CallContext) -> Result<V8Value, Error>;
+
+#[wrap(V8Handler)]
+struct Handler {
+    callback: Arc<Callback>,
+}
+
+#[wrap]
+impl ImplV8Handler for Handler {
+    fn execute(
+        &self,
+        name: Option<&CefString>,
+        object: Option<&mut impl ImplV8Value>,
+        arguments: Option<&[Option<impl ImplV8Value>]>,
+        retval: Option<&mut Option<V8Value>>,
+        exception: Option<&mut CefString>,
+    ) -> bool {
+        let call = CallContext {
+            name: name.map(ToString::to_string).unwrap_or_default(),
+            this: object.map(to_v8_value),
+        };
+        let args = arguments
+            .unwrap_or_default()
+            .iter()
+            .map(|arg| {
+                arg.as_ref().map(|arg| unsafe {
+                    arg.add_ref();
+                    arg.get_raw().wrap_result()
+                })
+            })
+            .collect::<Vec<_>>();
+
+        match (self.callback)(&args, &call) {
+            Ok(value) => {
+                if let Some(retval) = retval {
+                    *retval = Some(value);
+                }
+            }
+            Err(err) => {
+                if let Some(exception) = exception {
+                    exception.try_set(&err.to_string());
+                }
+            }
+        }
+        true
+    }
+}
+
+impl FromV8 for V8Value {
+    fn from_v8(value: &V8Value) -> Result<Self, Error> {
+        Ok(value.clone())
+    }
+
+    fn from_missing() -> Result<Self, Error> {
+        undefined()
+    }
+}
+
+impl IntoV8 for V8Value {
+    fn into_v8(self) -> Result<V8Value, Error> {
+        Ok(self)
+    }
+}
+
+impl FromV8 for () {
+    fn from_v8(_: &V8Value) -> Result<Self, Error> {
+        Ok(())
+    }
+
+    fn from_missing() -> Result<Self, Error> {
+        Ok(())
+    }
+}
+
+impl IntoV8 for () {
+    fn into_v8(self) -> Result<V8Value, Error> {
+        undefined()
+    }
+}
+
+impl FromV8 for bool {
+    fn from_v8(value: &V8Value) -> Result<Self, Error> {
+        if value.is_bool() {
+            Ok(value.get_bool_value())
+        } else {
+            Err(Error::expected("a boolean"))
+        }
+    }
+}
+
+impl IntoV8 for bool {
+    fn into_v8(self) -> Result<V8Value, Error> {
+        v8_value_create_bool(self.into()).ok_or_else(|| Error("cannot create boolean".to_string()))
+    }
+}
+
+fn number(value: &V8Value) -> Result<f64, Error> {
+    if value.is_int() || value.is_uint() || value.is_double() {
+        Ok(value.get_double_value())
+    } else {
+        Err(Error::expected("a number"))
+    }
+}
+
+impl FromV8 for f64 {
+    fn from_v8(value: &V8Value) -> Result<Self, Error> {
+        number(value)
+    }
+}
+
+impl IntoV8 for f64 {
+    fn into_v8(self) -> Result<V8Value, Error> {
+        v8_value_create_double(self).ok_or_else(|| Error("cannot create number".to_string()))
+    }
+}
+
+impl FromV8 for f32 {
+    fn from_v8(value: &V8Value) -> Result<Self, Error> {
+        number(value).map(|value| value as f32)
+    }
+}
+
+impl IntoV8 for f32 {
+    fn into_v8(self) -> Result<V8Value, Error> {
+        f64::from(self).into_v8()
+    }
+}
+
+/// `number` as an `i32`, if it is integral and in range.
+fn to_i32(number: f64) -> Option<i32> {
+    (number.fract() == 0.0 && (f64::from(i32::MIN)..=f64::from(i32::MAX)).contains(&number))
+        .then_some(number as i32)
+}
+
+/// `number` as a `u32`, if it is integral and in range.
+fn to_u32(number: f64) -> Option<u32> {
+    (number.fract() == 0.0 && (0.0..=f64::from(u32::MAX)).contains(&number))
+        .then_some(number as u32)
+}
+
+impl FromV8 for i32 {
+    fn from_v8(value: &V8Value) -> Result<Self, Error> {
+        if value.is_int() {
+            return Ok(value.get_int_value());
+        }
+        to_i32(number(value)?).ok_or_else(|| Error::expected("a 32-bit integer"))
+    }
+}
+
+impl IntoV8 for i32 {
+    fn into_v8(self) -> Result<V8Value, Error> {
+        v8_value_create_int(self).ok_or_else(|| Error("cannot create number".to_string()))
+    }
+}
+
+impl FromV8 for u32 {
+    fn from_v8(value: &V8Value) -> Result<Self, Error> {
+        if value.is_uint() {
+            return Ok(value.get_uint_value());
+        }
+        to_u32(number(value)?).ok_or_else(|| Error::expected("an unsigned 32-bit integer"))
+    }
+}
+
+impl IntoV8 for u32 {
+    fn into_v8(self) -> Result<V8Value, Error> {
+        v8_value_create_uint(self).ok_or_else(|| Error("cannot create number".to_string()))
+    }
+}
+
+impl FromV8 for String {
+    fn from_v8(value: &V8Value) -> Result<Self, Error> {
+        if value.is_string() {
+            Ok(CefString::from(&value.get_string_value()).to_string())
+        } else {
+            Err(Error::expected("a string"))
+        }
+    }
+}
+
+impl IntoV8 for String {
+    fn into_v8(self) -> Result<V8Value, Error> {
+        self.as_str().into_v8()
+    }
+}
+
+impl IntoV8 for &str {
+    fn into_v8(self) -> Result<V8Value, Error> {
+        v8_value_create_string(Some(&CefString::from(self)))
+            .ok_or_else(|| Error("cannot create string".to_string()))
+    }
+}
+
+impl<T: FromV8> FromV8 for Option<T> {
+    fn from_v8(value: &V8Value) -> Result<Self, Error> {
+        if value.is_null() || value.is_undefined() {
+            Ok(None)
+        } else {
+            T::from_v8(value).map(Some)
+        }
+    }
+
+    fn from_missing() -> Result<Self, Error> {
+        Ok(None)
+    }
+}
+
+impl<T: IntoV8> IntoV8 for Option<T> {
+    fn into_v8(self) -> Result<V8Value, Error> {
+        match self {
+            Some(value) => value.into_v8(),
+            None => v8_value_create_null().ok_or_else(|| Error("cannot create null".to_string())),
+        }
+    }
+}
+
+impl<T: FromV8> FromV8 for Vec<T> {
+    fn from_v8(value: &V8Value) -> Result<Self, Error> {
+        if !value.is_array() {
+            return Err(Error::expected("an array"));
+        }
+        (0..value.get_array_length())
+            .map(|index| {
+                let item = value
+                    .get_value_byindex(index)
+                    .ok_or_else(|| Error(format!("cannot get array item {index}")))?;
+                T::from_v8(&item).map_err(|err| Error(format!("[{index}]: {err}")))
+            })
+            .collect()
+    }
+}
+
+impl<T: IntoV8> IntoV8 for Vec<T> {
+    fn into_v8(self) -> Result<V8Value, Error> {
+        let length = i32::try_from(self.len()).map_err(|_| Error("array too long".to_string()))?;
+        let array = v8_value_create_array(length)
+            .ok_or_else(|| Error("cannot create array".to_string()))?;
+        for (index, item) in (0..length).zip(self) {
+            let mut item = item.into_v8()?;
+            if !array.set_value_byindex(index, Some(&mut item)) {
+                return Err(Error(format!("cannot set array item {index}")));
+            }
+        }
+        Ok(array)
+    }
+}
+
+fn entries<T: FromV8>(
+    value: &V8Value,
+) -> Result<impl Iterator<Item = Result<(String, T), Error>> + '_, Error> {
+    if !value.is_object() || value.is_array() || value.is_function() {
+        return Err(Error::expected("an object"));
+    }
+    let mut keys = CefStringList::new();
+    value.get_keys(Some(&mut keys));
+    Ok(keys.into_iter().map(|key| {
+        let item = value
+            .get_value_bykey(Some(&CefString::from(key.as_str())))
+            .ok_or_else(|| Error(format!("cannot get property {key}")))?;
+        let item = T::from_v8(&item).map_err(|err| Error(format!("{key}: {err}")))?;
+        Ok((key, item))
+    }))
+}
+
+fn object<T: IntoV8>(entries: impl IntoIterator<Item = (String, T)>) -> Result<V8Value, Error> {
+    let object = new_object()?;
+    for (key, value) in entries {
+        set(
+            &object,
+            &CefString::from(key.as_str()),
+            &mut value.into_v8()?,
+        )?;
+    }
+    Ok(object)
+}
+
+impl<T: FromV8> FromV8 for HashMap<String, T> {
+    fn from_v8(value: &V8Value) -> Result<Self, Error> {
+        entries(value)?.collect()
+    }
+}
+
+impl<T: IntoV8> IntoV8 for HashMap<String, T> {
+    fn into_v8(self) -> Result<V8Value, Error> {
+        object(self)
+    }
+}
+
+impl<T: FromV8> FromV8 for BTreeMap<String, T> {
+    fn from_v8(value: &V8Value) -> Result<Self, Error> {
+        entries(value)?.collect()
+    }
+}
+
+impl<T: IntoV8> IntoV8 for BTreeMap<String, T> {
+    fn into_v8(self) -> Result<V8Value, Error> {
+        object(self)
+    }
+}
+
+impl FromV8 for ArrayBuffer {
+    fn from_v8(value: &V8Value) -> Result<Self, Error> {
+        if !value.is_array_buffer() {
+            return Err(Error::expected("an ArrayBuffer"));
+        }
+        let length = value.get_array_buffer_byte_length();
+        let data = value.get_array_buffer_data();
+        if length == 0 || data.is_null() {
+            return Ok(Self::default());
+        }
+        Ok(Self(
+            unsafe { slice::from_raw_parts(data.cast::<u8>(), length) }.to_vec(),
+        ))
+    }
+}
+
+impl IntoV8 for ArrayBuffer {
+    fn into_v8(mut self) -> Result<V8Value, Error> {
+        v8_value_create_array_buffer_with_copy(self.0.as_mut_ptr(), self.0.len())
+            .ok_or_else(|| Error("cannot create ArrayBuffer".to_string()))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl FromV8 for serde_json::Value {
+    fn from_v8(value: &V8Value) -> Result<Self, Error> {
+        use serde_json::{Number, Value};
+
+        if value.is_null() || value.is_undefined() {
+            Ok(Value::Null)
+        } else if value.is_bool() {
+            Ok(Value::Bool(value.get_bool_value()))
+        } else if value.is_int() {
+            Ok(Value::from(value.get_int_value()))
+        } else if value.is_uint() {
+            Ok(Value::from(value.get_uint_value()))
+        } else if value.is_double() {
+            Ok(Number::from_f64(value.get_double_value())
+                .map(Value::Number)
+                .unwrap_or(Value::Null))
+        } else if value.is_string() {
+            String::from_v8(value).map(Value::String)
+        } else if value.is_array() {
+            Vec::from_v8(value).map(Value::Array)
+        } else if value.is_array_buffer() {
+            ArrayBuffer::from_v8(value).map(|buffer| Value::from(buffer.0))
+        } else {
+            entries(value)?.collect::<Result<_, _>>().map(Value::Object)
+        }
+    }
+
+    fn from_missing() -> Result<Self, Error> {
+        Ok(serde_json::Value::Null)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl IntoV8 for serde_json::Value {
+    fn into_v8(self) -> Result<V8Value, Error> {
+        use serde_json::Value;
+
+        match self {
+            Value::Null => None::<()>.into_v8(),
+            Value::Bool(value) => value.into_v8(),
+            Value::Number(value) => match value.as_i64().map(i32::try_from) {
+                Some(Ok(value)) => value.into_v8(),
+                _ => value.as_f64().unwrap_or(f64::NAN).into_v8(),
+            },
+            Value::String(value) => value.into_v8(),
+            Value::Array(value) => value.into_v8(),
+            Value::Object(value) => object(value),
+        }
+    }
+}
+
+/// Convert the argument at `index`, naming it in the error message.
+fn arg<T: FromV8>(args: &[Option<V8Value>], index: usize) -> Result<T, Error> {
+    match args.get(index) {
+        Some(Some(value)) => T::from_v8(value),
+        _ => T::from_missing(),
+    }
+    .map_err(|err| Error(format!("argument {}: {err}", index + 1)))
+}
+
+macro_rules! impl_from_args {
+    ($($name:ident: $index:tt),*) => {
+        impl<$($name: FromV8),*> FromArgs for ($($name,)*) {
+            #[allow(unused_variables)]
+            fn from_args(args: &[Option<V8Value>]) -> Result<Self, Error> {
+                Ok(($(arg::<$name>(args, $index)?,)*))
+            }
+        }
+    };
+}
+
+impl_from_args!();
+impl_from_args!(A: 0);
+impl_from_args!(A: 0, B: 1);
+impl_from_args!(A: 0, B: 1, C: 2);
+impl_from_args!(A: 0, B: 1, C: 2, D: 3);
+impl_from_args!(A: 0, B: 1, C: 2, D: 3, E: 4);
+impl_from_args!(A: 0, B: 1, C: 2, D: 3, E: 4, F: 5);
+impl_from_args!(A: 0, B: 1, C: 2, D: 3, E: 4, F: 5, G: 6);
+impl_from_args!(A: 0, B: 1, C: 2, D: 3, E: 4, F: 5, G: 6, H: 7);
+
+impl FromArgs for Vec<V8Value> {
+    fn from_args(args: &[Option<V8Value>]) -> Result<Self, Error> {
+        (0..args.len()).map(|index| arg(args, index)).collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn error(message: &str) -> Error {
+        Error(message.to_string())
+    }
+
+    #[test]
+    fn splits_paths() {
+        assert_eq!(split_path("send").unwrap(), (vec![], "send"));
+        assert_eq!(
+            split_path("petplay.pose.send").unwrap(),
+            (vec!["petplay", "pose"], "send")
+        );
+        for path in ["", "petplay.", ".send", "petplay..send"] {
+            assert_eq!(
+                split_path(path),
+                Err(Error(format!("invalid path {path:?}")))
+            );
+        }
+    }
+
+    #[test]
+    fn converts_integers() {
+        assert_eq!(to_i32(-2147483648.0), Some(i32::MIN));
+        assert_eq!(to_i32(2147483648.0), None);
+        assert_eq!(to_i32(0.5), None);
+        assert_eq!(to_i32(f64::NAN), None);
+        assert_eq!(to_u32(4294967295.0), Some(u32::MAX));
+        assert_eq!(to_u32(-1.0), None);
+        assert_eq!(to_u32(f64::INFINITY), None);
+    }
+
+    #[test]
+    fn counts_arguments() {
+        // Extra arguments are ignored, missing ones are only accepted by optional types.
+        assert_eq!(<()>::from_args(&[None, None]), Ok(()));
+        assert_eq!(
+            <(Option<i32>, Option<String>)>::from_args(&[]),
+            Ok((None, None))
+        );
+        assert_eq!(<((),)>::from_args(&[]), Ok(((),)));
+        assert!(Vec::<V8Value>::from_args(&[]).unwrap().is_empty());
+
+        assert_eq!(<(i32,)>::from_args(&[]), Err(error("argument 1: missing")));
+        // A `None` argument is treated as missing.
+        assert_eq!(
+            <(Option<bool>, String)>::from_args(&[None]),
+            Err(error("argument 2: missing"))
+        );
+        assert_eq!(
+            <(Option<bool>, Option<bool>, Vec<i32>)>::from_args(&[None, None]),
+            Err(error("argument 3: missing"))
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn missing_json_is_null() {
+        assert_eq!(
+            <(serde_json::Value,)>::from_args(&[]),
+            Ok((serde_json::Value::Null,))
+        );
+    }
+
+    // The tests below create V8 values, so they need libcef and an entered V8 context in the
+    // renderer process.
+
+    #[test]
+    #[ignore = "needs a V8 context"]
+    fn round_trip() {
+        let value = 7.into_v8().unwrap();
+        assert_eq!(i32::from_v8(&value), Ok(7));
+        assert_eq!(f64::from_v8(&value), Ok(7.0));
+        assert_eq!(u32::from_v8(&value), Ok(7));
+
+        let value = vec!["a".to_string(), "b".to_string()].into_v8().unwrap();
+        assert_eq!(
+            Vec::<String>::from_v8(&value),
+            Ok(vec!["a".to_string(), "b".to_string()])
+        );
+
+        let map = BTreeMap::from([("x".to_string(), 0.5)]);
+        assert_eq!(BTreeMap::from_v8(&map.clone().into_v8().unwrap()), Ok(map));
+
+        let buffer = ArrayBuffer(vec![1, 2, 3]);
+        assert_eq!(
+            ArrayBuffer::from_v8(&buffer.clone().into_v8().unwrap()),
+            Ok(buffer)
+        );
+        assert_eq!(
+            Option::<bool>::from_v8(&None::<bool>.into_v8().unwrap()),
+            Ok(None)
+        );
+    }
+
+    #[test]
+    #[ignore = "needs a V8 context"]
+    fn rejects_mismatched_types() {
+        let text = "text".into_v8().unwrap();
+        assert_eq!(bool::from_v8(&text), Err(error("expected a boolean")));
+        assert_eq!(f64::from_v8(&text), Err(error("expected a number")));
+        assert_eq!(Vec::<i32>::from_v8(&text), Err(error("expected an array")));
+        assert_eq!(
+            HashMap::<String, i32>::from_v8(&text),
+            Err(error("expected an object"))
+        );
+        assert_eq!(
+            i32::from_v8(&0.5.into_v8().unwrap()),
+            Err(error("expected a 32-bit integer"))
+        );
+        assert_eq!(
+            Vec::<i32>::from_v8(&vec![Some(1), None].into_v8().unwrap()),
+            Err(error("[1]: expected a number"))
+        );
+
+        let args = [Some(1.into_v8().unwrap()), Some(text)];
+        assert_eq!(
+            <(i32, i32)>::from_args(&args),
+            Err(error("argument 2: expected a number"))
+        );
+        assert_eq!(<(i32,)>::from_args(&args), Ok((1,)));
+    }
+}
@@ -0,0 +1,1034 @@
+//! [serde] support for [Value], [DictionaryValue] and [ListValue], enabled with the `serde`
+//! feature.
+//!
+//! [to_value] and [from_value] convert any [Serialize]/[Deserialize] type, and also implement
+//! `TryFrom<serde_json::Value> for Value` and `TryFrom<&Value> for serde_json::Value`.
+//!
+//! CEF values only have 32-bit integers, so larger integers are stored as doubles. Doubles with
+//! an integral value are read back as integers, so they still deserialize into integer fields.
+//! Enums use the externally tagged representation, like [serde_json].
+//!
+//! ```no_run
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Message {
+//!     id: u32,
+//!     text: String,
+//! }
+//!
+//! # fn main() -> Result<(), cef::value::Error> {
+//! let message = Message { id: 1, text: "hello".to_string() };
+//! let dictionary = cef::value::to_dictionary(&message)?;
+//! let message: Message = cef::value::from_dictionary(&dictionary)?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::fmt::{self, Display, Formatter};
+
+use serde::{
+    de::{self, DeserializeSeed, IntoDeserializer, MapAccess, SeqAccess, Visitor},
+    ser::{self, Impossible, Serialize},
+    Deserialize,
+};
+
+use crate::*;
+
+/// Failed to convert between a CEF value and a Rust type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Error(String);
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl ser::Error for Error {
+    fn custom<T: Display>(msg: T) -> Self {
+        Self(msg.to_string())
+    }
+}
+
+impl de::Error for Error {
+    fn custom<T: Display>(msg: T) -> Self {
+        Self(msg.to_string())
+    }
+}
+
+/// Serialize `value` to a new [Value].
+pub fn to_value<T: Serialize + ?Sized>(value: &T) -> Result<Value, Error> {
+    value.serialize(Serializer)
+}
+
+/// Serialize `value` to a new [DictionaryValue]. Fails if `value` doesn't serialize to a map or
+/// struct.
+pub fn to_dictionary<T: Serialize + ?Sized>(value: &T) -> Result<DictionaryValue, Error> {
+    to_value(value)?
+        .get_dictionary()
+        .and_then(|dictionary| dictionary.copy(false))
+        .ok_or_else(|| Error("expected a dictionary".to_string()))
+}
+
+/// Serialize `value` to a new [ListValue]. Fails if `value` doesn't serialize to a sequence.
+pub fn to_list<T: Serialize + ?Sized>(value: &T) -> Result<ListValue, Error> {
+    to_value(value)?
+        .get_list()
+        .and_then(|list| list.copy())
+        .ok_or_else(|| Error("expected a list".to_string()))
+}
+
+/// Deserialize an instance of `T` from a [Value].
+pub fn from_value<'de, T: Deserialize<'de>>(value: &Value) -> Result<T, Error> {
+    T::deserialize(Deserializer::from(value.clone()))
+}
+
+/// Deserialize an instance of `T` from a [DictionaryValue].
+pub fn from_dictionary<'de, T: Deserialize<'de>>(dictionary: &DictionaryValue) -> Result<T, Error> {
+    T::deserialize(Deserializer::from(dictionary.clone()))
+}
+
+/// Deserialize an instance of `T` from a [ListValue].
+pub fn from_list<'de, T: Deserialize<'de>>(list: &ListValue) -> Result<T, Error> {
+    T::deserialize(Deserializer::from(list.clone()))
+}
+
+impl TryFrom<serde_json::Value> for Value {
+    type Error = Error;
+
+    fn try_from(value: serde_json::Value) -> Result<Self, Self::Error> {
+        to_value(&value)
+    }
+}
+
+impl TryFrom<&Value> for serde_json::Value {
+    type Error = Error;
+
+    fn try_from(value: &Value) -> Result<Self, Self::Error> {
+        from_value(value)
+    }
+}
+
+/// A number as it is stored in a CEF value.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Number {
+    Int(i32),
+    Double(f64),
+}
+
+impl From<i64> for Number {
+    fn from(v: i64) -> Self {
+        match i32::try_from(v) {
+            Ok(v) => Self::Int(v),
+            Err(_) => Self::Double(v as f64),
+        }
+    }
+}
+
+impl From<u64> for Number {
+    fn from(v: u64) -> Self {
+        match i32::try_from(v) {
+            Ok(v) => Self::Int(v),
+            Err(_) => Self::Double(v as f64),
+        }
+    }
+}
+
+/// Doubles in this range are read back as integers.
+const MAX_SAFE_INTEGER: f64 = 9007199254740991.0;
+
+/// The integral value of a double, if it is read back as an integer.
+fn integral(v: f64) -> Option<i64> {
+    (v.fract() == 0.0 && v.abs() <= MAX_SAFE_INTEGER).then_some(v as i64)
+}
+
+fn new_value() -> Result<Value, Error> {
+    value_create().ok_or_else(|| Error("cannot create CEF value".to_string()))
+}
+
+fn check(result: bool) -> Result<(), Error> {
+    if result {
+        Ok(())
+    } else {
+        Err(Error("cannot set CEF value".to_string()))
+    }
+}
+
+fn dictionary_to_value(mut dictionary: DictionaryValue) -> Result<Value, Error> {
+    let value = new_value()?;
+    check(value.set_dictionary(Some(&mut dictionary)))?;
+    Ok(value)
+}
+
+fn list_to_value(mut list: ListValue) -> Result<Value, Error> {
+    let value = new_value()?;
+    check(value.set_list(Some(&mut list)))?;
+    Ok(value)
+}
+
+/// Wrap `value` in a single entry dictionary keyed by the enum variant.
+fn tagged(variant: &str, mut value: Value) -> Result<Value, Error> {
+    let dictionary = dictionary_value_create()
+        .ok_or_else(|| Error("cannot create CEF dictionary".to_string()))?;
+    check(dictionary.set_value(Some(&CefString::from(variant)), Some(&mut value)))?;
+    dictionary_to_value(dictionary)
+}
+
+/// [serde::Serializer] which creates a new [Value].
+pub struct Serializer;
+
+impl Serializer {
+    fn serialize_number(self, number: Number) -> Result<Value, Error> {
+        use ser::Serializer as _;
+
+        match number {
+            Number::Int(v) => self.serialize_i32(v),
+            Number::Double(v) => self.serialize_f64(v),
+        }
+    }
+}
+
+impl ser::Serializer for Serializer {
+    type Ok = Value;
+    type Error = Error;
+
+    type SerializeSeq = SerializeList;
+    type SerializeTuple = SerializeList;
+    type SerializeTupleStruct = SerializeList;
+    type SerializeTupleVariant = SerializeList;
+    type SerializeMap = SerializeDictionary;
+    type SerializeStruct = SerializeDictionary;
+    type SerializeStructVariant = SerializeDictionary;
+
+    fn serialize_bool(self, v: bool) -> Result<Value, Error> {
+        let value = new_value()?;
+        check(value.set_bool(v))?;
+        Ok(value)
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<Value, Error> {
+        self.serialize_i32(v.into())
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<Value, Error> {
+        self.serialize_i32(v.into())
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<Value, Error> {
+        let value = new_value()?;
+        check(value.set_int(v))?;
+        Ok(value)
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<Value, Error> {
+        self.serialize_number(v.into())
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<Value, Error> {
+        self.serialize_i32(v.into())
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<Value, Error> {
+        self.serialize_i32(v.into())
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<Value, Error> {
+        self.serialize_i64(v.into())
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<Value, Error> {
+        self.serialize_number(v.into())
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<Value, Error> {
+        self.serialize_f64(v.into())
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<Value, Error> {
+        let value = new_value()?;
+        check(value.set_double(v))?;
+        Ok(value)
+    }
+
+    fn serialize_char(self, v: char) -> Result<Value, Error> {
+        self.serialize_str(v.encode_utf8(&mut [0; 4]))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<Value, Error> {
+        let value = new_value()?;
+        check(value.set_string(Some(&CefString::from(v))))?;
+        Ok(value)
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<Value, Error> {
+        let mut binary = binary_value_create(Some(v))
+            .ok_or_else(|| Error("cannot create CEF binary value".to_string()))?;
+        let value = new_value()?;
+        check(value.set_binary(Some(&mut binary)))?;
+        Ok(value)
+    }
+
+    fn serialize_none(self) -> Result<Value, Error> {
+        self.serialize_unit()
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<Value, Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<Value, Error> {
+        let value = new_value()?;
+        check(value.set_null())?;
+        Ok(value)
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Value, Error> {
+        self.serialize_unit()
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<Value, Error> {
+        self.serialize_str(variant)
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<Value, Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<Value, Error> {
+        tagged(variant, value.serialize(self)?)
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<SerializeList, Error> {
+        SerializeList::new(None)
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<SerializeList, Error> {
+        SerializeList::new(None)
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<SerializeList, Error> {
+        SerializeList::new(None)
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<SerializeList, Error> {
+        SerializeList::new(Some(variant))
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<SerializeDictionary, Error> {
+        SerializeDictionary::new(None)
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<SerializeDictionary, Error> {
+        SerializeDictionary::new(None)
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<SerializeDictionary, Error> {
+        SerializeDictionary::new(Some(variant))
+    }
+}
+
+#[doc(hidden)]
+pub struct SerializeList {
+    list: ListValue,
+    variant: Option<&'static str>,
+}
+
+impl SerializeList {
+    fn new(variant: Option<&'static str>) -> Result<Self, Error> {
+        let list =
+            list_value_create().ok_or_else(|| Error("cannot create CEF list".to_string()))?;
+        Ok(Self { list, variant })
+    }
+
+    fn push<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        let mut value = value.serialize(Serializer)?;
+        check(self.list.set_value(self.list.get_size(), Some(&mut value)))
+    }
+
+    fn finish(self) -> Result<Value, Error> {
+        let value = list_to_value(self.list)?;
+        match self.variant {
+            Some(variant) => tagged(variant, value),
+            None => Ok(value),
+        }
+    }
+}
+
+impl ser::SerializeSeq for SerializeList {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Value, Error> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTuple for SerializeList {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Value, Error> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTupleStruct for SerializeList {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Value, Error> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTupleVariant for SerializeList {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Value, Error> {
+        self.finish()
+    }
+}
+
+#[doc(hidden)]
+pub struct SerializeDictionary {
+    dictionary: DictionaryValue,
+    variant: Option<&'static str>,
+    key: Option<String>,
+}
+
+impl SerializeDictionary {
+    fn new(variant: Option<&'static str>) -> Result<Self, Error> {
+        let dictionary = dictionary_value_create()
+            .ok_or_else(|| Error("cannot create CEF dictionary".to_string()))?;
+        Ok(Self {
+            dictionary,
+            variant,
+            key: None,
+        })
+    }
+
+    fn insert<T: Serialize + ?Sized>(&mut self, key: &str, value: &T) -> Result<(), Error> {
+        let mut value = value.serialize(Serializer)?;
+        check(
+            self.dictionary
+                .set_value(Some(&CefString::from(key)), Some(&mut value)),
+        )
+    }
+
+    fn finish(self) -> Result<Value, Error> {
+        let value = dictionary_to_value(self.dictionary)?;
+        match self.variant {
+            Some(variant) => tagged(variant, value),
+            None => Ok(value),
+        }
+    }
+}
+
+impl ser::SerializeMap for SerializeDictionary {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), Error> {
+        self.key = Some(key.serialize(KeySerializer)?);
+        Ok(())
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        let key = self
+            .key
+            .take()
+            .ok_or_else(|| Error("serialize_value called before serialize_key".to_string()))?;
+        self.insert(&key, value)
+    }
+
+    fn end(self) -> Result<Value, Error> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeStruct for SerializeDictionary {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        self.insert(key, value)
+    }
+
+    fn end(self) -> Result<Value, Error> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeStructVariant for SerializeDictionary {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        self.insert(key, value)
+    }
+
+    fn end(self) -> Result<Value, Error> {
+        self.finish()
+    }
+}
+
+/// Dictionary keys are strings, integer and `char` keys are converted like [serde_json] does.
+struct KeySerializer;
+
+fn key_error() -> Error {
+    Error("dictionary keys must be strings".to_string())
+}
+
+macro_rules! serialize_key_to_string {
+    ($($method:ident: $ty:ty),*) => {
+        $(fn $method(self, v: $ty) -> Result<String, Error> {
+            Ok(v.to_string())
+        })*
+    };
+}
+
+macro_rules! serialize_key_error {
+    ($($method:ident: $ty:ty),*) => {
+        $(fn $method(self, _v: $ty) -> Result<String, Error> {
+            Err(key_error())
+        })*
+    };
+}
+
+impl ser::Serializer for KeySerializer {
+    type Ok = String;
+    type Error = Error;
+
+    type SerializeSeq = Impossible<String, Error>;
+    type SerializeTuple = Impossible<String, Error>;
+    type SerializeTupleStruct = Impossible<String, Error>;
+    type SerializeTupleVariant = Impossible<String, Error>;
+    type SerializeMap = Impossible<String, Error>;
+    type SerializeStruct = Impossible<String, Error>;
+    type SerializeStructVariant = Impossible<String, Error>;
+
+    serialize_key_to_string! {
+        serialize_i8: i8, serialize_i16: i16, serialize_i32: i32, serialize_i64: i64,
+        serialize_u8: u8, serialize_u16: u16, serialize_u32: u32, serialize_u64: u64,
+        serialize_char: char, serialize_str: &str
+    }
+
+    serialize_key_error! {
+        serialize_bool: bool, serialize_f32: f32, serialize_f64: f64, serialize_bytes: &[u8]
+    }
+
+    fn serialize_none(self) -> Result<String, Error> {
+        Err(key_error())
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<String, Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<String, Error> {
+        Err(key_error())
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<String, Error> {
+        Err(key_error())
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<String, Error> {
+        Ok(variant.to_string())
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<String, Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<String, Error> {
+        Err(key_error())
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, Error> {
+        Err(key_error())
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple, Error> {
+        Err(key_error())
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct, Error> {
+        Err(key_error())
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Error> {
+        Err(key_error())
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Error> {
+        Err(key_error())
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStruct, Error> {
+        Err(key_error())
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, Error> {
+        Err(key_error())
+    }
+}
+
+/// [serde::Deserializer] which reads from a [Value], [DictionaryValue] or [ListValue].
+pub enum Deserializer {
+    Value(Value),
+    Dictionary(DictionaryValue),
+    List(ListValue),
+}
+
+impl From<Value> for Deserializer {
+    fn from(value: Value) -> Self {
+        Self::Value(value)
+    }
+}
+
+impl From<DictionaryValue> for Deserializer {
+    fn from(value: DictionaryValue) -> Self {
+        Self::Dictionary(value)
+    }
+}
+
+impl From<ListValue> for Deserializer {
+    fn from(value: ListValue) -> Self {
+        Self::List(value)
+    }
+}
+
+fn missing(what: &str) -> Error {
+    Error(format!("cannot read CEF {what}"))
+}
+
+impl<'de> de::Deserializer<'de> for Deserializer {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        let value = match self {
+            Self::Dictionary(dictionary) => {
+                return visitor.visit_map(DictionaryAccess::new(dictionary))
+            }
+            Self::List(list) => return visitor.visit_seq(ListAccess::new(list)),
+            Self::Value(value) => value,
+        };
+
+        match value.get_type() {
+            ValueType::Null => visitor.visit_unit(),
+            ValueType::Bool => visitor.visit_bool(value.get_bool()),
+            ValueType::Int => visitor.visit_i32(value.get_int()),
+            ValueType::Double => {
+                let v = value.get_double();
+                match integral(v) {
+                    Some(v) => visitor.visit_i64(v),
+                    None => visitor.visit_f64(v),
+                }
+            }
+            ValueType::String => {
+                visitor.visit_string(CefString::from(&value.get_string()).to_string())
+            }
+            ValueType::Binary => {
+                let binary = value.get_binary().ok_or_else(|| missing("binary value"))?;
+                let mut buffer = vec![0; binary.get_size()];
+                let size = binary.get_data(Some(&mut buffer), 0);
+                buffer.truncate(size);
+                visitor.visit_byte_buf(buffer)
+            }
+            ValueType::Dictionary => {
+                let dictionary = value
+                    .get_dictionary()
+                    .ok_or_else(|| missing("dictionary"))?;
+                visitor.visit_map(DictionaryAccess::new(dictionary))
+            }
+            ValueType::List => {
+                let list = value.get_list().ok_or_else(|| missing("list"))?;
+                visitor.visit_seq(ListAccess::new(list))
+            }
+            value_type => Err(Error(format!("unsupported CEF value type {value_type:?}"))),
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match &self {
+            Self::Value(value) if value.get_type() == ValueType::Null => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        let dictionary = match self {
+            Self::Value(value) if value.get_type() == ValueType::String => {
+                let variant = CefString::from(&value.get_string()).to_string();
+                return visitor.visit_enum(variant.into_deserializer());
+            }
+            Self::Value(value) if value.get_type() == ValueType::Dictionary => value
+                .get_dictionary()
+                .ok_or_else(|| missing("dictionary"))?,
+            Self::Dictionary(dictionary) => dictionary,
+            _ => {
+                return Err(Error(
+                    "expected a string or dictionary for enum".to_string(),
+                ))
+            }
+        };
+
+        let mut keys = CefStringList::new();
+        dictionary.get_keys(Some(&mut keys));
+        let mut keys = keys.into_iter();
+        let (Some(variant), None) = (keys.next(), keys.next()) else {
+            return Err(Error(
+                "expected a dictionary with a single key for enum".to_string(),
+            ));
+        };
+        let value = dictionary
+            .get_value(Some(&CefString::from(variant.as_str())))
+            .ok_or_else(|| missing("value"))?;
+        visitor.visit_enum(EnumAccess { variant, value })
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple tuple_struct map struct
+        identifier ignored_any
+    }
+}
+
+struct DictionaryAccess {
+    dictionary: DictionaryValue,
+    keys: std::vec::IntoIter<String>,
+    value: Option<Value>,
+}
+
+impl DictionaryAccess {
+    fn new(dictionary: DictionaryValue) -> Self {
+        let mut keys = CefStringList::new();
+        dictionary.get_keys(Some(&mut keys));
+        Self {
+            dictionary,
+            keys: keys.into_iter().collect::<Vec<_>>().into_iter(),
+            value: None,
+        }
+    }
+}
+
+impl<'de> MapAccess<'de> for DictionaryAccess {
+    type Error = Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Error> {
+        let Some(key) = self.keys.next() else {
+            return Ok(None);
+        };
+        self.value = Some(
+            self.dictionary
+                .get_value(Some(&CefString::from(key.as_str())))
+                .ok_or_else(|| missing("value"))?,
+        );
+        seed.deserialize(key.into_deserializer()).map(Some)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Error> {
+        let value = self
+            .value
+            .take()
+            .ok_or_else(|| Error("next_value called before next_key".to_string()))?;
+        seed.deserialize(Deserializer::Value(value))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.keys.len())
+    }
+}
+
+struct ListAccess {
+    list: ListValue,
+    index: usize,
+    size: usize,
+}
+
+impl ListAccess {
+    fn new(list: ListValue) -> Self {
+        let size = list.get_size();
+        Self {
+            list,
+            index: 0,
+            size,
+        }
+    }
+}
+
+impl<'de> SeqAccess<'de> for ListAccess {
+    type Error = Error;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, Error> {
+        if self.index >= self.size {
+            return Ok(None);
+        }
+
+        let value = self
+            .list
+            .get_value(self.index)
+            .ok_or_else(|| missing("value"))?;
+        self.index += 1;
+        seed.deserialize(Deserializer::Value(value)).map(Some)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.size - self.index)
+    }
+}
+
+struct EnumAccess {
+    variant: String,
+    value: Value,
+}
+
+impl<'de> de::EnumAccess<'de> for EnumAccess {
+    type Error = Error;
+    type Variant = VariantAccess;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(
+        self,
+        seed: V,
+    ) -> Result<(V::Value, VariantAccess), Error> {
+        let variant = seed.deserialize(self.variant.into_deserializer())?;
+        Ok((variant, VariantAccess(self.value)))
+    }
+}
+
+struct VariantAccess(Value);
+
+impl<'de> de::VariantAccess<'de> for VariantAccess {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<(), Error> {
+        match self.0.get_type() {
+            ValueType::Null => Ok(()),
+            _ => Err(Error("expected a unit variant".to_string())),
+        }
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value, Error> {
+        seed.deserialize(Deserializer::Value(self.0))
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value, Error> {
+        de::Deserializer::deserialize_any(Deserializer::Value(self.0), visitor)
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        de::Deserializer::deserialize_any(Deserializer::Value(self.0), visitor)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde::Serialize;
+    use std::collections::BTreeMap;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    enum Kind {
+        Text,
+        Number(u64),
+        Pair { left: i32, right: f64 },
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Message {
+        id: u32,
+        text: Option<String>,
+        kinds: Vec<Kind>,
+    }
+
+    #[test]
+    fn stores_numbers() {
+        assert_eq!(Number::from(-1_i64), Number::Int(-1));
+        assert_eq!(Number::from(i64::from(i32::MIN)), Number::Int(i32::MIN));
+        assert_eq!(
+            Number::from(i64::from(i32::MIN) - 1),
+            Number::Double(f64::from(i32::MIN) - 1.0)
+        );
+        assert_eq!(
+            Number::from(u64::from(u32::MAX)),
+            Number::Double(4294967295.0)
+        );
+
+        assert_eq!(integral(4294967296.0), Some(4294967296));
+        assert_eq!(integral(-MAX_SAFE_INTEGER), Some(-9007199254740991));
+        assert_eq!(integral(MAX_SAFE_INTEGER + 1.0), None);
+        assert_eq!(integral(0.5), None);
+        assert_eq!(integral(f64::NAN), None);
+        assert_eq!(integral(f64::INFINITY), None);
+    }
+
+    #[test]
+    fn serializes_keys() {
+        assert_eq!("key".serialize(KeySerializer).unwrap(), "key");
+        assert_eq!('k'.serialize(KeySerializer).unwrap(), "k");
+        assert_eq!((-7_i64).serialize(KeySerializer).unwrap(), "-7");
+        assert_eq!(Some(7_u8).serialize(KeySerializer).unwrap(), "7");
+        assert_eq!(Kind::Text.serialize(KeySerializer).unwrap(), "Text");
+
+        assert_eq!(true.serialize(KeySerializer), Err(key_error()));
+        assert_eq!(0.5.serialize(KeySerializer), Err(key_error()));
+        assert_eq!(None::<&str>.serialize(KeySerializer), Err(key_error()));
+        assert_eq!(Kind::Number(1).serialize(KeySerializer), Err(key_error()));
+        assert_eq!(
+            BTreeMap::<String, i32>::new().serialize(KeySerializer),
+            Err(key_error())
+        );
+    }
+
+    // The tests below create CEF values, so they need libcef at runtime.
+
+    #[test]
+    #[ignore = "needs libcef"]
+    fn round_trip() {
+        let message = Message {
+            id: 7,
+            text: Some("hello".to_string()),
+            kinds: vec![
+                Kind::Text,
+                Kind::Number(u64::from(u32::MAX) + 1),
+                Kind::Pair {
+                    left: -1,
+                    right: 0.5,
+                },
+            ],
+        };
+
+        let dictionary = to_dictionary(&message).unwrap();
+        assert_eq!(from_dictionary::<Message>(&dictionary).unwrap(), message);
+    }
+
+    #[test]
+    #[ignore = "needs libcef"]
+    fn json_interop() {
+        let json = serde_json::json!({ "a": [1, 2.5, "three", null, true] });
+        let value = Value::try_from(json.clone()).unwrap();
+        assert_eq!(serde_json::Value::try_from(&value).unwrap(), json);
+    }
+}
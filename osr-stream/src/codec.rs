@@ -0,0 +1,230 @@
+//! Compression of frame message bodies.
+//!
+//! Each side lists the codecs it supports in its hello message, and writers only compress with
+//! a codec both sides support. The codec of a frame is declared in its header. A compressed body
+//! starts with its uncompressed length as a 4 byte integer, followed by the codec's output.
+//!
+//! Codecs other than [Codec::None] are behind the cargo feature of the same name.
+
+use std::{
+    fmt::{self, Display, Formatter},
+    io,
+    str::FromStr,
+};
+
+use crate::protocol::*;
+
+/// zstd level used by [Codec::Zstd], favouring speed over ratio.
+#[cfg(feature = "zstd")]
+const ZSTD_LEVEL: i32 = 1;
+
+/// How a frame message body is compressed.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum Codec {
+    #[default]
+    None = 0,
+    /// LZ4 block compression, fast with a modest ratio.
+    Lz4 = 1,
+    /// zstd, slower than LZ4 with a better ratio.
+    Zstd = 2,
+    /// The [QOI](https://qoiformat.org) image format, applied to the body as a sequence of 4
+    /// byte pixels. Bodies whose length isn't a multiple of 4 are sent uncompressed.
+    Qoi = 3,
+}
+
+impl Codec {
+    pub const ALL: [Self; 4] = [Self::None, Self::Lz4, Self::Zstd, Self::Qoi];
+
+    pub fn from_u8(value: u8) -> Option<Self> {
+        Self::ALL.into_iter().find(|codec| *codec as u8 == value)
+    }
+
+    /// The bit of this codec in the codec set of a [Hello] message.
+    pub fn bit(self) -> u16 {
+        1 << self as u8
+    }
+
+    /// Whether this codec was enabled when building the crate.
+    pub fn is_available(self) -> bool {
+        match self {
+            Self::None => true,
+            Self::Lz4 => cfg!(feature = "lz4"),
+            Self::Zstd => cfg!(feature = "zstd"),
+            Self::Qoi => cfg!(feature = "qoi"),
+        }
+    }
+
+    /// The codec set of all available codecs.
+    pub fn available() -> u16 {
+        Self::ALL
+            .into_iter()
+            .filter(|codec| codec.is_available())
+            .fold(0, |codecs, codec| codecs | codec.bit())
+    }
+
+    /// Compress `input` into `out`, replacing its contents.
+    pub fn compress(self, input: &[u8], out: &mut Vec<u8>) -> io::Result<()> {
+        let len = u32::try_from(input.len())
+            .ok()
+            .filter(|len| *len <= MAX_MESSAGE_SIZE)
+            .ok_or_else(|| invalid_input("body too large"))?;
+        out.clear();
+        out.extend_from_slice(&len.to_le_bytes());
+        match self {
+            Self::None => out.extend_from_slice(input),
+            #[cfg(feature = "lz4")]
+            Self::Lz4 => {
+                out.resize(4 + lz4_flex::block::get_maximum_output_size(input.len()), 0);
+                let written = lz4_flex::block::compress_into(input, &mut out[4..])
+                    .map_err(|err| invalid_input(err.to_string()))?;
+                out.truncate(4 + written);
+            }
+            #[cfg(feature = "zstd")]
+            Self::Zstd => {
+                out.resize(4 + zstd::zstd_safe::compress_bound(input.len()), 0);
+                let written = zstd::bulk::compress_to_buffer(input, &mut out[4..], ZSTD_LEVEL)?;
+                out.truncate(4 + written);
+            }
+            #[cfg(feature = "qoi")]
+            Self::Qoi => {
+                let width = qoi_width(input.len()).ok_or_else(|| {
+                    invalid_input("QOI bodies must be a non-empty multiple of 4 bytes")
+                })?;
+                out.resize(4 + qoi::encode_max_len(width, 1, 4), 0);
+                let written = qoi::encode_to_buf(&mut out[4..], input, width, 1)
+                    .map_err(|err| invalid_input(err.to_string()))?;
+                out.truncate(4 + written);
+            }
+            #[cfg(not(all(feature = "lz4", feature = "zstd", feature = "qoi")))]
+            _ => return Err(unavailable(self)),
+        }
+        Ok(())
+    }
+
+    /// Decompress `input` into `out`, replacing its contents.
+    pub fn decompress(self, input: &[u8], out: &mut Vec<u8>) -> io::Result<()> {
+        let (len, input) = input
+            .split_first_chunk::<4>()
+            .ok_or_else(|| invalid_data("truncated compressed body"))?;
+        let len = u32::from_le_bytes(*len);
+        if len > MAX_MESSAGE_SIZE {
+            return Err(invalid_data(format!(
+                "compressed body of {len} bytes too large"
+            )));
+        }
+        out.resize(len as usize, 0);
+        let written = match self {
+            Self::None => {
+                let Some(bytes) = out.get_mut(..input.len()) else {
+                    return Err(invalid_data("compressed body length mismatch"));
+                };
+                bytes.copy_from_slice(input);
+                input.len()
+            }
+            #[cfg(feature = "lz4")]
+            Self::Lz4 => lz4_flex::block::decompress_into(input, out)
+                .map_err(|err| invalid_data(err.to_string()))?,
+            #[cfg(feature = "zstd")]
+            Self::Zstd => zstd::bulk::decompress_to_buffer(input, out)?,
+            #[cfg(feature = "qoi")]
+            Self::Qoi => {
+                let header =
+                    qoi::decode_header(input).map_err(|err| invalid_data(err.to_string()))?;
+                if Some(header.width) != qoi_width(out.len())
+                    || header.height != 1
+                    || header.channels != qoi::Channels::Rgba
+                {
+                    return Err(invalid_data("compressed body length mismatch"));
+                }
+                qoi::decode_to_buf(&mut *out, input)
+                    .map_err(|err| invalid_data(err.to_string()))?;
+                out.len()
+            }
+            #[cfg(not(all(feature = "lz4", feature = "zstd", feature = "qoi")))]
+            _ => return Err(unavailable(self)),
+        };
+        if written != out.len() {
+            return Err(invalid_data("compressed body length mismatch"));
+        }
+        Ok(())
+    }
+}
+
+/// The width of the single row QOI image holding `len` bytes.
+#[cfg(feature = "qoi")]
+fn qoi_width(len: usize) -> Option<u32> {
+    if len == 0 || !len.is_multiple_of(4) {
+        return None;
+    }
+    u32::try_from(len / 4).ok()
+}
+
+#[cfg(not(all(feature = "lz4", feature = "zstd", feature = "qoi")))]
+fn unavailable(codec: Codec) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::Unsupported,
+        format!("{codec} compression is not enabled"),
+    )
+}
+
+impl FromStr for Codec {
+    type Err = io::Error;
+
+    fn from_str(s: &str) -> io::Result<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|codec| codec.to_string() == s)
+            .ok_or_else(|| invalid_input(format!("unknown codec {s:?}")))
+    }
+}
+
+impl Display for Codec {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::None => "none",
+            Self::Lz4 => "lz4",
+            Self::Zstd => "zstd",
+            Self::Qoi => "qoi",
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn round_trips_bodies() {
+        // A gradient with runs, like a mostly static page.
+        let input: Vec<u8> = (0..64 * 64)
+            .flat_map(|i: u32| [(i / 64) as u8, (i / 256) as u8, 0, 255])
+            .collect();
+        let mut compressed = Vec::new();
+        let mut output = Vec::new();
+        for codec in Codec::ALL.into_iter().filter(|codec| codec.is_available()) {
+            codec.compress(&input, &mut compressed).unwrap();
+            codec.decompress(&compressed, &mut output).unwrap();
+            assert_eq!(output, input, "{codec}");
+            if codec != Codec::None {
+                assert!(compressed.len() < input.len() / 2, "{codec}");
+            }
+
+            compressed[..4].copy_from_slice(&(input.len() as u32 + 4).to_le_bytes());
+            assert!(
+                codec.decompress(&compressed, &mut output).is_err(),
+                "{codec}"
+            );
+        }
+    }
+
+    #[test]
+    fn parses_codecs() {
+        for codec in Codec::ALL {
+            assert_eq!(codec.to_string().parse::<Codec>().unwrap(), codec);
+            assert_eq!(Codec::from_u8(codec as u8), Some(codec));
+        }
+        assert!("gzip".parse::<Codec>().is_err());
+        assert_eq!(Codec::available() & Codec::None.bit(), 1);
+    }
+}
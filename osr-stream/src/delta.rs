@@ -0,0 +1,292 @@
+//! Delta frames, which only carry the regions that changed since the previous frame.
+//!
+//! The body of a delta frame message is a list of rects followed by their pixels:
+//!
+//! | Size           | Field                                                   |
+//! | -------------- | ------------------------------------------------------- |
+//! | 4              | rect count                                              |
+//! | 16 per rect    | x, y, width and height of each rect, 4 bytes each       |
+//! | rest           | rows of each rect in order, `width * 4` bytes per row   |
+//!
+//! The width, height, stride and format in the header of a delta frame must match the previous
+//! frame on the connection. Writers send a full frame (a keyframe) whenever that isn't the case.
+
+use std::io;
+
+use crate::protocol::*;
+
+/// [merge_dirty] merges the rects into their bounding box past this count.
+pub const MAX_DIRTY_RECTS: usize = 64;
+
+/// The regions to send for a frame of which the `dirty` regions changed, when it replaces a
+/// frame with the `replaced` dirty regions which was never sent. `None` stands for the whole
+/// frame; a frame which didn't replace another one passes no `replaced` rects.
+pub fn merge_dirty(replaced: Option<Vec<Rect>>, dirty: Option<&[Rect]>) -> Option<Vec<Rect>> {
+    let dirty = replaced.zip(dirty).map(|(mut replaced, dirty)| {
+        replaced.extend_from_slice(dirty);
+        replaced
+    })?;
+    if dirty.len() <= MAX_DIRTY_RECTS {
+        return Some(dirty);
+    }
+    Some(
+        dirty
+            .into_iter()
+            .reduce(|a, b| a.union(&b))
+            .into_iter()
+            .collect(),
+    )
+}
+
+/// Encode the `dirty` regions of `frame` as a delta frame body into `out`. Rects are clipped to
+/// the frame, empty ones are dropped.
+pub fn encode(frame: &Frame, dirty: &[Rect], out: &mut Vec<u8>) -> io::Result<()> {
+    frame.validate()?;
+    let bytes_per_pixel = frame.format.bytes_per_pixel();
+    let rects = dirty
+        .iter()
+        .map(|rect| rect.clip(frame.width, frame.height))
+        .filter(|rect| !rect.is_empty());
+
+    out.clear();
+    out.extend_from_slice(&0u32.to_le_bytes());
+    let mut count = 0u32;
+    for rect in rects.clone() {
+        out.extend_from_slice(&rect.encode());
+        count += 1;
+    }
+    out[..4].copy_from_slice(&count.to_le_bytes());
+
+    for rect in rects {
+        let start = rect.x as usize * bytes_per_pixel;
+        let end = start + rect.width as usize * bytes_per_pixel;
+        for y in rect.y..rect.y + rect.height {
+            out.extend_from_slice(&frame.row(y)[start..end]);
+        }
+    }
+    Ok(())
+}
+
+/// Reconstructs full frames from keyframes and the delta frames following them.
+#[derive(Debug, Default)]
+pub struct Decoder {
+    header: Option<FrameHeader>,
+    data: Vec<u8>,
+    dirty: Vec<Rect>,
+}
+
+impl Decoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start over from the keyframe described by `header`. Returns the buffer to fill with its
+    /// `header.data_len()` bytes of pixels, or an error if the header is invalid.
+    pub fn keyframe(&mut self, header: FrameHeader) -> io::Result<&mut [u8]> {
+        header.validate()?;
+        self.header = Some(header);
+        self.data.resize(header.data_len(), 0);
+        self.dirty.clear();
+        self.dirty
+            .push(Rect::new(0, 0, header.width, header.height));
+        Ok(&mut self.data)
+    }
+
+    /// Apply the delta frame `body` to the current frame.
+    pub fn apply(&mut self, header: FrameHeader, body: &[u8]) -> io::Result<()> {
+        let current = self
+            .header
+            .as_mut()
+            .ok_or_else(|| invalid_data("delta frame without a keyframe"))?;
+        if (header.width, header.height, header.stride, header.format)
+            != (
+                current.width,
+                current.height,
+                current.stride,
+                current.format,
+            )
+        {
+            return Err(invalid_data(
+                "delta frame does not match the previous frame",
+            ));
+        }
+
+        let (count, rest) = body
+            .split_first_chunk::<4>()
+            .ok_or_else(|| invalid_data("truncated delta frame"))?;
+        let count = u32::from_le_bytes(*count) as usize;
+        let (rects, mut pixels) = rest
+            .split_at_checked(count.saturating_mul(RECT_SIZE))
+            .ok_or_else(|| invalid_data("truncated delta frame"))?;
+
+        let bytes_per_pixel = header.format.bytes_per_pixel();
+        let mut dirty = Vec::with_capacity(count);
+        for rect in rects.chunks_exact(RECT_SIZE) {
+            let rect = Rect::decode(rect.try_into().unwrap());
+            if rect.clip(header.width, header.height) != rect {
+                return Err(invalid_data("delta rect outside of the frame"));
+            }
+            dirty.push(rect);
+        }
+        let len: u64 = dirty
+            .iter()
+            .map(|rect| rect.area() * bytes_per_pixel as u64)
+            .sum();
+        if len != pixels.len() as u64 {
+            return Err(invalid_data("delta frame length does not match its rects"));
+        }
+
+        let stride = header.stride as usize;
+        for rect in &dirty {
+            let row_len = rect.width as usize * bytes_per_pixel;
+            for y in rect.y as usize..(rect.y + rect.height) as usize {
+                let start = y * stride + rect.x as usize * bytes_per_pixel;
+                let (row, rest) = pixels.split_at(row_len);
+                self.data[start..start + row_len].copy_from_slice(row);
+                pixels = rest;
+            }
+        }
+        current.sequence = header.sequence;
+        self.dirty = dirty;
+        Ok(())
+    }
+
+    /// The current frame, once a keyframe arrived.
+    pub fn frame(&self) -> Option<Frame<'_>> {
+        let header = self.header?;
+        Some(Frame {
+            width: header.width,
+            height: header.height,
+            stride: header.stride,
+            format: header.format,
+            data: &self.data,
+        })
+    }
+
+    /// The regions which changed with the last keyframe or delta frame.
+    pub fn dirty_rects(&self) -> &[Rect] {
+        &self.dirty
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn merges_dirty_rects() {
+        let a = Rect::new(0, 0, 2, 2);
+        let b = Rect::new(4, 4, 2, 2);
+        assert_eq!(merge_dirty(Some(vec![]), Some(&[a])), Some(vec![a]));
+        assert_eq!(merge_dirty(Some(vec![a]), Some(&[b])), Some(vec![a, b]));
+
+        // Either frame changed completely.
+        assert_eq!(merge_dirty(None, Some(&[b])), None);
+        assert_eq!(merge_dirty(Some(vec![a]), None), None);
+
+        let rects = (0..MAX_DIRTY_RECTS as u32)
+            .map(|i| Rect::new(i, i, 1, 1))
+            .collect::<Vec<_>>();
+        let (replaced, dirty) = rects.split_at(MAX_DIRTY_RECTS / 2);
+        assert_eq!(
+            merge_dirty(Some(replaced.to_vec()), Some(dirty)),
+            Some(rects)
+        );
+
+        let replaced = vec![a; MAX_DIRTY_RECTS];
+        assert_eq!(
+            merge_dirty(Some(replaced), Some(&[b])),
+            Some(vec![Rect::new(0, 0, 6, 6)])
+        );
+    }
+
+    fn frame_header(width: u32, height: u32) -> FrameHeader {
+        FrameHeader {
+            width,
+            height,
+            stride: width * 4,
+            delta: true,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn reconstructs_frames() {
+        let previous = vec![0; 4 * 3 * 4];
+        let next: Vec<u8> = (0..4 * 3 * 4).collect();
+        let mut decoder = Decoder::new();
+        decoder
+            .keyframe(frame_header(4, 3))
+            .unwrap()
+            .copy_from_slice(&previous);
+
+        // The second rect is clipped to the frame.
+        let dirty = [
+            Rect::new(1, 0, 2, 2),
+            Rect::new(3, 2, 5, 5),
+            Rect::default(),
+        ];
+        let mut body = Vec::new();
+        encode(
+            &Frame::new(4, 3, PixelFormat::Bgra8, &next),
+            &dirty,
+            &mut body,
+        )
+        .unwrap();
+        assert_eq!(body.len(), 4 + 2 * RECT_SIZE + (4 + 1) * 4);
+        decoder.apply(frame_header(4, 3), &body).unwrap();
+        assert_eq!(
+            decoder.dirty_rects(),
+            [Rect::new(1, 0, 2, 2), Rect::new(3, 2, 1, 1)]
+        );
+
+        let mut expected = previous;
+        for (x, y) in [(1, 0), (2, 0), (1, 1), (2, 1), (3, 2)] {
+            let offset = (y * 4 + x) * 4;
+            expected[offset..offset + 4].copy_from_slice(&next[offset..offset + 4]);
+        }
+        assert_eq!(decoder.frame().unwrap().data, expected);
+    }
+
+    #[test]
+    fn rejects_mismatched_deltas() {
+        let mut body = Vec::new();
+        encode(
+            &Frame::new(2, 2, PixelFormat::Bgra8, &[0; 16]),
+            &[Rect::new(0, 0, 1, 1)],
+            &mut body,
+        )
+        .unwrap();
+
+        let mut decoder = Decoder::new();
+        assert!(decoder.apply(frame_header(2, 2), &body).is_err());
+        decoder.keyframe(frame_header(3, 2)).unwrap();
+        assert!(decoder.apply(frame_header(2, 2), &body).is_err());
+        decoder.keyframe(frame_header(2, 2)).unwrap();
+        assert!(decoder
+            .apply(frame_header(2, 2), &body[..body.len() - 1])
+            .is_err());
+        decoder.apply(frame_header(2, 2), &body).unwrap();
+    }
+
+    #[test]
+    fn rejects_short_strides() {
+        let mut body = Vec::new();
+        encode(
+            &Frame::new(1, 1, PixelFormat::Bgra8, &[0; 4]),
+            &[Rect::new(0, 0, 1, 1)],
+            &mut body,
+        )
+        .unwrap();
+
+        // A keyframe whose rows don't fit the stride leaves no frame to apply deltas to.
+        let mut decoder = Decoder::new();
+        let header = FrameHeader {
+            stride: 0,
+            ..frame_header(4, 1)
+        };
+        assert!(decoder.keyframe(header).is_err());
+        assert!(decoder.frame().is_none());
+        assert!(decoder.apply(header, &body).is_err());
+    }
+}
@@ -0,0 +1,428 @@
+//! Input events sent by the consumer back to the producer, added in protocol version 3.
+//!
+//! Each input message (kind 2) carries one event. Its payload starts with the event type, the
+//! remaining fields depend on the type:
+//!
+//! | Type | Event                        | Size |
+//! | ---- | ---------------------------- | ---- |
+//! | 0    | [InputEvent::MouseMove]      | 16   |
+//! | 1    | [InputEvent::MouseButton]    | 16   |
+//! | 2    | [InputEvent::MouseWheel]     | 24   |
+//! | 3    | [InputEvent::Key]            | 20   |
+//! | 4    | [InputEvent::Touch]          | 36   |
+//!
+//! See [InputEvent::encode] for the field offsets. Receivers skip events of unknown types.
+//!
+//! Coordinates are in pixels of the streamed frames, with the origin at the top left corner of
+//! the frame. The producer scales them to the coordinates of the browser view.
+
+use std::io::{self, Read};
+
+use crate::{protocol::*, InputReceiver};
+
+/// Modifier flags of input events, the same bits as CEF's `cef_event_flags_t`.
+pub mod modifiers {
+    pub const CAPS_LOCK_ON: u32 = 1 << 0;
+    pub const SHIFT_DOWN: u32 = 1 << 1;
+    pub const CONTROL_DOWN: u32 = 1 << 2;
+    pub const ALT_DOWN: u32 = 1 << 3;
+    pub const LEFT_MOUSE_BUTTON: u32 = 1 << 4;
+    pub const MIDDLE_MOUSE_BUTTON: u32 = 1 << 5;
+    pub const RIGHT_MOUSE_BUTTON: u32 = 1 << 6;
+    pub const COMMAND_DOWN: u32 = 1 << 7;
+    pub const NUM_LOCK_ON: u32 = 1 << 8;
+    pub const IS_KEY_PAD: u32 = 1 << 9;
+    pub const IS_REPEAT: u32 = 1 << 13;
+}
+
+/// Input messages larger than this are rejected as corrupt.
+const MAX_INPUT_SIZE: u32 = 1024;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum MouseButton {
+    #[default]
+    Left = 0,
+    Middle = 1,
+    Right = 2,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum KeyEventKind {
+    /// A key press without the character it produces, followed by [KeyEventKind::Char].
+    #[default]
+    RawKeyDown = 0,
+    KeyDown = 1,
+    KeyUp = 2,
+    /// The character produced by a key press.
+    Char = 3,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum TouchEventKind {
+    #[default]
+    Released = 0,
+    Pressed = 1,
+    Moved = 2,
+    Cancelled = 3,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum PointerKind {
+    #[default]
+    Touch = 0,
+    Mouse = 1,
+    Pen = 2,
+    Eraser = 3,
+    Unknown = 4,
+}
+
+macro_rules! from_u8 {
+    ($($name:ident { $($variant:ident),* })*) => {
+        $(impl $name {
+            pub fn from_u8(value: u8) -> Option<Self> {
+                [$(Self::$variant),*].into_iter().find(|variant| *variant as u8 == value)
+            }
+        })*
+    };
+}
+
+from_u8! {
+    MouseButton { Left, Middle, Right }
+    KeyEventKind { RawKeyDown, KeyDown, KeyUp, Char }
+    TouchEventKind { Released, Pressed, Moved, Cancelled }
+    PointerKind { Touch, Mouse, Pen, Eraser, Unknown }
+}
+
+/// A key event, with the fields of CEF's `cef_key_event_t`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct KeyInput {
+    pub kind: KeyEventKind,
+    pub modifiers: u32,
+    pub windows_key_code: i32,
+    pub native_key_code: i32,
+    pub is_system_key: bool,
+    /// The UTF-16 code unit produced by the key.
+    pub character: u16,
+    /// The UTF-16 code unit produced by the key without modifiers.
+    pub unmodified_character: u16,
+}
+
+/// A touch event, with the fields of CEF's `cef_touch_event_t`.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct TouchInput {
+    /// Identifies the touch point across events.
+    pub id: i32,
+    pub kind: TouchEventKind,
+    pub pointer: PointerKind,
+    pub x: f32,
+    pub y: f32,
+    pub radius_x: f32,
+    pub radius_y: f32,
+    pub rotation_angle: f32,
+    pub pressure: f32,
+    pub modifiers: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum InputEvent {
+    MouseMove {
+        x: f32,
+        y: f32,
+        modifiers: u32,
+        /// The pointer left the view.
+        leave: bool,
+    },
+    MouseButton {
+        x: f32,
+        y: f32,
+        modifiers: u32,
+        button: MouseButton,
+        up: bool,
+        click_count: u8,
+    },
+    MouseWheel {
+        x: f32,
+        y: f32,
+        modifiers: u32,
+        delta_x: i32,
+        delta_y: i32,
+    },
+    Key(KeyInput),
+    Touch(TouchInput),
+}
+
+impl InputEvent {
+    /// Replace the position of pointer events with `f(x, y)`.
+    pub fn map_position(mut self, f: impl FnOnce(f32, f32) -> (f32, f32)) -> Self {
+        let (x, y) = match &mut self {
+            Self::MouseMove { x, y, .. }
+            | Self::MouseButton { x, y, .. }
+            | Self::MouseWheel { x, y, .. }
+            | Self::Touch(TouchInput { x, y, .. }) => (x, y),
+            Self::Key(_) => return self,
+        };
+        (*x, *y) = f(*x, *y);
+        self
+    }
+
+    /// Encode the payload of an input message. The first byte is the event type, followed by
+    /// one byte per small field and then the remaining fields, 4 bytes each:
+    ///
+    /// - mouse move: leave; x, y, modifiers.
+    /// - mouse button: button, up, click count; x, y, modifiers.
+    /// - mouse wheel: x, y, modifiers, delta x, delta y.
+    /// - key: kind, system key; modifiers, Windows key code, native key code, then the
+    ///   character and unmodified character, 2 bytes each.
+    /// - touch: kind, pointer kind; id, x, y, radius x, radius y, rotation angle, pressure,
+    ///   modifiers.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(36);
+        let mut push = |small: [u8; 4], fields: &[[u8; 4]]| {
+            bytes.extend_from_slice(&small);
+            fields
+                .iter()
+                .for_each(|field| bytes.extend_from_slice(field));
+        };
+        match *self {
+            Self::MouseMove {
+                x,
+                y,
+                modifiers,
+                leave,
+            } => push(
+                [0, leave.into(), 0, 0],
+                &[x.to_le_bytes(), y.to_le_bytes(), modifiers.to_le_bytes()],
+            ),
+            Self::MouseButton {
+                x,
+                y,
+                modifiers,
+                button,
+                up,
+                click_count,
+            } => push(
+                [1, button as u8, up.into(), click_count],
+                &[x.to_le_bytes(), y.to_le_bytes(), modifiers.to_le_bytes()],
+            ),
+            Self::MouseWheel {
+                x,
+                y,
+                modifiers,
+                delta_x,
+                delta_y,
+            } => push(
+                [2, 0, 0, 0],
+                &[
+                    x.to_le_bytes(),
+                    y.to_le_bytes(),
+                    modifiers.to_le_bytes(),
+                    delta_x.to_le_bytes(),
+                    delta_y.to_le_bytes(),
+                ],
+            ),
+            Self::Key(key) => {
+                let [c0, c1] = key.character.to_le_bytes();
+                let [u0, u1] = key.unmodified_character.to_le_bytes();
+                push(
+                    [3, key.kind as u8, key.is_system_key.into(), 0],
+                    &[
+                        key.modifiers.to_le_bytes(),
+                        key.windows_key_code.to_le_bytes(),
+                        key.native_key_code.to_le_bytes(),
+                        [c0, c1, u0, u1],
+                    ],
+                )
+            }
+            Self::Touch(touch) => push(
+                [4, touch.kind as u8, touch.pointer as u8, 0],
+                &[
+                    touch.id.to_le_bytes(),
+                    touch.x.to_le_bytes(),
+                    touch.y.to_le_bytes(),
+                    touch.radius_x.to_le_bytes(),
+                    touch.radius_y.to_le_bytes(),
+                    touch.rotation_angle.to_le_bytes(),
+                    touch.pressure.to_le_bytes(),
+                    touch.modifiers.to_le_bytes(),
+                ],
+            ),
+        }
+        bytes
+    }
+
+    /// Decode the payload of an input message. Returns `None` for events of unknown types.
+    /// Payloads may be longer in newer versions, the extra bytes are ignored.
+    pub fn decode(bytes: &[u8]) -> io::Result<Option<Self>> {
+        let (small, fields) = bytes
+            .split_first_chunk::<4>()
+            .ok_or_else(|| invalid_data("truncated input message"))?;
+        let expected = match small[0] {
+            0 | 1 => 12,
+            2 => 20,
+            3 => 16,
+            4 => 32,
+            _ => return Ok(None),
+        };
+        if fields.len() < expected {
+            return Err(invalid_data("truncated input message"));
+        }
+        let field =
+            |index: usize| -> [u8; 4] { fields[index * 4..index * 4 + 4].try_into().unwrap() };
+        let u32_at = |index| u32::from_le_bytes(field(index));
+        let i32_at = |index| i32::from_le_bytes(field(index));
+        let f32_at = |index| f32::from_le_bytes(field(index));
+        let invalid = |what: &str| invalid_data(format!("unknown {what} in input message"));
+
+        let event = match small[0] {
+            0 => Self::MouseMove {
+                x: f32_at(0),
+                y: f32_at(1),
+                modifiers: u32_at(2),
+                leave: small[1] != 0,
+            },
+            1 => Self::MouseButton {
+                x: f32_at(0),
+                y: f32_at(1),
+                modifiers: u32_at(2),
+                button: MouseButton::from_u8(small[1]).ok_or_else(|| invalid("mouse button"))?,
+                up: small[2] != 0,
+                click_count: small[3],
+            },
+            2 => Self::MouseWheel {
+                x: f32_at(0),
+                y: f32_at(1),
+                modifiers: u32_at(2),
+                delta_x: i32_at(3),
+                delta_y: i32_at(4),
+            },
+            3 => {
+                let [c0, c1, u0, u1] = field(3);
+                Self::Key(KeyInput {
+                    kind: KeyEventKind::from_u8(small[1]).ok_or_else(|| invalid("key event"))?,
+                    modifiers: u32_at(0),
+                    windows_key_code: i32_at(1),
+                    native_key_code: i32_at(2),
+                    is_system_key: small[2] != 0,
+                    character: u16::from_le_bytes([c0, c1]),
+                    unmodified_character: u16::from_le_bytes([u0, u1]),
+                })
+            }
+            _ => Self::Touch(TouchInput {
+                id: i32_at(0),
+                kind: TouchEventKind::from_u8(small[1]).ok_or_else(|| invalid("touch event"))?,
+                pointer: PointerKind::from_u8(small[2]).ok_or_else(|| invalid("pointer"))?,
+                x: f32_at(1),
+                y: f32_at(2),
+                radius_x: f32_at(3),
+                radius_y: f32_at(4),
+                rotation_angle: f32_at(5),
+                pressure: f32_at(6),
+                modifiers: u32_at(7),
+            }),
+        };
+        Ok(Some(event))
+    }
+}
+
+/// Reads the input messages sent by the consumer, skipping all other messages. Used on a
+/// separate handle to the connection, after the handshake.
+pub struct InputReader<R> {
+    stream: R,
+}
+
+impl<R: Read> InputReader<R> {
+    pub fn new(stream: R) -> Self {
+        Self { stream }
+    }
+
+    /// Block until the next input event arrives.
+    pub fn read_input(&mut self) -> io::Result<InputEvent> {
+        loop {
+            let envelope = Envelope::read_from(&mut self.stream)?;
+            if envelope.kind != MessageKind::Input as u8 {
+                io::copy(
+                    &mut (&mut self.stream).take(envelope.length.into()),
+                    &mut io::sink(),
+                )?;
+                continue;
+            }
+            if envelope.length > MAX_INPUT_SIZE {
+                return Err(invalid_data("input message too large"));
+            }
+            let mut payload = vec![0; envelope.length as usize];
+            self.stream.read_exact(&mut payload)?;
+            if let Some(event) = InputEvent::decode(&payload)? {
+                return Ok(event);
+            }
+        }
+    }
+}
+
+impl<R: Read + Send> InputReceiver for InputReader<R> {
+    fn recv(&mut self) -> io::Result<InputEvent> {
+        self.read_input()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn round_trips_events() {
+        let events = [
+            InputEvent::MouseMove {
+                x: 1.5,
+                y: 2.0,
+                modifiers: modifiers::SHIFT_DOWN,
+                leave: true,
+            },
+            InputEvent::MouseButton {
+                x: 3.0,
+                y: 4.0,
+                modifiers: modifiers::LEFT_MOUSE_BUTTON,
+                button: MouseButton::Right,
+                up: true,
+                click_count: 2,
+            },
+            InputEvent::MouseWheel {
+                x: 5.0,
+                y: 6.0,
+                modifiers: 0,
+                delta_x: -120,
+                delta_y: 240,
+            },
+            InputEvent::Key(KeyInput {
+                kind: KeyEventKind::Char,
+                modifiers: modifiers::CONTROL_DOWN,
+                windows_key_code: 0x41,
+                native_key_code: 38,
+                is_system_key: true,
+                character: 'a' as u16,
+                unmodified_character: 'A' as u16,
+            }),
+            InputEvent::Touch(TouchInput {
+                id: 7,
+                kind: TouchEventKind::Moved,
+                pointer: PointerKind::Pen,
+                x: 7.0,
+                y: 8.0,
+                radius_x: 1.0,
+                radius_y: 2.0,
+                rotation_angle: 0.5,
+                pressure: 0.25,
+                modifiers: 0,
+            }),
+        ];
+        for event in events {
+            let bytes = event.encode();
+            assert_eq!(InputEvent::decode(&bytes).unwrap(), Some(event));
+            assert!(InputEvent::decode(&bytes[..bytes.len() - 1]).is_err());
+        }
+        assert_eq!(InputEvent::decode(&[9, 0, 0, 0]).unwrap(), None);
+    }
+}
@@ -0,0 +1,134 @@
+//! Stream offscreen-rendered frames from a windowless browser to a consumer in another process.
+//!
+//! The producer side is `cef::osr::stream`, which re-exports this crate. Consumers only need
+//! this crate, which doesn't depend on CEF:
+//!
+//! ```no_run
+//! use cef_osr_stream::Endpoint;
+//!
+//! let mut source = "tcp:127.0.0.1:7878".parse::<Endpoint>()?.connect()?;
+//! loop {
+//!     let frame = source.recv()?;
+//!     println!("{}x{} {:?}", frame.width, frame.height, frame.format);
+//! }
+//! # Ok::<(), std::io::Error>(())
+//! ```
+//!
+//! # Wire protocol
+//!
+//! All integers are little-endian. Every message starts with an 8 byte envelope:
+//!
+//! | Offset | Size | Field                                  |
+//! | ------ | ---- | -------------------------------------- |
+//! | 0      | 1    | message kind                           |
+//! | 1      | 3    | reserved, zero                         |
+//! | 4      | 4    | payload length, excluding the envelope |
+//!
+//! Receivers skip messages of unknown kinds.
+//!
+//! After connecting, each side sends a hello message (kind 0) and waits for the peer's:
+//!
+//! | Offset | Size | Field                                   |
+//! | ------ | ---- | --------------------------------------- |
+//! | 0      | 4    | magic, `COSR`                           |
+//! | 4      | 2    | newest protocol version of the sender   |
+//! | 6      | 2    | codecs supported by the sender          |
+//!
+//! The connection uses the lower of the two versions. A side which doesn't support that
+//! version closes the connection. Newer versions may append fields to the hello payload.
+//! Bit `n` of the codecs is set if the sender supports the [Codec] `n`, see [codec].
+//!
+//! The producer then sends frame messages (kind 1), each with a 24 byte header:
+//!
+//! | Offset | Size | Field                                   |
+//! | ------ | ---- | --------------------------------------- |
+//! | 0      | 4    | sequence number                         |
+//! | 4      | 4    | width in pixels                         |
+//! | 8      | 4    | height in pixels                        |
+//! | 12     | 4    | stride, bytes per row                   |
+//! | 16     | 1    | [PixelFormat]                           |
+//! | 17     | 1    | [Codec] of the rest of the message      |
+//! | 18     | 2    | flags, bit 0 marks delta frames         |
+//! | 20     | 4    | reserved, zero                          |
+//!
+//! Keyframes are followed by `stride * height` bytes of pixels, top row first. Delta frames,
+//! added in version 2, only carry the regions which changed since the previous frame, see
+//! [delta]. Writers only send them if the connection uses version 2 or newer. Either body may
+//! be compressed with a codec both sides support, see [codec].
+//!
+//! From version 3 on, the consumer may send input messages (kind 2) to the producer, see
+//! [input].
+//!
+//! The shared memory transport in the `shm` module doesn't use these messages, frames are
+//! exchanged through a ring of slots in the segment instead.
+
+use std::io;
+
+pub mod codec;
+pub mod delta;
+pub mod input;
+pub mod process;
+pub mod protocol;
+#[cfg(unix)]
+pub mod shm;
+pub mod transport;
+
+mod reader;
+mod writer;
+
+pub use codec::Codec;
+pub use input::InputEvent;
+pub use process::FrameProcessor;
+pub use protocol::{Frame, PixelFormat, Rect};
+pub use reader::Reader;
+pub use transport::{Endpoint, Transport};
+pub use writer::Writer;
+
+/// The producer's connection to one consumer.
+///
+/// Sinks fail with [io::ErrorKind::InvalidInput] if they can't send a particular frame, e.g.
+/// because it's too large, without affecting the connection. Any other error means the consumer
+/// is gone.
+pub trait Sink: Send {
+    fn send(&mut self, frame: &Frame) -> io::Result<()>;
+
+    /// Send a frame of which only the `dirty` regions changed since the previous one. Sinks
+    /// which can't send delta frames send the full frame.
+    fn send_dirty(&mut self, frame: &Frame, dirty: &[Rect]) -> io::Result<()> {
+        let _ = dirty;
+        self.send(frame)
+    }
+
+    /// Compress the following frames with `codec` if the consumer supports it. Sinks which
+    /// can't compress ignore it.
+    fn set_codec(&mut self, codec: Codec) {
+        let _ = codec;
+    }
+
+    /// Take the receiver of the input events sent by the consumer, which can be used on another
+    /// thread. Returns `None` if the transport can't carry input, or after the first call.
+    fn take_input(&mut self) -> Option<Box<dyn InputReceiver>> {
+        None
+    }
+}
+
+/// The producer's side of the input sent by one consumer.
+pub trait InputReceiver: Send {
+    /// Block until the next input event arrives.
+    fn recv(&mut self) -> io::Result<InputEvent>;
+}
+
+/// The consumer's connection to the producer.
+pub trait Source: Send {
+    /// Block until the next frame arrives.
+    fn recv(&mut self) -> io::Result<Frame<'_>>;
+
+    /// Send an input event to the producer.
+    fn send_input(&mut self, event: &InputEvent) -> io::Result<()> {
+        let _ = event;
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "the transport can't carry input",
+        ))
+    }
+}
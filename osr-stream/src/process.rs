@@ -0,0 +1,408 @@
+//! Post-processing of painted frames before they are streamed.
+//!
+//! A [FrameProcessor] runs a list of stages over a copy of each frame, one row at a time.
+//!
+//! ```
+//! use cef_osr_stream::{process::FrameProcessor, Frame, PixelFormat};
+//!
+//! let mut processor = FrameProcessor::new()
+//!     .flip_vertical()
+//!     .unpremultiply()
+//!     .swizzle();
+//! let pixels = [0u8; 4 * 2 * 2];
+//! let frame = processor.process(&Frame::new(2, 2, PixelFormat::Bgra8, &pixels))?;
+//! assert_eq!(frame.format, PixelFormat::Rgba8);
+//! # Ok::<(), std::io::Error>(())
+//! ```
+
+use std::io;
+
+use crate::protocol::*;
+
+/// One step of a [FrameProcessor].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stage {
+    /// Reverse the order of the rows.
+    FlipVertical,
+    /// Swap the red and blue channels, converting between BGRA and RGBA.
+    Swizzle,
+    /// Convert straight alpha to premultiplied alpha.
+    Premultiply,
+    /// Convert premultiplied alpha, which CEF paints, to straight alpha.
+    Unpremultiply,
+    /// Make pixels transparent whose red, green and blue each differ from `color` by at most
+    /// `tolerance`.
+    ChromaKey { color: [u8; 3], tolerance: u8 },
+    /// Make pixels transparent whose luma differs from `luma` by at most `tolerance`.
+    LumaKey { luma: u8, tolerance: u8 },
+    /// Keep only the part of the frame inside the rect.
+    Crop(Rect),
+    /// Shrink the frame by an integer factor, averaging each square of `factor` by `factor`
+    /// pixels. Pixels past the last whole square are dropped.
+    Downscale(u32),
+}
+
+/// Runs [Stage]s over frames, reusing its buffers from frame to frame.
+#[derive(Debug, Clone, Default)]
+pub struct FrameProcessor {
+    stages: Vec<Stage>,
+    buffer: Vec<u8>,
+    scratch: Vec<u8>,
+}
+
+impl FrameProcessor {
+    /// A processor without stages, which only copies frames.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append `stage` to the pipeline.
+    pub fn stage(mut self, stage: Stage) -> Self {
+        self.stages.push(stage);
+        self
+    }
+
+    pub fn flip_vertical(self) -> Self {
+        self.stage(Stage::FlipVertical)
+    }
+
+    pub fn swizzle(self) -> Self {
+        self.stage(Stage::Swizzle)
+    }
+
+    pub fn premultiply(self) -> Self {
+        self.stage(Stage::Premultiply)
+    }
+
+    pub fn unpremultiply(self) -> Self {
+        self.stage(Stage::Unpremultiply)
+    }
+
+    /// `color` is red, green and blue, regardless of the pixel format.
+    pub fn chroma_key(self, color: [u8; 3], tolerance: u8) -> Self {
+        self.stage(Stage::ChromaKey { color, tolerance })
+    }
+
+    pub fn luma_key(self, luma: u8, tolerance: u8) -> Self {
+        self.stage(Stage::LumaKey { luma, tolerance })
+    }
+
+    pub fn crop(self, rect: Rect) -> Self {
+        self.stage(Stage::Crop(rect))
+    }
+
+    pub fn downscale(self, factor: u32) -> Self {
+        self.stage(Stage::Downscale(factor))
+    }
+
+    pub fn stages(&self) -> &[Stage] {
+        &self.stages
+    }
+
+    /// Run the stages over a copy of `frame`. The result is tightly packed and borrows the
+    /// processor's buffer until the next call.
+    pub fn process(&mut self, frame: &Frame) -> io::Result<Frame<'_>> {
+        frame.validate()?;
+        let mut width = frame.width;
+        let mut height = frame.height;
+        let mut format = frame.format;
+        let row_len = width as usize * format.bytes_per_pixel();
+
+        self.buffer.clear();
+        for y in 0..height {
+            self.buffer.extend_from_slice(&frame.row(y)[..row_len]);
+        }
+
+        for stage in &self.stages {
+            let buffer = &mut self.buffer;
+            let row_len = width as usize * 4;
+            let rows = buffer.chunks_exact_mut(row_len.max(1));
+            match *stage {
+                Stage::FlipVertical => flip_vertical(buffer, row_len),
+                Stage::Swizzle => {
+                    rows.for_each(swizzle);
+                    format = match format {
+                        PixelFormat::Bgra8 => PixelFormat::Rgba8,
+                        PixelFormat::Rgba8 => PixelFormat::Bgra8,
+                    };
+                }
+                Stage::Premultiply => rows.for_each(premultiply),
+                Stage::Unpremultiply => rows.for_each(unpremultiply),
+                Stage::ChromaKey { color, tolerance } => {
+                    let [r, g, b] = color;
+                    let key = match format {
+                        PixelFormat::Bgra8 => [b, g, r],
+                        PixelFormat::Rgba8 => [r, g, b],
+                    };
+                    rows.for_each(|row| chroma_key(row, key, tolerance));
+                }
+                Stage::LumaKey { luma, tolerance } => {
+                    rows.for_each(|row| luma_key(row, format, luma, tolerance))
+                }
+                Stage::Crop(rect) => {
+                    let rect = rect.clip(width, height);
+                    crop(buffer, row_len, rect);
+                    (width, height) = (rect.width, rect.height);
+                }
+                Stage::Downscale(factor) => {
+                    if factor == 0 {
+                        return Err(invalid_input("downscale factor must not be 0"));
+                    }
+                    downscale(buffer, &mut self.scratch, width, height, factor);
+                    std::mem::swap(buffer, &mut self.scratch);
+                    (width, height) = (width / factor, height / factor);
+                }
+            }
+        }
+
+        Ok(Frame::new(width, height, format, &self.buffer))
+    }
+
+    /// Map a region of a `width` by `height` input frame to the region of the processed frame
+    /// it affects, for sending only the dirty regions of processed frames.
+    pub fn map_rect(&self, rect: Rect, mut width: u32, mut height: u32) -> Rect {
+        let mut rect = rect.clip(width, height);
+        for stage in &self.stages {
+            match *stage {
+                Stage::FlipVertical => rect.y = height - rect.y - rect.height,
+                Stage::Crop(crop) => {
+                    let crop = crop.clip(width, height);
+                    let x = rect.x.max(crop.x);
+                    let y = rect.y.max(crop.y);
+                    let right = (rect.x + rect.width).min(crop.x + crop.width).max(x);
+                    let bottom = (rect.y + rect.height).min(crop.y + crop.height).max(y);
+                    (width, height) = (crop.width, crop.height);
+                    rect = Rect::new(x - crop.x, y - crop.y, right - x, bottom - y)
+                        .clip(width, height);
+                }
+                Stage::Downscale(factor) if factor > 0 => {
+                    (width, height) = (width / factor, height / factor);
+                    let right = (rect.x + rect.width).div_ceil(factor);
+                    let bottom = (rect.y + rect.height).div_ceil(factor);
+                    let (x, y) = (rect.x / factor, rect.y / factor);
+                    rect = Rect::new(x, y, right - x, bottom - y).clip(width, height);
+                }
+                _ => {}
+            }
+        }
+        rect
+    }
+}
+
+fn flip_vertical(buffer: &mut [u8], row_len: usize) {
+    if row_len == 0 {
+        return;
+    }
+    let rows = buffer.len() / row_len;
+    let (top, bottom) = buffer.split_at_mut(rows / 2 * row_len);
+    let bottom_start = bottom.len() - rows / 2 * row_len;
+    top.chunks_exact_mut(row_len)
+        .zip(bottom[bottom_start..].chunks_exact_mut(row_len).rev())
+        .for_each(|(top, bottom)| top.swap_with_slice(bottom));
+}
+
+fn swizzle(row: &mut [u8]) {
+    for pixel in row.chunks_exact_mut(4) {
+        pixel.swap(0, 2);
+    }
+}
+
+/// `value * alpha / 255`, rounded.
+fn scale(value: u8, alpha: u8) -> u8 {
+    let product = u32::from(value) * u32::from(alpha) + 128;
+    ((product + (product >> 8)) >> 8) as u8
+}
+
+fn premultiply(row: &mut [u8]) {
+    for pixel in row.chunks_exact_mut(4) {
+        let alpha = pixel[3];
+        for channel in &mut pixel[..3] {
+            *channel = scale(*channel, alpha);
+        }
+    }
+}
+
+fn unpremultiply(row: &mut [u8]) {
+    for pixel in row.chunks_exact_mut(4) {
+        let alpha = u32::from(pixel[3]);
+        let divisor = alpha.max(1);
+        for channel in &mut pixel[..3] {
+            let value = (u32::from(*channel) * 255 + divisor / 2) / divisor;
+            *channel = if alpha == 0 { 0 } else { value.min(255) as u8 };
+        }
+    }
+}
+
+/// Clear the pixels for which `matches` returns true, so they are transparent with either
+/// alpha representation.
+fn key(row: &mut [u8], matches: impl Fn(&[u8]) -> bool) {
+    for pixel in row.chunks_exact_mut(4) {
+        let keep = if matches(pixel) { 0 } else { 0xff };
+        pixel.iter_mut().for_each(|channel| *channel &= keep);
+    }
+}
+
+fn chroma_key(row: &mut [u8], color: [u8; 3], tolerance: u8) {
+    key(row, |pixel| {
+        pixel[..3]
+            .iter()
+            .zip(color)
+            .all(|(channel, key)| channel.abs_diff(key) <= tolerance)
+    });
+}
+
+/// Rec. 601 luma of a pixel, in 8 bit fixed point.
+fn luma(pixel: &[u8], format: PixelFormat) -> u8 {
+    let (r, g, b) = match format {
+        PixelFormat::Bgra8 => (pixel[2], pixel[1], pixel[0]),
+        PixelFormat::Rgba8 => (pixel[0], pixel[1], pixel[2]),
+    };
+    ((77 * u32::from(r) + 150 * u32::from(g) + 29 * u32::from(b) + 128) >> 8) as u8
+}
+
+fn luma_key(row: &mut [u8], format: PixelFormat, target: u8, tolerance: u8) {
+    key(row, |pixel| {
+        luma(pixel, format).abs_diff(target) <= tolerance
+    });
+}
+
+/// Move the rows inside `rect` to the start of `buffer`, tightly packed.
+fn crop(buffer: &mut Vec<u8>, row_len: usize, rect: Rect) {
+    let start = rect.x as usize * 4;
+    let len = rect.width as usize * 4;
+    for (index, y) in (rect.y..rect.y + rect.height).enumerate() {
+        let from = y as usize * row_len + start;
+        buffer.copy_within(from..from + len, index * len);
+    }
+    buffer.truncate(rect.height as usize * len);
+}
+
+fn downscale(input: &[u8], output: &mut Vec<u8>, width: u32, height: u32, factor: u32) {
+    let factor = factor as usize;
+    let row_len = width as usize * 4;
+    let out_width = width as usize / factor;
+    let out_height = height as usize / factor;
+    let area = (factor * factor) as u32;
+
+    output.clear();
+    if out_width == 0 || out_height == 0 {
+        return;
+    }
+    let mut sums = vec![0u32; out_width * 4];
+    for y in 0..out_height {
+        sums.fill(0);
+        for row in input[y * factor * row_len..]
+            .chunks_exact(row_len)
+            .take(factor)
+        {
+            for (sum, block) in sums.chunks_exact_mut(4).zip(row.chunks_exact(factor * 4)) {
+                for pixel in block.chunks_exact(4) {
+                    for (sum, channel) in sum.iter_mut().zip(pixel) {
+                        *sum += u32::from(*channel);
+                    }
+                }
+            }
+        }
+        output.extend(sums.iter().map(|sum| ((sum + area / 2) / area) as u8));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// A 4x2 BGRA reference image: opaque black, red, green and white on top, and half
+    /// transparent blue, dark gray, near black and a transparent pixel below. Colors are
+    /// premultiplied, as painted by CEF.
+    #[rustfmt::skip]
+    const IMAGE: [u8; 32] = [
+        0, 0, 0, 255,    0, 0, 255, 255,    0, 255, 0, 255,    255, 255, 255, 255,
+        128, 0, 0, 128,  64, 64, 64, 255,   2, 3, 1, 255,      0, 0, 0, 0,
+    ];
+
+    fn process(processor: FrameProcessor) -> (u32, u32, PixelFormat, Vec<u8>) {
+        let mut processor = processor;
+        let frame = processor
+            .process(&Frame::new(4, 2, PixelFormat::Bgra8, &IMAGE))
+            .unwrap();
+        (frame.width, frame.height, frame.format, frame.data.to_vec())
+    }
+
+    #[test]
+    fn flips_and_swizzles() {
+        #[rustfmt::skip]
+        let expected = vec![
+            0, 0, 128, 128,  64, 64, 64, 255,   1, 3, 2, 255,      0, 0, 0, 0,
+            0, 0, 0, 255,    255, 0, 0, 255,    0, 255, 0, 255,    255, 255, 255, 255,
+        ];
+        assert_eq!(
+            process(FrameProcessor::new().flip_vertical().swizzle()),
+            (4, 2, PixelFormat::Rgba8, expected)
+        );
+    }
+
+    #[test]
+    fn converts_alpha() {
+        let (.., straight) = process(FrameProcessor::new().unpremultiply());
+        assert_eq!(&straight[16..20], [255, 0, 0, 128]);
+        assert_eq!(&straight[28..32], [0, 0, 0, 0]);
+        assert_eq!(&straight[..16], &IMAGE[..16]);
+
+        let (.., premultiplied) = process(FrameProcessor::new().unpremultiply().premultiply());
+        assert_eq!(premultiplied, IMAGE);
+    }
+
+    #[test]
+    fn keys_colors() {
+        // Only pure black, unlike the near black pixel.
+        let (.., keyed) = process(FrameProcessor::new().chroma_key([0, 0, 0], 0));
+        assert_eq!(&keyed[..4], [0; 4]);
+        assert_eq!(&keyed[24..28], &IMAGE[24..28]);
+
+        // Red, green and blue are in BGRA order in the image.
+        let (.., keyed) = process(FrameProcessor::new().chroma_key([250, 5, 0], 5));
+        assert_eq!(&keyed[4..8], [0; 4]);
+        assert_eq!(&keyed[..4], &IMAGE[..4]);
+
+        let (.., keyed) = process(FrameProcessor::new().luma_key(0, 3));
+        let mut expected = IMAGE;
+        expected[..4].fill(0);
+        expected[24..28].fill(0);
+        assert_eq!(keyed, expected);
+    }
+
+    #[test]
+    fn crops_and_downscales() {
+        let (width, height, _, cropped) =
+            process(FrameProcessor::new().crop(Rect::new(1, 1, 8, 8)));
+        assert_eq!((width, height), (3, 1));
+        assert_eq!(cropped, &IMAGE[20..32]);
+
+        let (width, height, _, downscaled) = process(FrameProcessor::new().downscale(2));
+        assert_eq!((width, height), (2, 1));
+        assert_eq!(downscaled, [48, 16, 80, 223, 64, 128, 64, 191]);
+        let (width, height, _, empty) = process(
+            FrameProcessor::new()
+                .crop(Rect::new(0, 0, 0, 4))
+                .downscale(2),
+        );
+        assert_eq!((width, height), (0, 1));
+        assert!(empty.is_empty());
+        assert!(FrameProcessor::new()
+            .downscale(0)
+            .process(&Frame::new(4, 2, PixelFormat::Bgra8, &IMAGE))
+            .is_err());
+    }
+
+    #[test]
+    fn maps_dirty_rects() {
+        let processor = FrameProcessor::new()
+            .flip_vertical()
+            .crop(Rect::new(2, 2, 100, 100))
+            .downscale(4);
+        assert_eq!(
+            processor.map_rect(Rect::new(5, 10, 3, 80), 64, 64),
+            Rect::new(0, 0, 2, 13)
+        );
+    }
+}
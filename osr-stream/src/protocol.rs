@@ -0,0 +1,433 @@
+//! Encoding of the messages described in the [crate] documentation.
+
+use std::io::{self, Read, Write};
+
+use crate::Codec;
+
+/// Identifies the stream in the [Hello] message.
+pub const MAGIC: [u8; 4] = *b"COSR";
+
+/// The newest protocol version implemented by this crate.
+pub const VERSION: u16 = 3;
+
+/// The oldest protocol version this crate can still talk to.
+pub const MIN_VERSION: u16 = 1;
+
+/// Messages larger than this are rejected as corrupt instead of being allocated.
+pub const MAX_MESSAGE_SIZE: u32 = 256 << 20;
+
+/// Size of the [Envelope] in front of every message.
+pub const ENVELOPE_SIZE: usize = 8;
+
+/// Size of the [Hello] payload.
+pub const HELLO_SIZE: usize = 8;
+
+/// Size of the [FrameHeader] in front of the pixel data of a frame message.
+pub const FRAME_HEADER_SIZE: usize = 24;
+
+/// Size of an encoded [Rect].
+pub const RECT_SIZE: usize = 16;
+
+/// The first protocol version with delta frames.
+pub const DELTA_VERSION: u16 = 2;
+
+/// The first protocol version with input messages.
+pub const INPUT_VERSION: u16 = 3;
+
+/// Frame header flag for delta frames.
+const FLAG_DELTA: u16 = 1;
+
+/// The type of a message, in the first byte of its [Envelope].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum MessageKind {
+    Hello = 0,
+    Frame = 1,
+    /// An [InputEvent](crate::InputEvent) sent by the consumer.
+    Input = 2,
+}
+
+impl MessageKind {
+    pub fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            0 => Some(Self::Hello),
+            1 => Some(Self::Frame),
+            2 => Some(Self::Input),
+            _ => None,
+        }
+    }
+}
+
+/// The message type and payload length in front of every message. Receivers skip messages of
+/// unknown kinds, so newer peers can add message types without breaking older ones.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Envelope {
+    pub kind: u8,
+    pub length: u32,
+}
+
+impl Envelope {
+    pub fn new(kind: MessageKind, length: usize) -> io::Result<Self> {
+        let length = u32::try_from(length)
+            .ok()
+            .filter(|length| *length <= MAX_MESSAGE_SIZE)
+            .ok_or_else(|| invalid_input("message too large"))?;
+        Ok(Self {
+            kind: kind as u8,
+            length,
+        })
+    }
+
+    pub fn encode(&self) -> [u8; ENVELOPE_SIZE] {
+        let mut bytes = [0; ENVELOPE_SIZE];
+        bytes[0] = self.kind;
+        bytes[4..8].copy_from_slice(&self.length.to_le_bytes());
+        bytes
+    }
+
+    pub fn decode(bytes: &[u8; ENVELOPE_SIZE]) -> io::Result<Self> {
+        let length = u32::from_le_bytes(bytes[4..8].try_into().unwrap());
+        if length > MAX_MESSAGE_SIZE {
+            return Err(invalid_data("message too large"));
+        }
+        Ok(Self {
+            kind: bytes[0],
+            length,
+        })
+    }
+
+    pub fn read_from(reader: &mut impl Read) -> io::Result<Self> {
+        let mut bytes = [0; ENVELOPE_SIZE];
+        reader.read_exact(&mut bytes)?;
+        Self::decode(&bytes)
+    }
+}
+
+/// The first message sent by each side of a connection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Hello {
+    /// The newest protocol version supported by the sender.
+    pub version: u16,
+    /// The [Codec::bit]s of the codecs supported by the sender.
+    pub codecs: u16,
+}
+
+impl Default for Hello {
+    fn default() -> Self {
+        Self {
+            version: VERSION,
+            codecs: Codec::available(),
+        }
+    }
+}
+
+impl Hello {
+    pub fn encode(&self) -> [u8; HELLO_SIZE] {
+        let mut bytes = [0; HELLO_SIZE];
+        bytes[0..4].copy_from_slice(&MAGIC);
+        bytes[4..6].copy_from_slice(&self.version.to_le_bytes());
+        bytes[6..8].copy_from_slice(&self.codecs.to_le_bytes());
+        bytes
+    }
+
+    /// Decode a hello payload. Payloads may be longer than [HELLO_SIZE] in newer versions, the
+    /// extra bytes are ignored.
+    pub fn decode(bytes: &[u8]) -> io::Result<Self> {
+        if bytes.len() < HELLO_SIZE || bytes[0..4] != MAGIC {
+            return Err(invalid_data("not an offscreen frame stream"));
+        }
+        Ok(Self {
+            version: u16::from_le_bytes([bytes[4], bytes[5]]),
+            codecs: u16::from_le_bytes([bytes[6], bytes[7]]),
+        })
+    }
+
+    /// The version and codecs used on a connection with a peer which sent `peer`.
+    pub fn negotiate(&self, peer: &Hello) -> io::Result<Hello> {
+        let version = self.version.min(peer.version);
+        if version < MIN_VERSION {
+            return Err(invalid_data(format!(
+                "unsupported protocol version {}",
+                peer.version
+            )));
+        }
+        Ok(Hello {
+            version,
+            codecs: self.codecs & peer.codecs,
+        })
+    }
+}
+
+/// Exchange [Hello] messages over `stream` and return the [negotiated](Hello::negotiate)
+/// version and codecs.
+pub fn handshake(stream: &mut (impl Read + Write)) -> io::Result<Hello> {
+    let hello = Hello::default();
+    stream.write_all(&Envelope::new(MessageKind::Hello, HELLO_SIZE)?.encode())?;
+    stream.write_all(&hello.encode())?;
+    stream.flush()?;
+
+    let envelope = Envelope::read_from(stream)?;
+    if envelope.kind != MessageKind::Hello as u8 || envelope.length > 1024 {
+        return Err(invalid_data("expected hello message"));
+    }
+    let mut payload = vec![0; envelope.length as usize];
+    stream.read_exact(&mut payload)?;
+    hello.negotiate(&Hello::decode(&payload)?)
+}
+
+/// The layout of the pixel data.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum PixelFormat {
+    /// 8-bit blue, green, red, alpha, the format CEF paints in.
+    #[default]
+    Bgra8 = 0,
+    /// 8-bit red, green, blue, alpha.
+    Rgba8 = 1,
+}
+
+impl PixelFormat {
+    pub fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            0 => Some(Self::Bgra8),
+            1 => Some(Self::Rgba8),
+            _ => None,
+        }
+    }
+
+    pub fn bytes_per_pixel(self) -> usize {
+        4
+    }
+}
+
+/// The header of a frame message. It's followed by `stride * height` bytes of pixel data for
+/// keyframes, or the changed regions for delta frames.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct FrameHeader {
+    /// Incremented for every frame written to the connection.
+    pub sequence: u32,
+    pub width: u32,
+    pub height: u32,
+    /// Bytes per row, at least `width` times the bytes per pixel.
+    pub stride: u32,
+    pub format: PixelFormat,
+    /// Only the changed regions of the frame follow, see [crate::delta].
+    pub delta: bool,
+    /// How the rest of the message is compressed.
+    pub codec: Codec,
+}
+
+impl FrameHeader {
+    pub fn encode(&self) -> [u8; FRAME_HEADER_SIZE] {
+        let mut bytes = [0; FRAME_HEADER_SIZE];
+        bytes[0..4].copy_from_slice(&self.sequence.to_le_bytes());
+        bytes[4..8].copy_from_slice(&self.width.to_le_bytes());
+        bytes[8..12].copy_from_slice(&self.height.to_le_bytes());
+        bytes[12..16].copy_from_slice(&self.stride.to_le_bytes());
+        bytes[16] = self.format as u8;
+        bytes[17] = self.codec as u8;
+        let flags = if self.delta { FLAG_DELTA } else { 0 };
+        bytes[18..20].copy_from_slice(&flags.to_le_bytes());
+        bytes
+    }
+
+    pub fn decode(bytes: &[u8; FRAME_HEADER_SIZE]) -> io::Result<Self> {
+        let u32_at =
+            |offset: usize| u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap());
+        let flags = u16::from_le_bytes([bytes[18], bytes[19]]);
+        if flags & !FLAG_DELTA != 0 {
+            return Err(invalid_data("unknown frame flags"));
+        }
+        Ok(Self {
+            sequence: u32_at(0),
+            width: u32_at(4),
+            height: u32_at(8),
+            stride: u32_at(12),
+            format: PixelFormat::from_u8(bytes[16])
+                .ok_or_else(|| invalid_data("unknown pixel format"))?,
+            delta: flags & FLAG_DELTA != 0,
+            codec: Codec::from_u8(bytes[17]).ok_or_else(|| invalid_data("unknown codec"))?,
+        })
+    }
+
+    /// The length of the pixel data of a keyframe, before compression.
+    pub fn data_len(&self) -> usize {
+        self.stride as usize * self.height as usize
+    }
+
+    /// Check that the rows fit the stride, like [Frame::validate] does for frames being written.
+    pub fn validate(&self) -> io::Result<()> {
+        let row_len = self.width as u64 * self.format.bytes_per_pixel() as u64;
+        if u64::from(self.stride) < row_len {
+            return Err(invalid_data("stride shorter than a row"));
+        }
+        Ok(())
+    }
+}
+
+/// A region of a frame in pixels.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rect {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Rect {
+    pub fn new(x: u32, y: u32, width: u32, height: u32) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.width == 0 || self.height == 0
+    }
+
+    pub fn area(&self) -> u64 {
+        u64::from(self.width) * u64::from(self.height)
+    }
+
+    /// The part of this rect inside a frame of `width` by `height` pixels.
+    pub fn clip(&self, width: u32, height: u32) -> Self {
+        let x = self.x.min(width);
+        let y = self.y.min(height);
+        Self {
+            x,
+            y,
+            width: self.width.min(width - x),
+            height: self.height.min(height - y),
+        }
+    }
+
+    /// The smallest rect containing both rects.
+    pub fn union(&self, other: &Self) -> Self {
+        let x = self.x.min(other.x);
+        let y = self.y.min(other.y);
+        let right = (self.x + self.width).max(other.x + other.width);
+        let bottom = (self.y + self.height).max(other.y + other.height);
+        Self::new(x, y, right - x, bottom - y)
+    }
+
+    pub fn encode(&self) -> [u8; RECT_SIZE] {
+        let mut bytes = [0; RECT_SIZE];
+        bytes[0..4].copy_from_slice(&self.x.to_le_bytes());
+        bytes[4..8].copy_from_slice(&self.y.to_le_bytes());
+        bytes[8..12].copy_from_slice(&self.width.to_le_bytes());
+        bytes[12..16].copy_from_slice(&self.height.to_le_bytes());
+        bytes
+    }
+
+    pub fn decode(bytes: &[u8; RECT_SIZE]) -> Self {
+        let u32_at =
+            |offset: usize| u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap());
+        Self::new(u32_at(0), u32_at(4), u32_at(8), u32_at(12))
+    }
+}
+
+/// A frame of pixels, borrowed from the producer's buffer or the reader's receive buffer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Frame<'a> {
+    pub width: u32,
+    pub height: u32,
+    /// Bytes per row, at least `width` times the bytes per pixel.
+    pub stride: u32,
+    pub format: PixelFormat,
+    pub data: &'a [u8],
+}
+
+impl<'a> Frame<'a> {
+    /// A frame with tightly packed rows.
+    pub fn new(width: u32, height: u32, format: PixelFormat, data: &'a [u8]) -> Self {
+        Self {
+            width,
+            height,
+            stride: width * format.bytes_per_pixel() as u32,
+            format,
+            data,
+        }
+    }
+
+    /// The pixels of row `y`, without padding.
+    pub fn row(&self, y: u32) -> &'a [u8] {
+        let start = y as usize * self.stride as usize;
+        &self.data[start..start + self.width as usize * self.format.bytes_per_pixel()]
+    }
+
+    /// Check that the dimensions match the length of the data.
+    pub fn validate(&self) -> io::Result<()> {
+        let row_len = self.width as usize * self.format.bytes_per_pixel();
+        if (self.stride as usize) < row_len {
+            return Err(invalid_input("stride shorter than a row"));
+        }
+        if self.data.len() != self.stride as usize * self.height as usize {
+            return Err(invalid_input("data length does not match the frame size"));
+        }
+        Ok(())
+    }
+}
+
+pub(crate) fn invalid_data(message: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.into())
+}
+
+pub(crate) fn invalid_input(message: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, message.into())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn round_trips_headers() {
+        let envelope = Envelope::new(MessageKind::Frame, 1234).unwrap();
+        assert_eq!(Envelope::decode(&envelope.encode()).unwrap(), envelope);
+
+        let hello = Hello {
+            version: 7,
+            codecs: 0b101,
+        };
+        assert_eq!(Hello::decode(&hello.encode()).unwrap(), hello);
+        assert!(Hello::decode(b"NOPE\x01\0\0\0").is_err());
+
+        let header = FrameHeader {
+            sequence: 42,
+            width: 2400,
+            height: 1200,
+            stride: 9600,
+            format: PixelFormat::Rgba8,
+            delta: true,
+            codec: Codec::Qoi,
+        };
+        assert_eq!(FrameHeader::decode(&header.encode()).unwrap(), header);
+
+        let rect = Rect::new(1, 2, 3, 4);
+        assert_eq!(Rect::decode(&rect.encode()), rect);
+    }
+
+    #[test]
+    fn negotiates_version() {
+        let hello = Hello::default();
+        let peer = Hello {
+            version: VERSION + 1,
+            codecs: Codec::None.bit() | 1 << 15,
+        };
+        assert_eq!(
+            hello.negotiate(&peer).unwrap(),
+            Hello {
+                version: VERSION,
+                codecs: Codec::None.bit(),
+            }
+        );
+        let peer = Hello {
+            version: 0,
+            codecs: 0,
+        };
+        assert!(hello.negotiate(&peer).is_err());
+    }
+}
@@ -0,0 +1,324 @@
+use std::io::{self, Read, Write};
+
+use crate::{delta::Decoder, protocol::*, Codec, InputEvent, Source};
+
+/// Reads frames from a connected stream, e.g. a socket or pipe. Delta frames are applied to the
+/// previous frame, so [Reader::read_frame] always returns full frames.
+pub struct Reader<S> {
+    stream: S,
+    version: u16,
+    header: FrameHeader,
+    decoder: Decoder,
+    /// The body of the last delta or compressed frame.
+    buffer: Vec<u8>,
+    /// The last decompressed body.
+    decompressed: Vec<u8>,
+}
+
+impl<S: Read + Write> Reader<S> {
+    /// Perform the [handshake] with the writer on the other end of `stream`.
+    pub fn new(mut stream: S) -> io::Result<Self> {
+        let version = handshake(&mut stream)?.version;
+        Ok(Self {
+            stream,
+            version,
+            header: Default::default(),
+            decoder: Decoder::new(),
+            buffer: Vec::new(),
+            decompressed: Vec::new(),
+        })
+    }
+
+    /// The negotiated protocol version.
+    pub fn version(&self) -> u16 {
+        self.version
+    }
+
+    /// The header of the last frame returned by [Reader::read_frame].
+    pub fn header(&self) -> &FrameHeader {
+        &self.header
+    }
+
+    /// The regions which changed with the last frame returned by [Reader::read_frame]. Covers
+    /// the whole frame for keyframes.
+    pub fn dirty_rects(&self) -> &[Rect] {
+        self.decoder.dirty_rects()
+    }
+
+    pub fn get_ref(&self) -> &S {
+        &self.stream
+    }
+
+    pub fn into_inner(self) -> S {
+        self.stream
+    }
+
+    /// Block until the next frame arrives. The frame borrows the receive buffer, which is
+    /// reused for the following frames. Delta frames received before the first keyframe are
+    /// skipped.
+    pub fn read_frame(&mut self) -> io::Result<Frame<'_>> {
+        loop {
+            let envelope = Envelope::read_from(&mut self.stream)?;
+            if envelope.kind != MessageKind::Frame as u8 {
+                self.skip(envelope.length)?;
+                continue;
+            }
+
+            let mut header = [0; FRAME_HEADER_SIZE];
+            if (envelope.length as usize) < FRAME_HEADER_SIZE {
+                return Err(invalid_data("truncated frame message"));
+            }
+            self.stream.read_exact(&mut header)?;
+            let header = FrameHeader::decode(&header)?;
+            header.validate()?;
+            let data_len = envelope.length as usize - FRAME_HEADER_SIZE;
+            if header.delta && self.decoder.frame().is_none() {
+                self.skip(data_len as u32)?;
+                continue;
+            }
+
+            // Uncompressed keyframes are read into the decoder directly.
+            if !header.delta && header.codec == Codec::None {
+                if data_len != header.data_len() {
+                    return Err(invalid_data("frame data length does not match the header"));
+                }
+                self.stream.read_exact(self.decoder.keyframe(header)?)?;
+                self.header = header;
+                return Ok(self.decoder.frame().unwrap());
+            }
+
+            self.buffer.resize(data_len, 0);
+            self.stream.read_exact(&mut self.buffer)?;
+            let body = if header.codec == Codec::None {
+                &self.buffer
+            } else {
+                header
+                    .codec
+                    .decompress(&self.buffer, &mut self.decompressed)?;
+                &self.decompressed
+            };
+            if header.delta {
+                self.decoder.apply(header, body)?;
+            } else {
+                if body.len() != header.data_len() {
+                    return Err(invalid_data("frame data length does not match the header"));
+                }
+                self.decoder.keyframe(header)?.copy_from_slice(body);
+            }
+            self.header = header;
+            return Ok(self.decoder.frame().unwrap());
+        }
+    }
+}
+
+impl<S: Read + Write> Reader<S> {
+    /// Send an input event to the writer, if it supports input.
+    pub fn send_input(&mut self, event: &InputEvent) -> io::Result<()> {
+        if self.version < INPUT_VERSION {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                format!("protocol version {} has no input", self.version),
+            ));
+        }
+        let payload = event.encode();
+        let envelope = Envelope::new(MessageKind::Input, payload.len())?;
+        self.stream.write_all(&envelope.encode())?;
+        self.stream.write_all(&payload)?;
+        self.stream.flush()
+    }
+}
+
+impl<S: Read> Reader<S> {
+    fn skip(&mut self, len: u32) -> io::Result<()> {
+        io::copy(&mut (&mut self.stream).take(len.into()), &mut io::sink())?;
+        Ok(())
+    }
+}
+
+impl<S: Read + Write + Send> Source for Reader<S> {
+    fn recv(&mut self) -> io::Result<Frame<'_>> {
+        self.read_frame()
+    }
+
+    fn send_input(&mut self, event: &InputEvent) -> io::Result<()> {
+        Reader::send_input(self, event)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::Writer;
+
+    /// One end of a connection, reading what the peer wrote earlier.
+    struct Duplex {
+        input: Cursor<Vec<u8>>,
+        output: Vec<u8>,
+    }
+
+    impl Duplex {
+        fn new(input: Vec<u8>) -> Self {
+            Self {
+                input: Cursor::new(input),
+                output: Vec::new(),
+            }
+        }
+    }
+
+    impl Read for Duplex {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            self.input.read(buf)
+        }
+    }
+
+    impl Write for Duplex {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.output.write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn hello() -> Vec<u8> {
+        let mut bytes = Envelope::new(MessageKind::Hello, HELLO_SIZE)
+            .unwrap()
+            .encode()
+            .to_vec();
+        bytes.extend(Hello::default().encode());
+        bytes
+    }
+
+    #[test]
+    fn reads_written_frames() {
+        let pixels: Vec<u8> = (0..2 * 3 * 4).collect();
+        let mut writer = Writer::new(Duplex::new(hello())).unwrap();
+        writer
+            .write_frame(&Frame::new(2, 3, PixelFormat::Bgra8, &pixels))
+            .unwrap();
+        writer
+            .write_frame(&Frame::new(3, 2, PixelFormat::Rgba8, &pixels))
+            .unwrap();
+
+        // The writer's output starts with its own hello, which the reader consumes.
+        let mut reader = Reader::new(Duplex::new(writer.into_inner().output)).unwrap();
+        let frame = reader.read_frame().unwrap();
+        assert_eq!(frame, Frame::new(2, 3, PixelFormat::Bgra8, &pixels));
+        assert_eq!(reader.header().sequence, 0);
+        let frame = reader.read_frame().unwrap();
+        assert_eq!(frame, Frame::new(3, 2, PixelFormat::Rgba8, &pixels));
+        assert_eq!(reader.header().sequence, 1);
+        assert!(reader.read_frame().is_err());
+    }
+
+    #[test]
+    fn reads_delta_frames() {
+        let first = vec![0; 4 * 4 * 4];
+        let second: Vec<u8> = (0..4 * 4 * 4).collect();
+        let dirty = [Rect::new(1, 1, 2, 1)];
+        let mut writer = Writer::new(Duplex::new(hello())).unwrap();
+        // Without a previous frame there is nothing to apply a delta to.
+        writer
+            .write_dirty(&Frame::new(4, 4, PixelFormat::Bgra8, &first), &dirty)
+            .unwrap();
+        writer
+            .write_dirty(&Frame::new(4, 4, PixelFormat::Bgra8, &second), &dirty)
+            .unwrap();
+
+        let mut reader = Reader::new(Duplex::new(writer.into_inner().output)).unwrap();
+        reader.read_frame().unwrap();
+        assert!(!reader.header().delta);
+        assert_eq!(reader.dirty_rects(), [Rect::new(0, 0, 4, 4)]);
+        let frame = reader.read_frame().unwrap().data.to_vec();
+        assert!(reader.header().delta);
+        assert_eq!(reader.dirty_rects(), dirty);
+        let mut expected = first;
+        expected[20..28].copy_from_slice(&second[20..28]);
+        assert_eq!(frame, expected);
+    }
+
+    #[test]
+    fn reads_compressed_frames() {
+        // Mostly one color, with a changed region.
+        let first = vec![0x40; 16 * 16 * 4];
+        let mut second = first.clone();
+        second[..8].copy_from_slice(&[1, 2, 3, 4, 5, 6, 7, 8]);
+        let dirty = [Rect::new(0, 0, 2, 1)];
+        for codec in Codec::ALL {
+            let mut writer = Writer::new(Duplex::new(hello())).unwrap();
+            writer.set_codec(codec);
+            let codec = if codec.is_available() {
+                codec
+            } else {
+                Codec::None
+            };
+            assert_eq!(writer.codec(), codec);
+            writer
+                .write_frame(&Frame::new(16, 16, PixelFormat::Bgra8, &first))
+                .unwrap();
+            writer
+                .write_dirty(&Frame::new(16, 16, PixelFormat::Bgra8, &second), &dirty)
+                .unwrap();
+
+            let mut reader = Reader::new(Duplex::new(writer.into_inner().output)).unwrap();
+            let frame = reader.read_frame().unwrap();
+            assert_eq!(frame.data, first);
+            assert_eq!(reader.header().codec, codec);
+            let frame = reader.read_frame().unwrap();
+            assert_eq!(frame.data, second);
+            assert!(reader.header().delta);
+        }
+    }
+
+    #[test]
+    fn sends_input() {
+        let event = InputEvent::MouseWheel {
+            x: 1.0,
+            y: 2.0,
+            modifiers: 0,
+            delta_x: 0,
+            delta_y: -120,
+        };
+        let mut reader = Reader::new(Duplex::new(hello())).unwrap();
+        reader.send_input(&event).unwrap();
+
+        // The input reader skips the reader's hello.
+        let output = Cursor::new(reader.into_inner().output);
+        let mut input = crate::input::InputReader::new(output);
+        assert_eq!(input.read_input().unwrap(), event);
+        assert!(input.read_input().is_err());
+    }
+
+    #[test]
+    fn rejects_invalid_frames() {
+        let mut writer = Writer::new(Duplex::new(hello())).unwrap();
+        assert!(writer
+            .write_frame(&Frame::new(2, 2, PixelFormat::Bgra8, &[0; 15]))
+            .is_err());
+        assert!(Writer::new(Duplex::new(b"garbage!".to_vec())).is_err());
+    }
+
+    #[test]
+    fn rejects_short_strides() {
+        // An uncompressed 4x1 keyframe with a stride of 0, so its data length of 0 matches.
+        let header = FrameHeader {
+            width: 4,
+            height: 1,
+            ..Default::default()
+        };
+        let mut input = hello();
+        input.extend(
+            Envelope::new(MessageKind::Frame, FRAME_HEADER_SIZE)
+                .unwrap()
+                .encode(),
+        );
+        input.extend(header.encode());
+
+        let mut reader = Reader::new(Duplex::new(input)).unwrap();
+        let err = reader.read_frame().unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+}
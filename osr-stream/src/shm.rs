@@ -0,0 +1,631 @@
+//! A POSIX shared memory transport, where the producer writes frames into a ring of slots
+//! which the consumer reads in place.
+//!
+//! The segment starts with a 64 byte header, followed by `slot_count` slots of
+//! `64 + slot_size` bytes (rounded up to a multiple of 64). All integers are native-endian,
+//! since both sides run on the same machine.
+//!
+//! | Offset | Size | Field                                                            |
+//! | ------ | ---- | ---------------------------------------------------------------- |
+//! | 0      | 4    | magic, `COSR`, written last when the segment is initialized      |
+//! | 4      | 2    | protocol version                                                 |
+//! | 6      | 2    | slot count                                                       |
+//! | 8      | 8    | slot size, the capacity for pixel data                           |
+//! | 16     | 8    | latest frame, `(sequence + 1) << 32 \| slot`, or 0 before the first |
+//! | 24     | 4    | low 32 bits of `latest >> 32`, a futex word on Linux             |
+//! | 28     | 4    | slot held by the reader, or `u32::MAX`                           |
+//! | 32     | 4    | non-zero once the producer closed the stream                     |
+//! | 36     | 4    | process ID of the producer, or 0 if unknown                      |
+//!
+//! Each slot starts with a 4 byte sequence lock, which is odd while the producer writes the
+//! slot, followed at offset 8 by the [FrameHeader] and at offset 64 by the pixel data.
+//!
+//! The producer never writes the latest slot or the slot held by the reader. To write a slot it
+//! makes the sequence lock odd and then checks the reader's slot, while the reader stores its
+//! slot and then checks the sequence lock, both sequentially consistent. Either the producer
+//! sees the claim and picks another slot, or the reader sees the odd lock and retries, so the
+//! reader never sees a partially written frame. With at least three slots, the producer always
+//! finds a free one. Only one reader can be attached at a time.
+//!
+//! A producer which crashes never closes the stream, so while the reader waits for a frame it
+//! also checks that the producer process still exists.
+
+use std::{
+    ffi::CString,
+    io,
+    ptr::NonNull,
+    sync::atomic::{AtomicI32, AtomicU32, AtomicU64, Ordering},
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    protocol::{
+        invalid_data, invalid_input, FrameHeader, FRAME_HEADER_SIZE, MAGIC, MIN_VERSION, VERSION,
+    },
+    Codec, Frame, Sink, Source, Transport,
+};
+
+const HEADER_SIZE: usize = 64;
+const SLOT_HEADER_SIZE: usize = 64;
+const NO_SLOT: u32 = u32::MAX;
+
+/// How long the reader sleeps between checks for a new frame where futexes aren't available.
+#[cfg(not(target_os = "linux"))]
+const POLL_INTERVAL: Duration = Duration::from_millis(1);
+/// How often the reader checks that the producer is still alive while it waits for a frame.
+const LIVENESS_INTERVAL: Duration = Duration::from_millis(100);
+
+#[repr(C)]
+struct Header {
+    magic: AtomicU32,
+    version: u16,
+    slot_count: u16,
+    slot_size: u64,
+    latest: AtomicU64,
+    futex: AtomicU32,
+    reader_slot: AtomicU32,
+    closed: AtomicU32,
+    producer: AtomicI32,
+}
+
+const _: () = assert!(std::mem::size_of::<Header>() <= HEADER_SIZE);
+
+fn slot_stride(slot_size: usize) -> usize {
+    SLOT_HEADER_SIZE + slot_size.div_ceil(64) * 64
+}
+
+/// A shared memory segment mapped into this process.
+struct Mapping {
+    ptr: NonNull<u8>,
+    len: usize,
+    /// The layout from the header, read once after it was validated, since the other process
+    /// could change the header later.
+    slot_count: usize,
+    slot_size: usize,
+}
+
+// The mapping is only accessed through atomics and the slot protocol.
+unsafe impl Send for Mapping {}
+
+impl Mapping {
+    fn new(fd: libc::c_int, len: usize) -> io::Result<Self> {
+        let ptr = unsafe {
+            libc::mmap(
+                std::ptr::null_mut(),
+                len,
+                libc::PROT_READ | libc::PROT_WRITE,
+                libc::MAP_SHARED,
+                fd,
+                0,
+            )
+        };
+        if ptr == libc::MAP_FAILED {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(Self {
+            ptr: NonNull::new(ptr.cast()).expect("mmap returned null"),
+            len,
+            slot_count: 0,
+            slot_size: 0,
+        })
+    }
+
+    fn header(&self) -> &Header {
+        unsafe { &*self.ptr.as_ptr().cast::<Header>() }
+    }
+
+    fn slot_ptr(&self, slot: usize) -> *mut u8 {
+        debug_assert!(slot < self.slot_count);
+        unsafe {
+            self.ptr
+                .as_ptr()
+                .add(HEADER_SIZE + slot * slot_stride(self.slot_size))
+        }
+    }
+
+    fn slot_lock(&self, slot: usize) -> &AtomicU32 {
+        unsafe { &*self.slot_ptr(slot).cast::<AtomicU32>() }
+    }
+
+    fn slot_header(&self, slot: usize) -> *mut [u8; FRAME_HEADER_SIZE] {
+        unsafe { self.slot_ptr(slot).add(8).cast() }
+    }
+
+    fn slot_data(&self, slot: usize) -> *mut u8 {
+        unsafe { self.slot_ptr(slot).add(SLOT_HEADER_SIZE) }
+    }
+}
+
+impl Drop for Mapping {
+    fn drop(&mut self) {
+        unsafe {
+            libc::munmap(self.ptr.as_ptr().cast(), self.len);
+        }
+    }
+}
+
+fn shm_name(name: &str) -> io::Result<CString> {
+    let name = if name.starts_with('/') {
+        name.to_string()
+    } else {
+        format!("/{name}")
+    };
+    CString::new(name).map_err(|_| invalid_input("shared memory name contains NUL"))
+}
+
+fn shm_open(name: &CString, flags: libc::c_int) -> io::Result<std::os::fd::OwnedFd> {
+    use std::os::fd::FromRawFd;
+
+    #[cfg(target_vendor = "apple")]
+    let fd = unsafe { libc::shm_open(name.as_ptr(), flags, 0o600 as libc::c_uint) };
+    #[cfg(not(target_vendor = "apple"))]
+    let fd = unsafe { libc::shm_open(name.as_ptr(), flags, 0o600) };
+    if fd < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(unsafe { std::os::fd::OwnedFd::from_raw_fd(fd) })
+}
+
+/// Whether the process `pid` exists. A `pid` of 0 is unknown and assumed to be alive.
+fn is_alive(pid: libc::pid_t) -> bool {
+    pid == 0
+        || unsafe { libc::kill(pid, 0) } == 0
+        || io::Error::last_os_error().raw_os_error() != Some(libc::ESRCH)
+}
+
+/// Wait until `futex` no longer holds `expected`, for at most [LIVENESS_INTERVAL].
+#[cfg(target_os = "linux")]
+fn futex_wait(futex: &AtomicU32, expected: u32) {
+    let timeout = libc::timespec {
+        tv_sec: 0,
+        tv_nsec: LIVENESS_INTERVAL.as_nanos() as _,
+    };
+    unsafe {
+        libc::syscall(
+            libc::SYS_futex,
+            futex.as_ptr(),
+            libc::FUTEX_WAIT,
+            expected,
+            &timeout as *const libc::timespec,
+        );
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn futex_wake(futex: &AtomicU32) {
+    unsafe {
+        libc::syscall(libc::SYS_futex, futex.as_ptr(), libc::FUTEX_WAKE, i32::MAX);
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn futex_wait(_futex: &AtomicU32, _expected: u32) {
+    thread::sleep(POLL_INTERVAL);
+}
+
+#[cfg(not(target_os = "linux"))]
+fn futex_wake(_futex: &AtomicU32) {}
+
+/// Creates a shared memory segment for each consumer session.
+pub struct ShmTransport {
+    name: String,
+    slot_count: usize,
+    slot_size: usize,
+}
+
+impl ShmTransport {
+    /// Enough for a 4K BGRA frame.
+    pub const DEFAULT_SLOT_SIZE: usize = 3840 * 2160 * 4;
+    pub const DEFAULT_SLOT_COUNT: usize = 3;
+
+    /// `name` is the POSIX shared memory object name, a leading `/` is added if missing.
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            slot_count: Self::DEFAULT_SLOT_COUNT,
+            slot_size: Self::DEFAULT_SLOT_SIZE,
+        }
+    }
+
+    /// The number of slots in the ring, at least three.
+    pub fn slot_count(mut self, slot_count: usize) -> Self {
+        self.slot_count = slot_count.max(3);
+        self
+    }
+
+    /// The capacity for pixel data of each slot, which limits the frame size. Larger frames
+    /// are dropped, the consumer stays attached.
+    pub fn slot_size(mut self, slot_size: usize) -> Self {
+        self.slot_size = slot_size;
+        self
+    }
+}
+
+impl Transport for ShmTransport {
+    /// Create the segment. Consumers attach to it with [ShmReader::open] at any time, so this
+    /// doesn't wait for one.
+    fn accept(&mut self) -> io::Result<Box<dyn Sink>> {
+        Ok(Box::new(ShmWriter::create(
+            &self.name,
+            self.slot_count,
+            self.slot_size,
+        )?))
+    }
+}
+
+/// The producer side of a shared memory segment. Dropping it marks the stream as closed and
+/// unlinks the segment.
+pub struct ShmWriter {
+    mapping: Mapping,
+    name: CString,
+    next_slot: usize,
+    sequence: u64,
+}
+
+impl ShmWriter {
+    /// Create the segment, replacing any stale segment with the same name.
+    pub fn create(name: &str, slot_count: usize, slot_size: usize) -> io::Result<Self> {
+        let slot_count = u16::try_from(slot_count.max(3))
+            .map_err(|_| invalid_input("too many shared memory slots"))?;
+        let len = HEADER_SIZE + usize::from(slot_count) * slot_stride(slot_size);
+        let name = shm_name(name)?;
+
+        unsafe {
+            libc::shm_unlink(name.as_ptr());
+        }
+        let fd = shm_open(&name, libc::O_CREAT | libc::O_EXCL | libc::O_RDWR)?;
+        let mut mapping = {
+            use std::os::fd::AsRawFd;
+
+            let result = unsafe { libc::ftruncate(fd.as_raw_fd(), len as libc::off_t) };
+            if result != 0 {
+                let err = io::Error::last_os_error();
+                unsafe {
+                    libc::shm_unlink(name.as_ptr());
+                }
+                return Err(err);
+            }
+            Mapping::new(fd.as_raw_fd(), len)?
+        };
+        mapping.slot_count = usize::from(slot_count);
+        mapping.slot_size = slot_size;
+
+        unsafe {
+            let header = mapping.ptr.as_ptr().cast::<Header>();
+            (*header).version = VERSION;
+            (*header).slot_count = slot_count;
+            (*header).slot_size = slot_size as u64;
+        }
+        let header = mapping.header();
+        header.reader_slot.store(NO_SLOT, Ordering::Relaxed);
+        header
+            .producer
+            .store(std::process::id() as libc::pid_t, Ordering::Relaxed);
+        header
+            .magic
+            .store(u32::from_ne_bytes(MAGIC), Ordering::Release);
+
+        Ok(Self {
+            mapping,
+            name,
+            next_slot: 0,
+            sequence: 0,
+        })
+    }
+
+    /// Copy `frame` into a free slot and publish it as the latest frame.
+    pub fn write_frame(&mut self, frame: &Frame) -> io::Result<()> {
+        frame.validate()?;
+        if frame.data.len() > self.mapping.slot_size {
+            return Err(invalid_input("frame larger than the shared memory slots"));
+        }
+
+        let header = self.mapping.header();
+        let slot_count = self.mapping.slot_count;
+        let latest = header.latest.load(Ordering::SeqCst);
+        let latest_slot = (latest != 0).then_some((latest & 0xffff_ffff) as usize);
+        let slot = (0..slot_count)
+            .map(|offset| (self.next_slot + offset) % slot_count)
+            .filter(|slot| Some(*slot) != latest_slot)
+            .find(|slot| self.try_lock(*slot))
+            .ok_or_else(|| io::Error::new(io::ErrorKind::WouldBlock, "no free slot"))?;
+
+        let frame_header = FrameHeader {
+            sequence: self.sequence as u32,
+            width: frame.width,
+            height: frame.height,
+            stride: frame.stride,
+            format: frame.format,
+            delta: false,
+            codec: Codec::None,
+        };
+        unsafe {
+            *self.mapping.slot_header(slot) = frame_header.encode();
+            std::ptr::copy_nonoverlapping(
+                frame.data.as_ptr(),
+                self.mapping.slot_data(slot),
+                frame.data.len(),
+            );
+        }
+        self.mapping.slot_lock(slot).fetch_add(1, Ordering::Release);
+
+        let published = self.sequence + 1;
+        header
+            .latest
+            .store(published << 32 | slot as u64, Ordering::SeqCst);
+        header.futex.store(published as u32, Ordering::SeqCst);
+        futex_wake(&header.futex);
+
+        self.sequence += 1;
+        self.next_slot = (slot + 1) % slot_count;
+        Ok(())
+    }
+
+    /// Make the slot's sequence lock odd, unless the reader holds the slot.
+    fn try_lock(&self, slot: usize) -> bool {
+        let header = self.mapping.header();
+        if header.reader_slot.load(Ordering::SeqCst) == slot as u32 {
+            return false;
+        }
+
+        let lock = self.mapping.slot_lock(slot);
+        lock.fetch_add(1, Ordering::SeqCst);
+        if header.reader_slot.load(Ordering::SeqCst) == slot as u32 {
+            lock.fetch_sub(1, Ordering::SeqCst);
+            return false;
+        }
+        true
+    }
+}
+
+impl Sink for ShmWriter {
+    fn send(&mut self, frame: &Frame) -> io::Result<()> {
+        self.write_frame(frame)
+    }
+}
+
+impl Drop for ShmWriter {
+    fn drop(&mut self) {
+        let header = self.mapping.header();
+        header.closed.store(1, Ordering::SeqCst);
+        header.futex.fetch_add(1, Ordering::SeqCst);
+        futex_wake(&header.futex);
+        unsafe {
+            libc::shm_unlink(self.name.as_ptr());
+        }
+    }
+}
+
+/// The consumer side of a shared memory segment. Frames are read in place, the slot of the
+/// last frame returned by [ShmReader::read_frame] is held until the next call.
+pub struct ShmReader {
+    mapping: Mapping,
+    last_sequence: u64,
+}
+
+impl ShmReader {
+    /// Attach to the segment created by the producer.
+    pub fn open(name: &str) -> io::Result<Self> {
+        use std::os::fd::AsRawFd;
+
+        let fd = shm_open(&shm_name(name)?, libc::O_RDWR)?;
+        let mut stat = unsafe { std::mem::zeroed::<libc::stat>() };
+        if unsafe { libc::fstat(fd.as_raw_fd(), &mut stat) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let len = stat.st_size as usize;
+        if len < HEADER_SIZE {
+            return Err(invalid_data("shared memory segment too small"));
+        }
+
+        let mut mapping = Mapping::new(fd.as_raw_fd(), len)?;
+        let header = mapping.header();
+        if header.magic.load(Ordering::Acquire) != u32::from_ne_bytes(MAGIC) {
+            return Err(invalid_data("not an offscreen frame stream"));
+        }
+        if !(MIN_VERSION..=VERSION).contains(&header.version) {
+            return Err(invalid_data(format!(
+                "unsupported protocol version {}",
+                header.version
+            )));
+        }
+        let slot_count = usize::from(header.slot_count);
+        let slot_size = usize::try_from(header.slot_size).unwrap_or(usize::MAX);
+        let required = (slot_size <= len)
+            .then(|| slot_count.checked_mul(slot_stride(slot_size)))
+            .flatten()
+            .and_then(|slots| slots.checked_add(HEADER_SIZE));
+        if slot_count < 3 || required.is_none_or(|required| required > len) {
+            return Err(invalid_data("invalid shared memory layout"));
+        }
+        mapping.slot_count = slot_count;
+        mapping.slot_size = slot_size;
+
+        Ok(Self {
+            mapping,
+            last_sequence: 0,
+        })
+    }
+
+    /// Block until a frame newer than the last one is published. Fails with
+    /// [io::ErrorKind::UnexpectedEof] once the producer closed the stream or exited.
+    pub fn read_frame(&mut self) -> io::Result<Frame<'_>> {
+        let header = self.mapping.header();
+        header.reader_slot.store(NO_SLOT, Ordering::SeqCst);
+
+        let mut checked = Instant::now();
+        let slot = loop {
+            if header.closed.load(Ordering::SeqCst) != 0 {
+                return Err(io::ErrorKind::UnexpectedEof.into());
+            }
+            let futex = header.futex.load(Ordering::SeqCst);
+            let latest = header.latest.load(Ordering::SeqCst);
+            if latest >> 32 <= self.last_sequence {
+                if checked.elapsed() >= LIVENESS_INTERVAL {
+                    if !is_alive(header.producer.load(Ordering::Relaxed)) {
+                        return Err(io::Error::new(
+                            io::ErrorKind::UnexpectedEof,
+                            "producer exited without closing the stream",
+                        ));
+                    }
+                    checked = Instant::now();
+                }
+                futex_wait(&header.futex, futex);
+                continue;
+            }
+
+            let slot = (latest & 0xffff_ffff) as u32;
+            if slot as usize >= self.mapping.slot_count {
+                return Err(invalid_data("invalid shared memory slot"));
+            }
+            header.reader_slot.store(slot, Ordering::SeqCst);
+            let lock = self.mapping.slot_lock(slot as usize).load(Ordering::SeqCst);
+            if lock.is_multiple_of(2) {
+                break slot as usize;
+            }
+            // The producer started overwriting the slot before seeing our claim.
+            header.reader_slot.store(NO_SLOT, Ordering::SeqCst);
+            thread::yield_now();
+        };
+
+        let frame_header = FrameHeader::decode(unsafe { &*self.mapping.slot_header(slot) })?;
+        frame_header.validate()?;
+        if frame_header.data_len() > self.mapping.slot_size {
+            return Err(invalid_data("frame larger than the shared memory slots"));
+        }
+        self.last_sequence = u64::from(frame_header.sequence) + 1;
+        let data = unsafe {
+            std::slice::from_raw_parts(self.mapping.slot_data(slot), frame_header.data_len())
+        };
+        Ok(Frame {
+            width: frame_header.width,
+            height: frame_header.height,
+            stride: frame_header.stride,
+            format: frame_header.format,
+            data,
+        })
+    }
+}
+
+impl Source for ShmReader {
+    fn recv(&mut self) -> io::Result<Frame<'_>> {
+        self.read_frame()
+    }
+}
+
+impl Drop for ShmReader {
+    fn drop(&mut self) {
+        self.mapping
+            .header()
+            .reader_slot
+            .store(NO_SLOT, Ordering::SeqCst);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::PixelFormat;
+
+    fn name(test: &str) -> String {
+        format!("/cef-osr-stream-test-{test}-{}", std::process::id())
+    }
+
+    #[test]
+    fn reads_latest_frame_in_place() {
+        let name = name("latest");
+        let mut writer = ShmWriter::create(&name, 3, 64).unwrap();
+        let mut reader = ShmReader::open(&name).unwrap();
+
+        for value in 1..=4u8 {
+            writer
+                .write_frame(&Frame::new(2, 2, PixelFormat::Bgra8, &[value; 16]))
+                .unwrap();
+        }
+        let frame = reader.read_frame().unwrap();
+        assert_eq!(frame.data, [4; 16]);
+
+        // The held slot is skipped while the producer keeps writing.
+        for value in 5..=10u8 {
+            writer
+                .write_frame(&Frame::new(2, 2, PixelFormat::Bgra8, &[value; 16]))
+                .unwrap();
+        }
+        assert_eq!(reader.read_frame().unwrap().data, [10; 16]);
+
+        assert!(writer
+            .write_frame(&Frame::new(4, 5, PixelFormat::Bgra8, &[0; 80]))
+            .is_err());
+        drop(writer);
+        assert_eq!(
+            reader.read_frame().unwrap_err().kind(),
+            io::ErrorKind::UnexpectedEof
+        );
+    }
+
+    #[test]
+    fn detects_dead_producer() {
+        let name = name("dead");
+        let writer = ShmWriter::create(&name, 3, 64).unwrap();
+        let mut reader = ShmReader::open(&name).unwrap();
+
+        // Pretend the segment was created by a process which crashed.
+        let mut child = std::process::Command::new("true").spawn().unwrap();
+        child.wait().unwrap();
+        writer
+            .mapping
+            .header()
+            .producer
+            .store(child.id() as libc::pid_t, Ordering::Relaxed);
+
+        let err = reader.read_frame().unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+        drop(writer);
+    }
+
+    #[test]
+    fn keeps_layout_from_open() {
+        let name = name("layout");
+        let mut writer = ShmWriter::create(&name, 3, 64).unwrap();
+        let mut reader = ShmReader::open(&name).unwrap();
+
+        // A misbehaving producer grows the slots after the reader attached.
+        unsafe {
+            (*writer.mapping.ptr.as_ptr().cast::<Header>()).slot_size = 1 << 40;
+        }
+        writer
+            .write_frame(&Frame::new(2, 2, PixelFormat::Bgra8, &[1; 16]))
+            .unwrap();
+        assert_eq!(reader.read_frame().unwrap().data, [1; 16]);
+    }
+
+    #[test]
+    fn never_reads_torn_frames() {
+        let name = name("torn");
+        let mut writer = ShmWriter::create(&name, 3, 4096).unwrap();
+        let mut reader = ShmReader::open(&name).unwrap();
+
+        let producer = thread::spawn(move || {
+            for value in 0..2000u32 {
+                let data = [value as u8; 4096];
+                writer
+                    .write_frame(&Frame::new(32, 32, PixelFormat::Bgra8, &data))
+                    .unwrap();
+            }
+        });
+        loop {
+            match reader.read_frame() {
+                Ok(frame) => {
+                    let first = frame.data[0];
+                    assert!(frame.data.iter().all(|byte| *byte == first));
+                }
+                Err(err) => {
+                    assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+                    break;
+                }
+            }
+        }
+        producer.join().unwrap();
+    }
+}
@@ -0,0 +1,366 @@
+//! Transports which carry the stream between processes, selected at runtime with an
+//! [Endpoint].
+
+use std::{
+    fmt::{self, Display, Formatter},
+    io::{self, Read, Write},
+    net::{TcpListener, TcpStream, ToSocketAddrs},
+    path::PathBuf,
+    str::FromStr,
+};
+
+#[cfg(unix)]
+use std::{
+    os::unix::net::{UnixListener, UnixStream},
+    path::Path,
+};
+
+#[cfg(unix)]
+use crate::shm::{ShmReader, ShmTransport};
+use crate::{
+    input::InputReader,
+    protocol::{invalid_input, Rect, INPUT_VERSION},
+    Codec, Frame, InputReceiver, Reader, Sink, Source, Writer,
+};
+
+/// Accepts consumers on the producer side of the stream.
+pub trait Transport: Send {
+    /// Block until the next consumer connects and complete the handshake with it.
+    fn accept(&mut self) -> io::Result<Box<dyn Sink>>;
+}
+
+/// The address of a stream, parsed from a `scheme:address` string:
+///
+/// - `tcp:127.0.0.1:7878` for a TCP socket, intended for loopback addresses.
+/// - `pipe:petplay-webxr` for the Windows named pipe `\\.\pipe\petplay-webxr`.
+/// - `unix:/tmp/petplay-webxr.sock` for a Unix domain socket.
+/// - `shm:/petplay-webxr` for a POSIX shared memory segment, see the `shm` module.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Endpoint {
+    Tcp(String),
+    Pipe(String),
+    Unix(PathBuf),
+    Shm(String),
+}
+
+impl Endpoint {
+    /// Start accepting consumers on the producer side.
+    pub fn listen(&self) -> io::Result<Box<dyn Transport>> {
+        match self {
+            Self::Tcp(address) => Ok(Box::new(TcpTransport::bind(address.as_str())?)),
+            #[cfg(target_os = "windows")]
+            Self::Pipe(name) => Ok(Box::new(PipeTransport::new(name))),
+            #[cfg(not(target_os = "windows"))]
+            Self::Pipe(_) => Err(unsupported(self)),
+            #[cfg(unix)]
+            Self::Unix(path) => Ok(Box::new(UnixTransport::bind(path)?)),
+            #[cfg(unix)]
+            Self::Shm(name) => Ok(Box::new(ShmTransport::new(name))),
+            #[cfg(not(unix))]
+            Self::Unix(_) | Self::Shm(_) => Err(unsupported(self)),
+        }
+    }
+
+    /// Connect to the producer from the consumer side.
+    pub fn connect(&self) -> io::Result<Box<dyn Source>> {
+        match self {
+            Self::Tcp(address) => {
+                let stream = TcpStream::connect(address.as_str())?;
+                stream.set_nodelay(true)?;
+                Ok(Box::new(Reader::new(stream)?))
+            }
+            #[cfg(target_os = "windows")]
+            Self::Pipe(name) => {
+                let pipe = std::fs::OpenOptions::new()
+                    .read(true)
+                    .write(true)
+                    .open(pipe_path(name))?;
+                Ok(Box::new(Reader::new(pipe)?))
+            }
+            #[cfg(not(target_os = "windows"))]
+            Self::Pipe(_) => Err(unsupported(self)),
+            #[cfg(unix)]
+            Self::Unix(path) => Ok(Box::new(Reader::new(UnixStream::connect(path)?)?)),
+            #[cfg(unix)]
+            Self::Shm(name) => Ok(Box::new(ShmReader::open(name)?)),
+            #[cfg(not(unix))]
+            Self::Unix(_) | Self::Shm(_) => Err(unsupported(self)),
+        }
+    }
+}
+
+fn unsupported(endpoint: &Endpoint) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::Unsupported,
+        format!("{endpoint} is not supported on this platform"),
+    )
+}
+
+impl FromStr for Endpoint {
+    type Err = io::Error;
+
+    fn from_str(s: &str) -> io::Result<Self> {
+        let (scheme, address) = s
+            .split_once(':')
+            .filter(|(_, address)| !address.is_empty())
+            .ok_or_else(|| invalid_input(format!("invalid endpoint {s:?}")))?;
+        match scheme {
+            "tcp" => Ok(Self::Tcp(address.to_string())),
+            "pipe" => Ok(Self::Pipe(address.to_string())),
+            "unix" => Ok(Self::Unix(address.into())),
+            "shm" => Ok(Self::Shm(address.to_string())),
+            _ => Err(invalid_input(format!("unknown endpoint scheme {scheme:?}"))),
+        }
+    }
+}
+
+impl Display for Endpoint {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Tcp(address) => write!(f, "tcp:{address}"),
+            Self::Pipe(name) => write!(f, "pipe:{name}"),
+            Self::Unix(path) => write!(f, "unix:{}", path.display()),
+            Self::Shm(name) => write!(f, "shm:{name}"),
+        }
+    }
+}
+
+/// A [Writer] with a second handle to the same connection, for reading the consumer's input
+/// on another thread.
+struct Connection<S, R> {
+    writer: Writer<S>,
+    input: Option<R>,
+}
+
+impl<S: Read + Write + Send, R: Read + Send + 'static> Sink for Connection<S, R> {
+    fn send(&mut self, frame: &Frame) -> io::Result<()> {
+        self.writer.write_frame(frame)
+    }
+
+    fn send_dirty(&mut self, frame: &Frame, dirty: &[Rect]) -> io::Result<()> {
+        self.writer.write_dirty(frame, dirty)
+    }
+
+    fn set_codec(&mut self, codec: Codec) {
+        self.writer.set_codec(codec)
+    }
+
+    fn take_input(&mut self) -> Option<Box<dyn InputReceiver>> {
+        if self.writer.version() < INPUT_VERSION {
+            return None;
+        }
+        Some(Box::new(InputReader::new(self.input.take()?)))
+    }
+}
+
+/// Accepts consumers on a TCP socket.
+pub struct TcpTransport(TcpListener);
+
+impl TcpTransport {
+    pub fn bind(address: impl ToSocketAddrs) -> io::Result<Self> {
+        Ok(Self(TcpListener::bind(address)?))
+    }
+
+    pub fn local_addr(&self) -> io::Result<std::net::SocketAddr> {
+        self.0.local_addr()
+    }
+}
+
+impl Transport for TcpTransport {
+    fn accept(&mut self) -> io::Result<Box<dyn Sink>> {
+        let (stream, _) = self.0.accept()?;
+        stream.set_nodelay(true)?;
+        let writer = Writer::new(stream)?;
+        let input = writer.get_ref().try_clone()?;
+        Ok(Box::new(Connection {
+            writer,
+            input: Some(input),
+        }))
+    }
+}
+
+/// Accepts consumers on a Unix domain socket. The socket file is removed when the transport is
+/// dropped.
+#[cfg(unix)]
+pub struct UnixTransport {
+    listener: UnixListener,
+    path: PathBuf,
+}
+
+#[cfg(unix)]
+impl UnixTransport {
+    /// Bind the socket at `path`, replacing a stale socket left behind by a previous process.
+    pub fn bind(path: impl AsRef<Path>) -> io::Result<Self> {
+        use std::os::unix::fs::FileTypeExt;
+
+        let path = path.as_ref().to_path_buf();
+        if std::fs::symlink_metadata(&path).is_ok_and(|metadata| metadata.file_type().is_socket()) {
+            std::fs::remove_file(&path)?;
+        }
+        Ok(Self {
+            listener: UnixListener::bind(&path)?,
+            path,
+        })
+    }
+}
+
+#[cfg(unix)]
+impl Transport for UnixTransport {
+    fn accept(&mut self) -> io::Result<Box<dyn Sink>> {
+        let (stream, _) = self.listener.accept()?;
+        let writer = Writer::new(stream)?;
+        let input = writer.get_ref().try_clone()?;
+        Ok(Box::new(Connection {
+            writer,
+            input: Some(input),
+        }))
+    }
+}
+
+#[cfg(unix)]
+impl Drop for UnixTransport {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn pipe_path(name: &str) -> String {
+    format!(r"\\.\pipe\{name}")
+}
+
+/// Accepts consumers on a Windows named pipe, creating a new pipe instance for each one.
+#[cfg(target_os = "windows")]
+pub struct PipeTransport {
+    path: Vec<u16>,
+}
+
+#[cfg(target_os = "windows")]
+impl PipeTransport {
+    /// Size of the outbound pipe buffer, enough for a few full frames.
+    const OUT_BUFFER_SIZE: u32 = 64 << 20;
+    const IN_BUFFER_SIZE: u32 = 1 << 20;
+
+    /// `name` is the pipe name without the `\\.\pipe\` prefix.
+    pub fn new(name: &str) -> Self {
+        Self {
+            path: pipe_path(name).encode_utf16().chain([0]).collect(),
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+impl Transport for PipeTransport {
+    fn accept(&mut self) -> io::Result<Box<dyn Sink>> {
+        use std::{
+            fs::File,
+            os::windows::io::{AsRawHandle, FromRawHandle, OwnedHandle},
+            ptr,
+        };
+
+        use windows_sys::Win32::{
+            Foundation::{ERROR_PIPE_CONNECTED, INVALID_HANDLE_VALUE},
+            Storage::FileSystem::PIPE_ACCESS_DUPLEX,
+            System::Pipes::{
+                ConnectNamedPipe, CreateNamedPipeW, PIPE_READMODE_BYTE, PIPE_REJECT_REMOTE_CLIENTS,
+                PIPE_TYPE_BYTE, PIPE_UNLIMITED_INSTANCES, PIPE_WAIT,
+            },
+        };
+
+        let pipe = unsafe {
+            let handle = CreateNamedPipeW(
+                self.path.as_ptr(),
+                PIPE_ACCESS_DUPLEX,
+                PIPE_TYPE_BYTE | PIPE_READMODE_BYTE | PIPE_WAIT | PIPE_REJECT_REMOTE_CLIENTS,
+                PIPE_UNLIMITED_INSTANCES,
+                Self::OUT_BUFFER_SIZE,
+                Self::IN_BUFFER_SIZE,
+                0,
+                ptr::null(),
+            );
+            if handle == INVALID_HANDLE_VALUE {
+                return Err(io::Error::last_os_error());
+            }
+            File::from(OwnedHandle::from_raw_handle(handle))
+        };
+
+        let connected = unsafe { ConnectNamedPipe(pipe.as_raw_handle(), ptr::null_mut()) };
+        if connected == 0 {
+            // The client may connect between creating the pipe and waiting for it.
+            let err = io::Error::last_os_error();
+            if err.raw_os_error() != Some(ERROR_PIPE_CONNECTED as i32) {
+                return Err(err);
+            }
+        }
+        let writer = Writer::new(pipe)?;
+        let input = PipeInput(writer.get_ref().try_clone()?);
+        Ok(Box::new(Connection {
+            writer,
+            input: Some(input),
+        }))
+    }
+}
+
+/// Reads from a synchronous pipe handle without blocking writes on the same pipe, which a
+/// pending read would.
+#[cfg(target_os = "windows")]
+struct PipeInput(std::fs::File);
+
+#[cfg(target_os = "windows")]
+impl PipeInput {
+    const POLL_INTERVAL: std::time::Duration = std::time::Duration::from_millis(2);
+}
+
+#[cfg(target_os = "windows")]
+impl Read for PipeInput {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        use std::{os::windows::io::AsRawHandle, ptr};
+
+        use windows_sys::Win32::{Foundation::ERROR_BROKEN_PIPE, System::Pipes::PeekNamedPipe};
+
+        loop {
+            let mut available = 0;
+            let peeked = unsafe {
+                PeekNamedPipe(
+                    self.0.as_raw_handle(),
+                    ptr::null_mut(),
+                    0,
+                    ptr::null_mut(),
+                    &mut available,
+                    ptr::null_mut(),
+                )
+            };
+            if peeked == 0 {
+                let err = io::Error::last_os_error();
+                if err.raw_os_error() == Some(ERROR_BROKEN_PIPE as i32) {
+                    return Ok(0);
+                }
+                return Err(err);
+            }
+            if available > 0 {
+                let len = buf.len().min(available as usize);
+                return self.0.read(&mut buf[..len]);
+            }
+            std::thread::sleep(Self::POLL_INTERVAL);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parses_endpoints() {
+        for s in [
+            "tcp:127.0.0.1:7878",
+            "pipe:petplay-webxr",
+            "unix:/tmp/petplay-webxr.sock",
+            "shm:/petplay-webxr",
+        ] {
+            assert_eq!(s.parse::<Endpoint>().unwrap().to_string(), s);
+        }
+        assert!("tcp:".parse::<Endpoint>().is_err());
+        assert!("carrier-pigeon:coop".parse::<Endpoint>().is_err());
+    }
+}
@@ -0,0 +1,160 @@
+use std::io::{self, Read, Write};
+
+use crate::{delta, protocol::*, Codec, Sink};
+
+/// Writes frames to a connected stream, e.g. a socket or pipe.
+pub struct Writer<S> {
+    stream: S,
+    version: u16,
+    /// The [Codec::bit]s of the codecs supported by both sides.
+    codecs: u16,
+    codec: Codec,
+    sequence: u32,
+    /// The header of the last frame written, which delta frames are relative to.
+    last: Option<FrameHeader>,
+    /// The body of the last delta frame, reused for the next one.
+    buffer: Vec<u8>,
+    /// The last compressed body, reused for the next one.
+    compressed: Vec<u8>,
+}
+
+impl<S: Read + Write> Writer<S> {
+    /// Perform the [handshake] with the reader on the other end of `stream`.
+    pub fn new(mut stream: S) -> io::Result<Self> {
+        let hello = handshake(&mut stream)?;
+        Ok(Self {
+            stream,
+            version: hello.version,
+            codecs: hello.codecs,
+            codec: Codec::None,
+            sequence: 0,
+            last: None,
+            buffer: Vec::new(),
+            compressed: Vec::new(),
+        })
+    }
+
+    /// The negotiated protocol version.
+    pub fn version(&self) -> u16 {
+        self.version
+    }
+
+    /// The [Codec::bit]s of the codecs supported by both sides.
+    pub fn codecs(&self) -> u16 {
+        self.codecs
+    }
+
+    /// The codec the following frames are compressed with.
+    pub fn codec(&self) -> Codec {
+        self.codec
+    }
+
+    /// Compress the following frames with `codec`, or leave them uncompressed if the reader
+    /// doesn't support it. Frames which wouldn't get smaller are sent uncompressed as well.
+    pub fn set_codec(&mut self, codec: Codec) {
+        self.codec = if self.codecs & codec.bit() != 0 {
+            codec
+        } else {
+            Codec::None
+        };
+    }
+
+    pub fn get_ref(&self) -> &S {
+        &self.stream
+    }
+
+    pub fn into_inner(self) -> S {
+        self.stream
+    }
+
+    /// Write the full frame as a keyframe.
+    pub fn write_frame(&mut self, frame: &Frame) -> io::Result<()> {
+        frame.validate()?;
+        let header = self.header(frame, false);
+        self.write_message(header, frame.data)
+    }
+
+    /// Write a frame of which only the `dirty` regions changed since the previous frame. Falls
+    /// back to a keyframe if the peer doesn't support delta frames, the frame size or format
+    /// changed, or the delta wouldn't be smaller.
+    pub fn write_dirty(&mut self, frame: &Frame, dirty: &[Rect]) -> io::Result<()> {
+        frame.validate()?;
+        let header = self.header(frame, true);
+        let matches_last = self.last.is_some_and(|last| {
+            (last.width, last.height, last.stride, last.format)
+                == (header.width, header.height, header.stride, header.format)
+        });
+        if self.version < DELTA_VERSION || !matches_last {
+            return self.write_frame(frame);
+        }
+
+        let mut body = std::mem::take(&mut self.buffer);
+        delta::encode(frame, dirty, &mut body)?;
+        let result = if body.len() < frame.data.len() {
+            self.write_message(header, &body)
+        } else {
+            self.write_frame(frame)
+        };
+        self.buffer = body;
+        result
+    }
+
+    fn header(&self, frame: &Frame, delta: bool) -> FrameHeader {
+        FrameHeader {
+            sequence: self.sequence,
+            width: frame.width,
+            height: frame.height,
+            stride: frame.stride,
+            format: frame.format,
+            delta,
+            codec: Codec::None,
+        }
+    }
+
+    fn write_message(&mut self, header: FrameHeader, body: &[u8]) -> io::Result<()> {
+        if self.codec == Codec::None {
+            return self.write_raw(header, body);
+        }
+        let mut compressed = std::mem::take(&mut self.compressed);
+        let result = match self.codec.compress(body, &mut compressed) {
+            Ok(()) if compressed.len() < body.len() => {
+                let header = FrameHeader {
+                    codec: self.codec,
+                    ..header
+                };
+                self.write_raw(header, &compressed)
+            }
+            _ => self.write_raw(header, body),
+        };
+        self.compressed = compressed;
+        result
+    }
+
+    fn write_raw(&mut self, header: FrameHeader, body: &[u8]) -> io::Result<()> {
+        let envelope = Envelope::new(MessageKind::Frame, FRAME_HEADER_SIZE + body.len())?;
+        // A failed write leaves the peer in an unknown state, so the next frame has to be a
+        // keyframe.
+        self.last = None;
+        self.stream.write_all(&envelope.encode())?;
+        self.stream.write_all(&header.encode())?;
+        self.stream.write_all(body)?;
+        self.stream.flush()?;
+        self.last = Some(header);
+        self.sequence = self.sequence.wrapping_add(1);
+        Ok(())
+    }
+}
+
+impl<S: Read + Write + Send> Sink for Writer<S> {
+    fn send(&mut self, frame: &Frame) -> io::Result<()> {
+        self.write_frame(frame)
+    }
+
+    fn send_dirty(&mut self, frame: &Frame, dirty: &[Rect]) -> io::Result<()> {
+        self.write_dirty(frame, dirty)
+    }
+
+    fn set_codec(&mut self, codec: Codec) {
+        Writer::set_codec(self, codec)
+    }
+}
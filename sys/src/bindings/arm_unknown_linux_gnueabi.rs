@@ -1888,30 +1888,90 @@ pub enum cef_errorcode_t {
     ERR_DNS_NO_MATCHING_SUPPORTED_ALPN = -811,
     ERR_DNS_SECURE_PROBE_RECORD_INVALID = -814,
 }
-#[repr(u32)]
-#[non_exhaustive]
+impl cef_cert_status_t {
+    pub const CERT_STATUS_NONE: cef_cert_status_t = cef_cert_status_t(0);
+}
+impl cef_cert_status_t {
+    pub const CERT_STATUS_COMMON_NAME_INVALID: cef_cert_status_t = cef_cert_status_t(1);
+}
+impl cef_cert_status_t {
+    pub const CERT_STATUS_DATE_INVALID: cef_cert_status_t = cef_cert_status_t(2);
+}
+impl cef_cert_status_t {
+    pub const CERT_STATUS_AUTHORITY_INVALID: cef_cert_status_t = cef_cert_status_t(4);
+}
+impl cef_cert_status_t {
+    pub const CERT_STATUS_NO_REVOCATION_MECHANISM: cef_cert_status_t = cef_cert_status_t(16);
+}
+impl cef_cert_status_t {
+    pub const CERT_STATUS_UNABLE_TO_CHECK_REVOCATION: cef_cert_status_t = cef_cert_status_t(32);
+}
+impl cef_cert_status_t {
+    pub const CERT_STATUS_REVOKED: cef_cert_status_t = cef_cert_status_t(64);
+}
+impl cef_cert_status_t {
+    pub const CERT_STATUS_INVALID: cef_cert_status_t = cef_cert_status_t(128);
+}
+impl cef_cert_status_t {
+    pub const CERT_STATUS_WEAK_SIGNATURE_ALGORITHM: cef_cert_status_t = cef_cert_status_t(256);
+}
+impl cef_cert_status_t {
+    pub const CERT_STATUS_NON_UNIQUE_NAME: cef_cert_status_t = cef_cert_status_t(1024);
+}
+impl cef_cert_status_t {
+    pub const CERT_STATUS_WEAK_KEY: cef_cert_status_t = cef_cert_status_t(2048);
+}
+impl cef_cert_status_t {
+    pub const CERT_STATUS_PINNED_KEY_MISSING: cef_cert_status_t = cef_cert_status_t(8192);
+}
+impl cef_cert_status_t {
+    pub const CERT_STATUS_NAME_CONSTRAINT_VIOLATION: cef_cert_status_t = cef_cert_status_t(16384);
+}
+impl cef_cert_status_t {
+    pub const CERT_STATUS_VALIDITY_TOO_LONG: cef_cert_status_t = cef_cert_status_t(32768);
+}
+impl cef_cert_status_t {
+    pub const CERT_STATUS_IS_EV: cef_cert_status_t = cef_cert_status_t(65536);
+}
+impl cef_cert_status_t {
+    pub const CERT_STATUS_REV_CHECKING_ENABLED: cef_cert_status_t = cef_cert_status_t(131072);
+}
+impl cef_cert_status_t {
+    pub const CERT_STATUS_SHA1_SIGNATURE_PRESENT: cef_cert_status_t = cef_cert_status_t(524288);
+}
+impl cef_cert_status_t {
+    pub const CERT_STATUS_CT_COMPLIANCE_FAILED: cef_cert_status_t = cef_cert_status_t(1048576);
+}
+impl ::std::ops::BitOr<cef_cert_status_t> for cef_cert_status_t {
+    type Output = Self;
+    #[inline]
+    fn bitor(self, other: Self) -> Self {
+        cef_cert_status_t(self.0 | other.0)
+    }
+}
+impl ::std::ops::BitOrAssign for cef_cert_status_t {
+    #[inline]
+    fn bitor_assign(&mut self, rhs: cef_cert_status_t) {
+        self.0 |= rhs.0;
+    }
+}
+impl ::std::ops::BitAnd<cef_cert_status_t> for cef_cert_status_t {
+    type Output = Self;
+    #[inline]
+    fn bitand(self, other: Self) -> Self {
+        cef_cert_status_t(self.0 & other.0)
+    }
+}
+impl ::std::ops::BitAndAssign for cef_cert_status_t {
+    #[inline]
+    fn bitand_assign(&mut self, rhs: cef_cert_status_t) {
+        self.0 &= rhs.0;
+    }
+}
+#[repr(transparent)]
 #[doc = "\n Supported certificate status code values. See net\\cert\\cert_status_flags.h\n for more information. CERT_STATUS_NONE is new in CEF because we use an\n enum while cert_status_flags.h uses a typedef and static const variables.\n"]
 #[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
-pub enum cef_cert_status_t {
-    CERT_STATUS_NONE = 0,
-    CERT_STATUS_COMMON_NAME_INVALID = 1,
-    CERT_STATUS_DATE_INVALID = 2,
-    CERT_STATUS_AUTHORITY_INVALID = 4,
-    CERT_STATUS_NO_REVOCATION_MECHANISM = 16,
-    CERT_STATUS_UNABLE_TO_CHECK_REVOCATION = 32,
-    CERT_STATUS_REVOKED = 64,
-    CERT_STATUS_INVALID = 128,
-    CERT_STATUS_WEAK_SIGNATURE_ALGORITHM = 256,
-    CERT_STATUS_NON_UNIQUE_NAME = 1024,
-    CERT_STATUS_WEAK_KEY = 2048,
-    CERT_STATUS_PINNED_KEY_MISSING = 8192,
-    CERT_STATUS_NAME_CONSTRAINT_VIOLATION = 16384,
-    CERT_STATUS_VALIDITY_TOO_LONG = 32768,
-    CERT_STATUS_IS_EV = 65536,
-    CERT_STATUS_REV_CHECKING_ENABLED = 131072,
-    CERT_STATUS_SHA1_SIGNATURE_PRESENT = 524288,
-    CERT_STATUS_CT_COMPLIANCE_FAILED = 1048576,
-}
+pub struct cef_cert_status_t(pub ::std::os::raw::c_uint);
 impl cef_resultcode_t {
     pub const CEF_RESULT_CODE_SANDBOX_FATAL_INTEGRITY: cef_resultcode_t =
         cef_resultcode_t::CEF_RESULT_CODE_SANDBOX_FATAL_FIRST;
@@ -2079,20 +2139,56 @@ pub enum cef_text_input_mode_t {
     CEF_TEXT_INPUT_MODE_SEARCH = 8,
     CEF_TEXT_INPUT_MODE_NUM_VALUES = 9,
 }
-#[repr(u32)]
-#[non_exhaustive]
-#[doc = "\n V8 property attribute values.\n"]
-#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
-pub enum cef_v8_propertyattribute_t {
+impl cef_v8_propertyattribute_t {
     #[doc = "\n Writeable, Enumerable, Configurable\n"]
-    V8_PROPERTY_ATTRIBUTE_NONE = 0,
+    pub const V8_PROPERTY_ATTRIBUTE_NONE: cef_v8_propertyattribute_t =
+        cef_v8_propertyattribute_t(0);
+}
+impl cef_v8_propertyattribute_t {
     #[doc = "\n Not writeable\n"]
-    V8_PROPERTY_ATTRIBUTE_READONLY = 1,
+    pub const V8_PROPERTY_ATTRIBUTE_READONLY: cef_v8_propertyattribute_t =
+        cef_v8_propertyattribute_t(1);
+}
+impl cef_v8_propertyattribute_t {
     #[doc = "\n Not enumerable\n"]
-    V8_PROPERTY_ATTRIBUTE_DONTENUM = 2,
+    pub const V8_PROPERTY_ATTRIBUTE_DONTENUM: cef_v8_propertyattribute_t =
+        cef_v8_propertyattribute_t(2);
+}
+impl cef_v8_propertyattribute_t {
     #[doc = "\n Not configurable\n"]
-    V8_PROPERTY_ATTRIBUTE_DONTDELETE = 4,
+    pub const V8_PROPERTY_ATTRIBUTE_DONTDELETE: cef_v8_propertyattribute_t =
+        cef_v8_propertyattribute_t(4);
 }
+impl ::std::ops::BitOr<cef_v8_propertyattribute_t> for cef_v8_propertyattribute_t {
+    type Output = Self;
+    #[inline]
+    fn bitor(self, other: Self) -> Self {
+        cef_v8_propertyattribute_t(self.0 | other.0)
+    }
+}
+impl ::std::ops::BitOrAssign for cef_v8_propertyattribute_t {
+    #[inline]
+    fn bitor_assign(&mut self, rhs: cef_v8_propertyattribute_t) {
+        self.0 |= rhs.0;
+    }
+}
+impl ::std::ops::BitAnd<cef_v8_propertyattribute_t> for cef_v8_propertyattribute_t {
+    type Output = Self;
+    #[inline]
+    fn bitand(self, other: Self) -> Self {
+        cef_v8_propertyattribute_t(self.0 & other.0)
+    }
+}
+impl ::std::ops::BitAndAssign for cef_v8_propertyattribute_t {
+    #[inline]
+    fn bitand_assign(&mut self, rhs: cef_v8_propertyattribute_t) {
+        self.0 &= rhs.0;
+    }
+}
+#[repr(transparent)]
+#[doc = "\n V8 property attribute values.\n"]
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
+pub struct cef_v8_propertyattribute_t(pub ::std::os::raw::c_uint);
 #[repr(u32)]
 #[non_exhaustive]
 #[doc = "\n Post data elements may represent either bytes or files.\n"]
@@ -2205,30 +2301,72 @@ pub enum cef_transition_type_t {
     #[doc = "\n General mask defining the bits used for the qualifiers.\n"]
     TT_QUALIFIER_MASK = 4294967040,
 }
-#[repr(u32)]
-#[non_exhaustive]
-#[doc = "\n Flags used to customize the behavior of CefURLRequest.\n"]
-#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
-pub enum cef_urlrequest_flags_t {
+impl cef_urlrequest_flags_t {
     #[doc = "\n Default behavior.\n"]
-    UR_FLAG_NONE = 0,
+    pub const UR_FLAG_NONE: cef_urlrequest_flags_t = cef_urlrequest_flags_t(0);
+}
+impl cef_urlrequest_flags_t {
     #[doc = "\n If set the cache will be skipped when handling the request. Setting this\n value is equivalent to specifying the \"Cache-Control: no-cache\" request\n header. Setting this value in combination with UR_FLAG_ONLY_FROM_CACHE\n will cause the request to fail.\n"]
-    UR_FLAG_SKIP_CACHE = 1,
+    pub const UR_FLAG_SKIP_CACHE: cef_urlrequest_flags_t = cef_urlrequest_flags_t(1);
+}
+impl cef_urlrequest_flags_t {
     #[doc = "\n If set the request will fail if it cannot be served from the cache (or\n some equivalent local store). Setting this value is equivalent to\n specifying the \"Cache-Control: only-if-cached\" request header. Setting\n this value in combination with UR_FLAG_SKIP_CACHE or UR_FLAG_DISABLE_CACHE\n will cause the request to fail.\n"]
-    UR_FLAG_ONLY_FROM_CACHE = 2,
+    pub const UR_FLAG_ONLY_FROM_CACHE: cef_urlrequest_flags_t = cef_urlrequest_flags_t(2);
+}
+impl cef_urlrequest_flags_t {
     #[doc = "\n If set the cache will not be used at all. Setting this value is equivalent\n to specifying the \"Cache-Control: no-store\" request header. Setting this\n value in combination with UR_FLAG_ONLY_FROM_CACHE will cause the request\n to fail.\n"]
-    UR_FLAG_DISABLE_CACHE = 4,
+    pub const UR_FLAG_DISABLE_CACHE: cef_urlrequest_flags_t = cef_urlrequest_flags_t(4);
+}
+impl cef_urlrequest_flags_t {
     #[doc = "\n If set user name, password, and cookies may be sent with the request, and\n cookies may be saved from the response.\n"]
-    UR_FLAG_ALLOW_STORED_CREDENTIALS = 8,
+    pub const UR_FLAG_ALLOW_STORED_CREDENTIALS: cef_urlrequest_flags_t = cef_urlrequest_flags_t(8);
+}
+impl cef_urlrequest_flags_t {
     #[doc = "\n If set upload progress events will be generated when a request has a body.\n"]
-    UR_FLAG_REPORT_UPLOAD_PROGRESS = 16,
+    pub const UR_FLAG_REPORT_UPLOAD_PROGRESS: cef_urlrequest_flags_t = cef_urlrequest_flags_t(16);
+}
+impl cef_urlrequest_flags_t {
     #[doc = "\n If set the CefURLRequestClient::OnDownloadData method will not be called.\n"]
-    UR_FLAG_NO_DOWNLOAD_DATA = 32,
+    pub const UR_FLAG_NO_DOWNLOAD_DATA: cef_urlrequest_flags_t = cef_urlrequest_flags_t(32);
+}
+impl cef_urlrequest_flags_t {
     #[doc = "\n If set 5XX redirect errors will be propagated to the observer instead of\n automatically re-tried. This currently only applies for requests\n originated in the browser process.\n"]
-    UR_FLAG_NO_RETRY_ON_5XX = 64,
+    pub const UR_FLAG_NO_RETRY_ON_5XX: cef_urlrequest_flags_t = cef_urlrequest_flags_t(64);
+}
+impl cef_urlrequest_flags_t {
     #[doc = "\n If set 3XX responses will cause the fetch to halt immediately rather than\n continue through the redirect.\n"]
-    UR_FLAG_STOP_ON_REDIRECT = 128,
+    pub const UR_FLAG_STOP_ON_REDIRECT: cef_urlrequest_flags_t = cef_urlrequest_flags_t(128);
+}
+impl ::std::ops::BitOr<cef_urlrequest_flags_t> for cef_urlrequest_flags_t {
+    type Output = Self;
+    #[inline]
+    fn bitor(self, other: Self) -> Self {
+        cef_urlrequest_flags_t(self.0 | other.0)
+    }
+}
+impl ::std::ops::BitOrAssign for cef_urlrequest_flags_t {
+    #[inline]
+    fn bitor_assign(&mut self, rhs: cef_urlrequest_flags_t) {
+        self.0 |= rhs.0;
+    }
+}
+impl ::std::ops::BitAnd<cef_urlrequest_flags_t> for cef_urlrequest_flags_t {
+    type Output = Self;
+    #[inline]
+    fn bitand(self, other: Self) -> Self {
+        cef_urlrequest_flags_t(self.0 & other.0)
+    }
+}
+impl ::std::ops::BitAndAssign for cef_urlrequest_flags_t {
+    #[inline]
+    fn bitand_assign(&mut self, rhs: cef_urlrequest_flags_t) {
+        self.0 &= rhs.0;
+    }
 }
+#[repr(transparent)]
+#[doc = "\n Flags used to customize the behavior of CefURLRequest.\n"]
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
+pub struct cef_urlrequest_flags_t(pub ::std::os::raw::c_uint);
 #[repr(u32)]
 #[non_exhaustive]
 #[doc = "\n Flags that represent CefURLRequest status.\n"]
@@ -2593,38 +2731,96 @@ pub enum cef_paint_element_type_t {
     PET_VIEW = 0,
     PET_POPUP = 1,
 }
-#[repr(u32)]
-#[non_exhaustive]
-#[doc = "\n Supported event bit flags.\n"]
-#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
-pub enum cef_event_flags_t {
-    EVENTFLAG_NONE = 0,
-    EVENTFLAG_CAPS_LOCK_ON = 1,
-    EVENTFLAG_SHIFT_DOWN = 2,
-    EVENTFLAG_CONTROL_DOWN = 4,
-    EVENTFLAG_ALT_DOWN = 8,
-    EVENTFLAG_LEFT_MOUSE_BUTTON = 16,
-    EVENTFLAG_MIDDLE_MOUSE_BUTTON = 32,
-    EVENTFLAG_RIGHT_MOUSE_BUTTON = 64,
+impl cef_event_flags_t {
+    pub const EVENTFLAG_NONE: cef_event_flags_t = cef_event_flags_t(0);
+}
+impl cef_event_flags_t {
+    pub const EVENTFLAG_CAPS_LOCK_ON: cef_event_flags_t = cef_event_flags_t(1);
+}
+impl cef_event_flags_t {
+    pub const EVENTFLAG_SHIFT_DOWN: cef_event_flags_t = cef_event_flags_t(2);
+}
+impl cef_event_flags_t {
+    pub const EVENTFLAG_CONTROL_DOWN: cef_event_flags_t = cef_event_flags_t(4);
+}
+impl cef_event_flags_t {
+    pub const EVENTFLAG_ALT_DOWN: cef_event_flags_t = cef_event_flags_t(8);
+}
+impl cef_event_flags_t {
+    pub const EVENTFLAG_LEFT_MOUSE_BUTTON: cef_event_flags_t = cef_event_flags_t(16);
+}
+impl cef_event_flags_t {
+    pub const EVENTFLAG_MIDDLE_MOUSE_BUTTON: cef_event_flags_t = cef_event_flags_t(32);
+}
+impl cef_event_flags_t {
+    pub const EVENTFLAG_RIGHT_MOUSE_BUTTON: cef_event_flags_t = cef_event_flags_t(64);
+}
+impl cef_event_flags_t {
     #[doc = " Mac OS-X command key."]
-    EVENTFLAG_COMMAND_DOWN = 128,
+    pub const EVENTFLAG_COMMAND_DOWN: cef_event_flags_t = cef_event_flags_t(128);
+}
+impl cef_event_flags_t {
     #[doc = " Mac OS-X command key."]
-    EVENTFLAG_NUM_LOCK_ON = 256,
+    pub const EVENTFLAG_NUM_LOCK_ON: cef_event_flags_t = cef_event_flags_t(256);
+}
+impl cef_event_flags_t {
     #[doc = " Mac OS-X command key."]
-    EVENTFLAG_IS_KEY_PAD = 512,
+    pub const EVENTFLAG_IS_KEY_PAD: cef_event_flags_t = cef_event_flags_t(512);
+}
+impl cef_event_flags_t {
     #[doc = " Mac OS-X command key."]
-    EVENTFLAG_IS_LEFT = 1024,
+    pub const EVENTFLAG_IS_LEFT: cef_event_flags_t = cef_event_flags_t(1024);
+}
+impl cef_event_flags_t {
     #[doc = " Mac OS-X command key."]
-    EVENTFLAG_IS_RIGHT = 2048,
+    pub const EVENTFLAG_IS_RIGHT: cef_event_flags_t = cef_event_flags_t(2048);
+}
+impl cef_event_flags_t {
     #[doc = " Mac OS-X command key."]
-    EVENTFLAG_ALTGR_DOWN = 4096,
+    pub const EVENTFLAG_ALTGR_DOWN: cef_event_flags_t = cef_event_flags_t(4096);
+}
+impl cef_event_flags_t {
     #[doc = " Mac OS-X command key."]
-    EVENTFLAG_IS_REPEAT = 8192,
+    pub const EVENTFLAG_IS_REPEAT: cef_event_flags_t = cef_event_flags_t(8192);
+}
+impl cef_event_flags_t {
     #[doc = " Mac OS-X command key."]
-    EVENTFLAG_PRECISION_SCROLLING_DELTA = 16384,
+    pub const EVENTFLAG_PRECISION_SCROLLING_DELTA: cef_event_flags_t = cef_event_flags_t(16384);
+}
+impl cef_event_flags_t {
     #[doc = " Mac OS-X command key."]
-    EVENTFLAG_SCROLL_BY_PAGE = 32768,
+    pub const EVENTFLAG_SCROLL_BY_PAGE: cef_event_flags_t = cef_event_flags_t(32768);
 }
+impl ::std::ops::BitOr<cef_event_flags_t> for cef_event_flags_t {
+    type Output = Self;
+    #[inline]
+    fn bitor(self, other: Self) -> Self {
+        cef_event_flags_t(self.0 | other.0)
+    }
+}
+impl ::std::ops::BitOrAssign for cef_event_flags_t {
+    #[inline]
+    fn bitor_assign(&mut self, rhs: cef_event_flags_t) {
+        self.0 |= rhs.0;
+    }
+}
+impl ::std::ops::BitAnd<cef_event_flags_t> for cef_event_flags_t {
+    type Output = Self;
+    #[inline]
+    fn bitand(self, other: Self) -> Self {
+        cef_event_flags_t(self.0 & other.0)
+    }
+}
+impl ::std::ops::BitAndAssign for cef_event_flags_t {
+    #[inline]
+    fn bitand_assign(&mut self, rhs: cef_event_flags_t) {
+        self.0 &= rhs.0;
+    }
+}
+#[repr(transparent)]
+#[doc = "\n Supported event bit flags.\n"]
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
+pub struct cef_event_flags_t(pub ::std::os::raw::c_uint);
 #[repr(u32)]
 #[non_exhaustive]
 #[doc = "\n Supported menu item types.\n"]
@@ -2637,26 +2833,66 @@ pub enum cef_menu_item_type_t {
     MENUITEMTYPE_SEPARATOR = 4,
     MENUITEMTYPE_SUBMENU = 5,
 }
-#[repr(u32)]
-#[non_exhaustive]
-#[doc = "\n Supported context menu type flags.\n"]
-#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
-pub enum cef_context_menu_type_flags_t {
+impl cef_context_menu_type_flags_t {
     #[doc = "\n No node is selected.\n"]
-    CM_TYPEFLAG_NONE = 0,
+    pub const CM_TYPEFLAG_NONE: cef_context_menu_type_flags_t = cef_context_menu_type_flags_t(0);
+}
+impl cef_context_menu_type_flags_t {
     #[doc = "\n The top page is selected.\n"]
-    CM_TYPEFLAG_PAGE = 1,
+    pub const CM_TYPEFLAG_PAGE: cef_context_menu_type_flags_t = cef_context_menu_type_flags_t(1);
+}
+impl cef_context_menu_type_flags_t {
     #[doc = "\n A subframe page is selected.\n"]
-    CM_TYPEFLAG_FRAME = 2,
+    pub const CM_TYPEFLAG_FRAME: cef_context_menu_type_flags_t = cef_context_menu_type_flags_t(2);
+}
+impl cef_context_menu_type_flags_t {
     #[doc = "\n A link is selected.\n"]
-    CM_TYPEFLAG_LINK = 4,
+    pub const CM_TYPEFLAG_LINK: cef_context_menu_type_flags_t = cef_context_menu_type_flags_t(4);
+}
+impl cef_context_menu_type_flags_t {
     #[doc = "\n A media node is selected.\n"]
-    CM_TYPEFLAG_MEDIA = 8,
+    pub const CM_TYPEFLAG_MEDIA: cef_context_menu_type_flags_t = cef_context_menu_type_flags_t(8);
+}
+impl cef_context_menu_type_flags_t {
     #[doc = "\n There is a textual or mixed selection that is selected.\n"]
-    CM_TYPEFLAG_SELECTION = 16,
+    pub const CM_TYPEFLAG_SELECTION: cef_context_menu_type_flags_t =
+        cef_context_menu_type_flags_t(16);
+}
+impl cef_context_menu_type_flags_t {
     #[doc = "\n An editable element is selected.\n"]
-    CM_TYPEFLAG_EDITABLE = 32,
+    pub const CM_TYPEFLAG_EDITABLE: cef_context_menu_type_flags_t =
+        cef_context_menu_type_flags_t(32);
+}
+impl ::std::ops::BitOr<cef_context_menu_type_flags_t> for cef_context_menu_type_flags_t {
+    type Output = Self;
+    #[inline]
+    fn bitor(self, other: Self) -> Self {
+        cef_context_menu_type_flags_t(self.0 | other.0)
+    }
+}
+impl ::std::ops::BitOrAssign for cef_context_menu_type_flags_t {
+    #[inline]
+    fn bitor_assign(&mut self, rhs: cef_context_menu_type_flags_t) {
+        self.0 |= rhs.0;
+    }
+}
+impl ::std::ops::BitAnd<cef_context_menu_type_flags_t> for cef_context_menu_type_flags_t {
+    type Output = Self;
+    #[inline]
+    fn bitand(self, other: Self) -> Self {
+        cef_context_menu_type_flags_t(self.0 & other.0)
+    }
+}
+impl ::std::ops::BitAndAssign for cef_context_menu_type_flags_t {
+    #[inline]
+    fn bitand_assign(&mut self, rhs: cef_context_menu_type_flags_t) {
+        self.0 &= rhs.0;
+    }
 }
+#[repr(transparent)]
+#[doc = "\n Supported context menu type flags.\n"]
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
+pub struct cef_context_menu_type_flags_t(pub ::std::os::raw::c_uint);
 #[repr(u32)]
 #[non_exhaustive]
 #[doc = "\n Supported context menu media types. These constants match their equivalents\n in Chromium's ContextMenuDataMediaType and should not be renumbered.\n"]
@@ -2679,53 +2915,220 @@ pub enum cef_context_menu_media_type_t {
     #[doc = "\n A plugin node is selected.\n"]
     CM_MEDIATYPE_NUM_VALUES = 7,
 }
-#[repr(u32)]
-#[non_exhaustive]
+impl cef_context_menu_media_state_flags_t {
+    pub const CM_MEDIAFLAG_NONE: cef_context_menu_media_state_flags_t =
+        cef_context_menu_media_state_flags_t(0);
+}
+impl cef_context_menu_media_state_flags_t {
+    pub const CM_MEDIAFLAG_IN_ERROR: cef_context_menu_media_state_flags_t =
+        cef_context_menu_media_state_flags_t(1);
+}
+impl cef_context_menu_media_state_flags_t {
+    pub const CM_MEDIAFLAG_PAUSED: cef_context_menu_media_state_flags_t =
+        cef_context_menu_media_state_flags_t(2);
+}
+impl cef_context_menu_media_state_flags_t {
+    pub const CM_MEDIAFLAG_MUTED: cef_context_menu_media_state_flags_t =
+        cef_context_menu_media_state_flags_t(4);
+}
+impl cef_context_menu_media_state_flags_t {
+    pub const CM_MEDIAFLAG_LOOP: cef_context_menu_media_state_flags_t =
+        cef_context_menu_media_state_flags_t(8);
+}
+impl cef_context_menu_media_state_flags_t {
+    pub const CM_MEDIAFLAG_CAN_SAVE: cef_context_menu_media_state_flags_t =
+        cef_context_menu_media_state_flags_t(16);
+}
+impl cef_context_menu_media_state_flags_t {
+    pub const CM_MEDIAFLAG_HAS_AUDIO: cef_context_menu_media_state_flags_t =
+        cef_context_menu_media_state_flags_t(32);
+}
+impl cef_context_menu_media_state_flags_t {
+    pub const CM_MEDIAFLAG_CAN_TOGGLE_CONTROLS: cef_context_menu_media_state_flags_t =
+        cef_context_menu_media_state_flags_t(64);
+}
+impl cef_context_menu_media_state_flags_t {
+    pub const CM_MEDIAFLAG_CONTROLS: cef_context_menu_media_state_flags_t =
+        cef_context_menu_media_state_flags_t(128);
+}
+impl cef_context_menu_media_state_flags_t {
+    pub const CM_MEDIAFLAG_CAN_PRINT: cef_context_menu_media_state_flags_t =
+        cef_context_menu_media_state_flags_t(256);
+}
+impl cef_context_menu_media_state_flags_t {
+    pub const CM_MEDIAFLAG_CAN_ROTATE: cef_context_menu_media_state_flags_t =
+        cef_context_menu_media_state_flags_t(512);
+}
+impl cef_context_menu_media_state_flags_t {
+    pub const CM_MEDIAFLAG_CAN_PICTURE_IN_PICTURE: cef_context_menu_media_state_flags_t =
+        cef_context_menu_media_state_flags_t(1024);
+}
+impl cef_context_menu_media_state_flags_t {
+    pub const CM_MEDIAFLAG_PICTURE_IN_PICTURE: cef_context_menu_media_state_flags_t =
+        cef_context_menu_media_state_flags_t(2048);
+}
+impl cef_context_menu_media_state_flags_t {
+    pub const CM_MEDIAFLAG_CAN_LOOP: cef_context_menu_media_state_flags_t =
+        cef_context_menu_media_state_flags_t(4096);
+}
+impl ::std::ops::BitOr<cef_context_menu_media_state_flags_t>
+    for cef_context_menu_media_state_flags_t
+{
+    type Output = Self;
+    #[inline]
+    fn bitor(self, other: Self) -> Self {
+        cef_context_menu_media_state_flags_t(self.0 | other.0)
+    }
+}
+impl ::std::ops::BitOrAssign for cef_context_menu_media_state_flags_t {
+    #[inline]
+    fn bitor_assign(&mut self, rhs: cef_context_menu_media_state_flags_t) {
+        self.0 |= rhs.0;
+    }
+}
+impl ::std::ops::BitAnd<cef_context_menu_media_state_flags_t>
+    for cef_context_menu_media_state_flags_t
+{
+    type Output = Self;
+    #[inline]
+    fn bitand(self, other: Self) -> Self {
+        cef_context_menu_media_state_flags_t(self.0 & other.0)
+    }
+}
+impl ::std::ops::BitAndAssign for cef_context_menu_media_state_flags_t {
+    #[inline]
+    fn bitand_assign(&mut self, rhs: cef_context_menu_media_state_flags_t) {
+        self.0 &= rhs.0;
+    }
+}
+#[repr(transparent)]
 #[doc = "\n Supported context menu media state bit flags. These constants match their\n equivalents in Chromium's ContextMenuData::MediaFlags and should not be\n renumbered.\n"]
 #[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
-pub enum cef_context_menu_media_state_flags_t {
-    CM_MEDIAFLAG_NONE = 0,
-    CM_MEDIAFLAG_IN_ERROR = 1,
-    CM_MEDIAFLAG_PAUSED = 2,
-    CM_MEDIAFLAG_MUTED = 4,
-    CM_MEDIAFLAG_LOOP = 8,
-    CM_MEDIAFLAG_CAN_SAVE = 16,
-    CM_MEDIAFLAG_HAS_AUDIO = 32,
-    CM_MEDIAFLAG_CAN_TOGGLE_CONTROLS = 64,
-    CM_MEDIAFLAG_CONTROLS = 128,
-    CM_MEDIAFLAG_CAN_PRINT = 256,
-    CM_MEDIAFLAG_CAN_ROTATE = 512,
-    CM_MEDIAFLAG_CAN_PICTURE_IN_PICTURE = 1024,
-    CM_MEDIAFLAG_PICTURE_IN_PICTURE = 2048,
-    CM_MEDIAFLAG_CAN_LOOP = 4096,
+pub struct cef_context_menu_media_state_flags_t(pub ::std::os::raw::c_uint);
+impl cef_context_menu_edit_state_flags_t {
+    pub const CM_EDITFLAG_NONE: cef_context_menu_edit_state_flags_t =
+        cef_context_menu_edit_state_flags_t(0);
+}
+impl cef_context_menu_edit_state_flags_t {
+    pub const CM_EDITFLAG_CAN_UNDO: cef_context_menu_edit_state_flags_t =
+        cef_context_menu_edit_state_flags_t(1);
+}
+impl cef_context_menu_edit_state_flags_t {
+    pub const CM_EDITFLAG_CAN_REDO: cef_context_menu_edit_state_flags_t =
+        cef_context_menu_edit_state_flags_t(2);
+}
+impl cef_context_menu_edit_state_flags_t {
+    pub const CM_EDITFLAG_CAN_CUT: cef_context_menu_edit_state_flags_t =
+        cef_context_menu_edit_state_flags_t(4);
+}
+impl cef_context_menu_edit_state_flags_t {
+    pub const CM_EDITFLAG_CAN_COPY: cef_context_menu_edit_state_flags_t =
+        cef_context_menu_edit_state_flags_t(8);
+}
+impl cef_context_menu_edit_state_flags_t {
+    pub const CM_EDITFLAG_CAN_PASTE: cef_context_menu_edit_state_flags_t =
+        cef_context_menu_edit_state_flags_t(16);
+}
+impl cef_context_menu_edit_state_flags_t {
+    pub const CM_EDITFLAG_CAN_DELETE: cef_context_menu_edit_state_flags_t =
+        cef_context_menu_edit_state_flags_t(32);
+}
+impl cef_context_menu_edit_state_flags_t {
+    pub const CM_EDITFLAG_CAN_SELECT_ALL: cef_context_menu_edit_state_flags_t =
+        cef_context_menu_edit_state_flags_t(64);
+}
+impl cef_context_menu_edit_state_flags_t {
+    pub const CM_EDITFLAG_CAN_TRANSLATE: cef_context_menu_edit_state_flags_t =
+        cef_context_menu_edit_state_flags_t(128);
+}
+impl cef_context_menu_edit_state_flags_t {
+    pub const CM_EDITFLAG_CAN_EDIT_RICHLY: cef_context_menu_edit_state_flags_t =
+        cef_context_menu_edit_state_flags_t(256);
+}
+impl ::std::ops::BitOr<cef_context_menu_edit_state_flags_t>
+    for cef_context_menu_edit_state_flags_t
+{
+    type Output = Self;
+    #[inline]
+    fn bitor(self, other: Self) -> Self {
+        cef_context_menu_edit_state_flags_t(self.0 | other.0)
+    }
 }
-#[repr(u32)]
-#[non_exhaustive]
+impl ::std::ops::BitOrAssign for cef_context_menu_edit_state_flags_t {
+    #[inline]
+    fn bitor_assign(&mut self, rhs: cef_context_menu_edit_state_flags_t) {
+        self.0 |= rhs.0;
+    }
+}
+impl ::std::ops::BitAnd<cef_context_menu_edit_state_flags_t>
+    for cef_context_menu_edit_state_flags_t
+{
+    type Output = Self;
+    #[inline]
+    fn bitand(self, other: Self) -> Self {
+        cef_context_menu_edit_state_flags_t(self.0 & other.0)
+    }
+}
+impl ::std::ops::BitAndAssign for cef_context_menu_edit_state_flags_t {
+    #[inline]
+    fn bitand_assign(&mut self, rhs: cef_context_menu_edit_state_flags_t) {
+        self.0 &= rhs.0;
+    }
+}
+#[repr(transparent)]
 #[doc = "\n Supported context menu edit state bit flags. These constants match their\n equivalents in Chromium's ContextMenuDataEditFlags and should not be\n renumbered.\n"]
 #[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
-pub enum cef_context_menu_edit_state_flags_t {
-    CM_EDITFLAG_NONE = 0,
-    CM_EDITFLAG_CAN_UNDO = 1,
-    CM_EDITFLAG_CAN_REDO = 2,
-    CM_EDITFLAG_CAN_CUT = 4,
-    CM_EDITFLAG_CAN_COPY = 8,
-    CM_EDITFLAG_CAN_PASTE = 16,
-    CM_EDITFLAG_CAN_DELETE = 32,
-    CM_EDITFLAG_CAN_SELECT_ALL = 64,
-    CM_EDITFLAG_CAN_TRANSLATE = 128,
-    CM_EDITFLAG_CAN_EDIT_RICHLY = 256,
+pub struct cef_context_menu_edit_state_flags_t(pub ::std::os::raw::c_uint);
+impl cef_quick_menu_edit_state_flags_t {
+    pub const QM_EDITFLAG_NONE: cef_quick_menu_edit_state_flags_t =
+        cef_quick_menu_edit_state_flags_t(0);
 }
-#[repr(u32)]
-#[non_exhaustive]
+impl cef_quick_menu_edit_state_flags_t {
+    pub const QM_EDITFLAG_CAN_ELLIPSIS: cef_quick_menu_edit_state_flags_t =
+        cef_quick_menu_edit_state_flags_t(1);
+}
+impl cef_quick_menu_edit_state_flags_t {
+    pub const QM_EDITFLAG_CAN_CUT: cef_quick_menu_edit_state_flags_t =
+        cef_quick_menu_edit_state_flags_t(2);
+}
+impl cef_quick_menu_edit_state_flags_t {
+    pub const QM_EDITFLAG_CAN_COPY: cef_quick_menu_edit_state_flags_t =
+        cef_quick_menu_edit_state_flags_t(4);
+}
+impl cef_quick_menu_edit_state_flags_t {
+    pub const QM_EDITFLAG_CAN_PASTE: cef_quick_menu_edit_state_flags_t =
+        cef_quick_menu_edit_state_flags_t(8);
+}
+impl ::std::ops::BitOr<cef_quick_menu_edit_state_flags_t> for cef_quick_menu_edit_state_flags_t {
+    type Output = Self;
+    #[inline]
+    fn bitor(self, other: Self) -> Self {
+        cef_quick_menu_edit_state_flags_t(self.0 | other.0)
+    }
+}
+impl ::std::ops::BitOrAssign for cef_quick_menu_edit_state_flags_t {
+    #[inline]
+    fn bitor_assign(&mut self, rhs: cef_quick_menu_edit_state_flags_t) {
+        self.0 |= rhs.0;
+    }
+}
+impl ::std::ops::BitAnd<cef_quick_menu_edit_state_flags_t> for cef_quick_menu_edit_state_flags_t {
+    type Output = Self;
+    #[inline]
+    fn bitand(self, other: Self) -> Self {
+        cef_quick_menu_edit_state_flags_t(self.0 & other.0)
+    }
+}
+impl ::std::ops::BitAndAssign for cef_quick_menu_edit_state_flags_t {
+    #[inline]
+    fn bitand_assign(&mut self, rhs: cef_quick_menu_edit_state_flags_t) {
+        self.0 &= rhs.0;
+    }
+}
+#[repr(transparent)]
 #[doc = "\n Supported quick menu state bit flags.\n"]
 #[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
-pub enum cef_quick_menu_edit_state_flags_t {
-    QM_EDITFLAG_NONE = 0,
-    QM_EDITFLAG_CAN_ELLIPSIS = 1,
-    QM_EDITFLAG_CAN_CUT = 2,
-    QM_EDITFLAG_CAN_COPY = 4,
-    QM_EDITFLAG_CAN_PASTE = 8,
-}
+pub struct cef_quick_menu_edit_state_flags_t(pub ::std::os::raw::c_uint);
 #[repr(u32)]
 #[non_exhaustive]
 #[doc = "\n Key event types.\n"]
@@ -3153,20 +3556,54 @@ pub enum cef_json_parser_options_t {
     #[doc = "\n Allows commas to exist after the last element in structures.\n"]
     JSON_PARSER_ALLOW_TRAILING_COMMAS = 1,
 }
-#[repr(u32)]
-#[non_exhaustive]
-#[doc = "\n Options that can be passed to CefWriteJSON.\n"]
-#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
-pub enum cef_json_writer_options_t {
+impl cef_json_writer_options_t {
     #[doc = "\n Default behavior.\n"]
-    JSON_WRITER_DEFAULT = 0,
+    pub const JSON_WRITER_DEFAULT: cef_json_writer_options_t = cef_json_writer_options_t(0);
+}
+impl cef_json_writer_options_t {
     #[doc = "\n This option instructs the writer that if a Binary value is encountered,\n the value (and key if within a dictionary) will be omitted from the\n output, and success will be returned. Otherwise, if a binary value is\n encountered, failure will be returned.\n"]
-    JSON_WRITER_OMIT_BINARY_VALUES = 1,
+    pub const JSON_WRITER_OMIT_BINARY_VALUES: cef_json_writer_options_t =
+        cef_json_writer_options_t(1);
+}
+impl cef_json_writer_options_t {
     #[doc = "\n This option instructs the writer to write doubles that have no fractional\n part as a normal integer (i.e., without using exponential notation\n or appending a '.0') as long as the value is within the range of a\n 64-bit int.\n"]
-    JSON_WRITER_OMIT_DOUBLE_TYPE_PRESERVATION = 2,
+    pub const JSON_WRITER_OMIT_DOUBLE_TYPE_PRESERVATION: cef_json_writer_options_t =
+        cef_json_writer_options_t(2);
+}
+impl cef_json_writer_options_t {
     #[doc = "\n Return a slightly nicer formatted json string (pads with whitespace to\n help with readability).\n"]
-    JSON_WRITER_PRETTY_PRINT = 4,
+    pub const JSON_WRITER_PRETTY_PRINT: cef_json_writer_options_t = cef_json_writer_options_t(4);
+}
+impl ::std::ops::BitOr<cef_json_writer_options_t> for cef_json_writer_options_t {
+    type Output = Self;
+    #[inline]
+    fn bitor(self, other: Self) -> Self {
+        cef_json_writer_options_t(self.0 | other.0)
+    }
+}
+impl ::std::ops::BitOrAssign for cef_json_writer_options_t {
+    #[inline]
+    fn bitor_assign(&mut self, rhs: cef_json_writer_options_t) {
+        self.0 |= rhs.0;
+    }
+}
+impl ::std::ops::BitAnd<cef_json_writer_options_t> for cef_json_writer_options_t {
+    type Output = Self;
+    #[inline]
+    fn bitand(self, other: Self) -> Self {
+        cef_json_writer_options_t(self.0 & other.0)
+    }
+}
+impl ::std::ops::BitAndAssign for cef_json_writer_options_t {
+    #[inline]
+    fn bitand_assign(&mut self, rhs: cef_json_writer_options_t) {
+        self.0 &= rhs.0;
+    }
 }
+#[repr(transparent)]
+#[doc = "\n Options that can be passed to CefWriteJSON.\n"]
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
+pub struct cef_json_writer_options_t(pub ::std::os::raw::c_uint);
 #[repr(u32)]
 #[non_exhaustive]
 #[doc = "\n Margin type for PDF printing.\n"]
@@ -3486,36 +3923,108 @@ pub enum cef_ssl_version_t {
     #[doc = " Unknown SSL version."]
     SSL_CONNECTION_VERSION_NUM_VALUES = 8,
 }
-#[repr(u32)]
-#[non_exhaustive]
+impl cef_ssl_content_status_t {
+    pub const SSL_CONTENT_NORMAL_CONTENT: cef_ssl_content_status_t = cef_ssl_content_status_t(0);
+}
+impl cef_ssl_content_status_t {
+    pub const SSL_CONTENT_DISPLAYED_INSECURE_CONTENT: cef_ssl_content_status_t =
+        cef_ssl_content_status_t(1);
+}
+impl cef_ssl_content_status_t {
+    pub const SSL_CONTENT_RAN_INSECURE_CONTENT: cef_ssl_content_status_t =
+        cef_ssl_content_status_t(2);
+}
+impl ::std::ops::BitOr<cef_ssl_content_status_t> for cef_ssl_content_status_t {
+    type Output = Self;
+    #[inline]
+    fn bitor(self, other: Self) -> Self {
+        cef_ssl_content_status_t(self.0 | other.0)
+    }
+}
+impl ::std::ops::BitOrAssign for cef_ssl_content_status_t {
+    #[inline]
+    fn bitor_assign(&mut self, rhs: cef_ssl_content_status_t) {
+        self.0 |= rhs.0;
+    }
+}
+impl ::std::ops::BitAnd<cef_ssl_content_status_t> for cef_ssl_content_status_t {
+    type Output = Self;
+    #[inline]
+    fn bitand(self, other: Self) -> Self {
+        cef_ssl_content_status_t(self.0 & other.0)
+    }
+}
+impl ::std::ops::BitAndAssign for cef_ssl_content_status_t {
+    #[inline]
+    fn bitand_assign(&mut self, rhs: cef_ssl_content_status_t) {
+        self.0 &= rhs.0;
+    }
+}
+#[repr(transparent)]
 #[doc = " Supported SSL content status flags. See content/public/common/ssl_status.h\n for more information."]
 #[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
-pub enum cef_ssl_content_status_t {
-    SSL_CONTENT_NORMAL_CONTENT = 0,
-    SSL_CONTENT_DISPLAYED_INSECURE_CONTENT = 1,
-    SSL_CONTENT_RAN_INSECURE_CONTENT = 2,
+pub struct cef_ssl_content_status_t(pub ::std::os::raw::c_uint);
+impl cef_scheme_options_t {
+    pub const CEF_SCHEME_OPTION_NONE: cef_scheme_options_t = cef_scheme_options_t(0);
 }
-#[repr(u32)]
-#[non_exhaustive]
-#[doc = " Configuration options for registering a custom scheme.\n These values are used when calling AddCustomScheme."]
-#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
-pub enum cef_scheme_options_t {
-    CEF_SCHEME_OPTION_NONE = 0,
+impl cef_scheme_options_t {
     #[doc = " For non-standard scheme URLs only the \"scheme:\" component is parsed and\n canonicalized. The remainder of the URL will be passed to the handler as-\n is. For example, \"scheme:///some%20text\" will remain the same.\n Non-standard scheme URLs cannot be used as a target for form submission.\n"]
-    CEF_SCHEME_OPTION_STANDARD = 1,
+    pub const CEF_SCHEME_OPTION_STANDARD: cef_scheme_options_t = cef_scheme_options_t(1);
+}
+impl cef_scheme_options_t {
     #[doc = "\n If CEF_SCHEME_OPTION_LOCAL is set the scheme will be treated with the same\n security rules as those applied to \"file\" URLs. Normal pages cannot link\n to or access local URLs. Also, by default, local URLs can only perform\n XMLHttpRequest calls to the same URL (origin + path) that originated the\n request. To allow XMLHttpRequest calls from a local URL to other URLs with\n the same origin set the CefSettings.file_access_from_file_urls_allowed\n value to true (1). To allow XMLHttpRequest calls from a local URL to all\n origins set the CefSettings.universal_access_from_file_urls_allowed value\n to true (1).\n"]
-    CEF_SCHEME_OPTION_LOCAL = 2,
+    pub const CEF_SCHEME_OPTION_LOCAL: cef_scheme_options_t = cef_scheme_options_t(2);
+}
+impl cef_scheme_options_t {
     #[doc = "\n If CEF_SCHEME_OPTION_DISPLAY_ISOLATED is set the scheme can only be\n displayed from other content hosted with the same scheme. For example,\n pages in other origins cannot create iframes or hyperlinks to URLs with\n the scheme. For schemes that must be accessible from other schemes don't\n set this, set CEF_SCHEME_OPTION_CORS_ENABLED, and use CORS\n \"Access-Control-Allow-Origin\" headers to further restrict access.\n"]
-    CEF_SCHEME_OPTION_DISPLAY_ISOLATED = 4,
+    pub const CEF_SCHEME_OPTION_DISPLAY_ISOLATED: cef_scheme_options_t = cef_scheme_options_t(4);
+}
+impl cef_scheme_options_t {
     #[doc = "\n If CEF_SCHEME_OPTION_SECURE is set the scheme will be treated with the\n same security rules as those applied to \"https\" URLs. For example, loading\n this scheme from other secure schemes will not trigger mixed content\n warnings.\n"]
-    CEF_SCHEME_OPTION_SECURE = 8,
+    pub const CEF_SCHEME_OPTION_SECURE: cef_scheme_options_t = cef_scheme_options_t(8);
+}
+impl cef_scheme_options_t {
     #[doc = "\n If CEF_SCHEME_OPTION_CORS_ENABLED is set the scheme can be sent CORS\n requests. This value should be set in most cases where\n CEF_SCHEME_OPTION_STANDARD is set.\n"]
-    CEF_SCHEME_OPTION_CORS_ENABLED = 16,
+    pub const CEF_SCHEME_OPTION_CORS_ENABLED: cef_scheme_options_t = cef_scheme_options_t(16);
+}
+impl cef_scheme_options_t {
     #[doc = "\n If CEF_SCHEME_OPTION_CSP_BYPASSING is set the scheme can bypass Content-\n Security-Policy (CSP) checks. This value should not be set in most cases\n where CEF_SCHEME_OPTION_STANDARD is set.\n"]
-    CEF_SCHEME_OPTION_CSP_BYPASSING = 32,
+    pub const CEF_SCHEME_OPTION_CSP_BYPASSING: cef_scheme_options_t = cef_scheme_options_t(32);
+}
+impl cef_scheme_options_t {
     #[doc = "\n If CEF_SCHEME_OPTION_FETCH_ENABLED is set the scheme can perform Fetch API\n requests.\n"]
-    CEF_SCHEME_OPTION_FETCH_ENABLED = 64,
+    pub const CEF_SCHEME_OPTION_FETCH_ENABLED: cef_scheme_options_t = cef_scheme_options_t(64);
+}
+impl ::std::ops::BitOr<cef_scheme_options_t> for cef_scheme_options_t {
+    type Output = Self;
+    #[inline]
+    fn bitor(self, other: Self) -> Self {
+        cef_scheme_options_t(self.0 | other.0)
+    }
+}
+impl ::std::ops::BitOrAssign for cef_scheme_options_t {
+    #[inline]
+    fn bitor_assign(&mut self, rhs: cef_scheme_options_t) {
+        self.0 |= rhs.0;
+    }
+}
+impl ::std::ops::BitAnd<cef_scheme_options_t> for cef_scheme_options_t {
+    type Output = Self;
+    #[inline]
+    fn bitand(self, other: Self) -> Self {
+        cef_scheme_options_t(self.0 & other.0)
+    }
+}
+impl ::std::ops::BitAndAssign for cef_scheme_options_t {
+    #[inline]
+    fn bitand_assign(&mut self, rhs: cef_scheme_options_t) {
+        self.0 &= rhs.0;
+    }
 }
+#[repr(transparent)]
+#[doc = " Configuration options for registering a custom scheme.\n These values are used when calling AddCustomScheme."]
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
+pub struct cef_scheme_options_t(pub ::std::os::raw::c_uint);
 #[doc = "\n Structure representing a range.\n"]
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
@@ -3866,17 +4375,55 @@ pub enum cef_show_state_t {
     CEF_SHOW_STATE_HIDDEN = 4,
     CEF_SHOW_STATE_NUM_VALUES = 5,
 }
-#[repr(u32)]
-#[non_exhaustive]
+impl cef_touch_handle_state_flags_t {
+    pub const CEF_THS_FLAG_NONE: cef_touch_handle_state_flags_t = cef_touch_handle_state_flags_t(0);
+}
+impl cef_touch_handle_state_flags_t {
+    pub const CEF_THS_FLAG_ENABLED: cef_touch_handle_state_flags_t =
+        cef_touch_handle_state_flags_t(1);
+}
+impl cef_touch_handle_state_flags_t {
+    pub const CEF_THS_FLAG_ORIENTATION: cef_touch_handle_state_flags_t =
+        cef_touch_handle_state_flags_t(2);
+}
+impl cef_touch_handle_state_flags_t {
+    pub const CEF_THS_FLAG_ORIGIN: cef_touch_handle_state_flags_t =
+        cef_touch_handle_state_flags_t(4);
+}
+impl cef_touch_handle_state_flags_t {
+    pub const CEF_THS_FLAG_ALPHA: cef_touch_handle_state_flags_t =
+        cef_touch_handle_state_flags_t(8);
+}
+impl ::std::ops::BitOr<cef_touch_handle_state_flags_t> for cef_touch_handle_state_flags_t {
+    type Output = Self;
+    #[inline]
+    fn bitor(self, other: Self) -> Self {
+        cef_touch_handle_state_flags_t(self.0 | other.0)
+    }
+}
+impl ::std::ops::BitOrAssign for cef_touch_handle_state_flags_t {
+    #[inline]
+    fn bitor_assign(&mut self, rhs: cef_touch_handle_state_flags_t) {
+        self.0 |= rhs.0;
+    }
+}
+impl ::std::ops::BitAnd<cef_touch_handle_state_flags_t> for cef_touch_handle_state_flags_t {
+    type Output = Self;
+    #[inline]
+    fn bitand(self, other: Self) -> Self {
+        cef_touch_handle_state_flags_t(self.0 & other.0)
+    }
+}
+impl ::std::ops::BitAndAssign for cef_touch_handle_state_flags_t {
+    #[inline]
+    fn bitand_assign(&mut self, rhs: cef_touch_handle_state_flags_t) {
+        self.0 &= rhs.0;
+    }
+}
+#[repr(transparent)]
 #[doc = "\n Values indicating what state of the touch handle is set.\n"]
 #[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
-pub enum cef_touch_handle_state_flags_t {
-    CEF_THS_FLAG_NONE = 0,
-    CEF_THS_FLAG_ENABLED = 1,
-    CEF_THS_FLAG_ORIENTATION = 2,
-    CEF_THS_FLAG_ORIGIN = 4,
-    CEF_THS_FLAG_ALPHA = 8,
-}
+pub struct cef_touch_handle_state_flags_t(pub ::std::os::raw::c_uint);
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
 pub struct _cef_touch_handle_state_t {
@@ -13824,6 +14371,61 @@ const _: () = {
 };
 #[doc = "\n Structure that should be implemented by the cef_urlrequest_t client. The\n functions of this structure will be called on the same thread that created\n the request unless otherwise documented.\n\n NOTE: This struct is allocated client-side.\n"]
 pub type cef_urlrequest_client_t = _cef_urlrequest_client_t;
+#[doc = "\n Structure that builds a cef_process_message_t containing a shared memory\n region. This structure is not thread-safe but may be used exclusively on a\n different thread from the one which constructed it.\n\n NOTE: This struct is allocated DLL-side.\n"]
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct _cef_shared_process_message_builder_t {
+    #[doc = "\n Base structure.\n"]
+    pub base: cef_base_ref_counted_t,
+    #[doc = "\n Returns true (1) if the builder is valid.\n"]
+    pub is_valid: ::std::option::Option<
+        unsafe extern "C" fn(
+            self_: *mut _cef_shared_process_message_builder_t,
+        ) -> ::std::os::raw::c_int,
+    >,
+    #[doc = "\n Returns the size of the shared memory region in bytes. Returns 0 for\n invalid instances.\n"]
+    pub size: ::std::option::Option<
+        unsafe extern "C" fn(self_: *mut _cef_shared_process_message_builder_t) -> usize,
+    >,
+    #[doc = "\n Returns the pointer to the writable memory. Returns nullptr for invalid\n instances. The returned pointer is only valid for the life span of this\n object.\n"]
+    pub memory: ::std::option::Option<
+        unsafe extern "C" fn(
+            self_: *mut _cef_shared_process_message_builder_t,
+        ) -> *mut ::std::os::raw::c_void,
+    >,
+    #[doc = "\n Creates a new cef_process_message_t from the data provided to the builder.\n Returns nullptr for invalid instances. Invalidates the builder instance.\n"]
+    pub build: ::std::option::Option<
+        unsafe extern "C" fn(
+            self_: *mut _cef_shared_process_message_builder_t,
+        ) -> *mut _cef_process_message_t,
+    >,
+}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of _cef_shared_process_message_builder_t"]
+        [::std::mem::size_of::<_cef_shared_process_message_builder_t>() - 36usize];
+    ["Alignment of _cef_shared_process_message_builder_t"]
+        [::std::mem::align_of::<_cef_shared_process_message_builder_t>() - 4usize];
+    ["Offset of field: _cef_shared_process_message_builder_t::base"]
+        [::std::mem::offset_of!(_cef_shared_process_message_builder_t, base) - 0usize];
+    ["Offset of field: _cef_shared_process_message_builder_t::is_valid"]
+        [::std::mem::offset_of!(_cef_shared_process_message_builder_t, is_valid) - 20usize];
+    ["Offset of field: _cef_shared_process_message_builder_t::size"]
+        [::std::mem::offset_of!(_cef_shared_process_message_builder_t, size) - 24usize];
+    ["Offset of field: _cef_shared_process_message_builder_t::memory"]
+        [::std::mem::offset_of!(_cef_shared_process_message_builder_t, memory) - 28usize];
+    ["Offset of field: _cef_shared_process_message_builder_t::build"]
+        [::std::mem::offset_of!(_cef_shared_process_message_builder_t, build) - 32usize];
+};
+#[doc = "\n Structure that builds a cef_process_message_t containing a shared memory\n region. This structure is not thread-safe but may be used exclusively on a\n different thread from the one which constructed it.\n\n NOTE: This struct is allocated DLL-side.\n"]
+pub type cef_shared_process_message_builder_t = _cef_shared_process_message_builder_t;
+unsafe extern "C" {
+    #[doc = "\n Creates a new cef_shared_process_message_builder_t with the specified |name|\n and shared memory region of specified |byte_size|.\n"]
+    pub fn cef_shared_process_message_builder_create(
+        name: *const cef_string_t,
+        byte_size: usize,
+    ) -> *mut cef_shared_process_message_builder_t;
+}
 #[doc = "\n A Layout handles the sizing of the children of a Panel according to\n implementation-specific heuristics. Methods must be called on the browser\n process UI thread unless otherwise indicated.\n\n NOTE: This struct is allocated DLL-side.\n"]
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
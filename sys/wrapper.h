@@ -17,6 +17,7 @@
 #include "include/capi/cef_app_capi.h"
 #include "include/capi/cef_client_capi.h"
 #include "include/capi/cef_urlrequest_capi.h"
+#include "include/capi/cef_shared_process_message_builder_capi.h"
 
 #include "include/capi/views/cef_layout_capi.h"
 #include "include/capi/views/cef_box_layout_capi.h"
@@ -3,7 +3,7 @@ use quote::{format_ident, quote, ToTokens};
 use regex::Regex;
 use std::{
     cell::OnceCell,
-    collections::BTreeMap,
+    collections::{BTreeMap, BTreeSet},
     fmt::{self, Debug, Display, Formatter},
     fs,
     io::Write,
@@ -49,6 +49,15 @@ struct TypeAliasRef<'a> {
 struct EnumRef<'a> {
     name: String,
     ty: Option<&'a syn::ItemEnum>,
+    consts: Vec<&'a syn::Ident>,
+}
+
+impl EnumRef<'_> {
+    /// Bit flags are generated by `bindgen` as newtypes with associated constants, so they can
+    /// hold any combination of flags.
+    fn is_flags(&self) -> bool {
+        self.ty.is_none()
+    }
 }
 
 struct FieldRef<'a> {
@@ -80,9 +89,15 @@ struct FnArgRef<'a> {
 }
 
 struct SignatureRef<'a> {
+    /// Name of the struct declaring the method, or empty for global functions.
+    owner: String,
     name: String,
+    /// Calling convention of the function pointer, `stdcall` for `CEF_CALLBACK` on 32-bit
+    /// Windows and `C` everywhere else.
+    abi: String,
     inputs: Vec<FnArgRef<'a>>,
     output: Option<&'a syn::Type>,
+    doc: String,
     merged_params: OnceCell<Vec<MergedParam>>,
 }
 
@@ -104,9 +119,54 @@ enum MergedParam {
         size_name: String,
         size_ty: ModifiedType,
     },
+    Sized {
+        slice_name: String,
+        slice_ty: ModifiedType,
+        /// Expressions whose product is the length of the slice.
+        factors: Vec<proc_macro2::TokenStream>,
+    },
+}
+
+struct ClosureArg {
+    name: syn::Ident,
+    ty: proc_macro2::TokenStream,
+    convert: Option<proc_macro2::TokenStream>,
 }
 
 impl SignatureRef<'_> {
+    /// Check if an `int` return value is really a boolean.
+    fn is_bool_output(&self) -> bool {
+        let name = self.name.trim_start_matches("cef_");
+        self.output.is_some_and(is_c_int)
+            && !INT_OUTPUTS.contains(&name)
+            && (BOOL_OUTPUTS.contains(&(self.owner.as_str(), name))
+                || self.doc.contains("true (1)")
+                || self.doc.contains("false (0)")
+                || self.doc.starts_with("True if")
+                || self.doc.starts_with("Return whether")
+                || self.doc.starts_with("Returns whether"))
+    }
+
+    /// Check if an `int` parameter is really a boolean.
+    fn is_bool_input(&self, arg: &FnArgRef) -> bool {
+        if !is_c_int(arg.ty) {
+            return false;
+        }
+        if BOOL_INPUTS.contains(&(self.name.as_str(), arg.name.as_str())) {
+            return true;
+        }
+
+        let name = format!("|{}|", arg.name);
+        self.doc.match_indices(&name).any(|(i, _)| {
+            let description = &self.doc[i + name.len()..];
+            let description = description
+                .find(['|', '.'])
+                .map(|end| &description[..end])
+                .unwrap_or(description);
+            description.contains("true (1)") || description.contains("false (0)")
+        })
+    }
+
     fn capture_params(&self) -> Option<proc_macro2::TokenStream> {
         let arg_names = self
             .inputs
@@ -139,15 +199,54 @@ impl SignatureRef<'_> {
                     .inputs
                     .iter()
                     .map(|arg| {
+                        let ty = if self.is_bool_input(arg) {
+                            Some(ModifiedType {
+                                modifiers: vec![],
+                                ty: syn::parse_quote! { bool },
+                            })
+                        } else {
+                            tree.resolve_modified_type(arg.ty)
+                        };
                         Some(MergedParam::Single {
                             name: make_snake_case_value_name(&arg.name),
-                            ty: tree.resolve_modified_type(arg.ty),
+                            ty,
                         })
                     })
                     .collect::<Vec<_>>();
 
                 for i in 1..(args.len()) {
                     let replacement = match (&args[i - 1], &args[i]) {
+                        (
+                            Some(MergedParam::Single {
+                                name: size_name,
+                                ty: Some(size_ty),
+                            }),
+                            Some(MergedParam::Single {
+                                name: elem_name,
+                                ty: Some(elem_ty),
+                            }),
+                        ) if SLICE_PARAMS.contains(&(
+                            self.name.as_str(),
+                            size_name.as_str(),
+                            elem_name.as_str(),
+                        )) =>
+                        {
+                            let modifiers = match elem_ty.modifiers.as_slice() {
+                                [TypeModifier::ConstPtr] => vec![TypeModifier::Slice],
+                                [TypeModifier::MutPtr] => vec![TypeModifier::MutSlice],
+                                _ => continue,
+                            };
+
+                            Some(MergedParam::Buffer {
+                                slice_name: elem_name.clone(),
+                                slice_ty: ModifiedType {
+                                    modifiers,
+                                    ty: elem_ty.ty.clone(),
+                                },
+                                size_name: size_name.clone(),
+                                size_ty: size_ty.clone(),
+                            })
+                        }
                         (
                             Some(MergedParam::Single {
                                 name: count_name,
@@ -185,6 +284,16 @@ impl SignatureRef<'_> {
                                             [TypeModifier::MutPtr],
                                             [TypeModifier::MutPtr, TypeModifier::MutPtr],
                                         ) => vec![TypeModifier::MutSlice],
+                                        ([], [TypeModifier::ConstPtr])
+                                            if tree.root(&elem_ty_string) != BASE_REF_COUNTED =>
+                                        {
+                                            vec![TypeModifier::Slice]
+                                        }
+                                        ([TypeModifier::MutPtr], [TypeModifier::MutPtr])
+                                            if tree.root(&elem_ty_string) != BASE_REF_COUNTED =>
+                                        {
+                                            vec![TypeModifier::MutSlice]
+                                        }
                                         _ => continue,
                                     };
                                     let Ok(slice_ty) = syn::parse2::<syn::Type>(elem_tokens) else {
@@ -242,7 +351,9 @@ impl SignatureRef<'_> {
                         ) if elem_ty.ty.to_token_stream().to_string()
                             == quote! { ::std::os::raw::c_void }.to_string() =>
                         {
-                            if size_name.as_str() != format!("{elem_name}_size").as_str()
+                            if !["size", "len", "length"]
+                                .iter()
+                                .any(|suffix| size_name.as_str() == format!("{elem_name}_{suffix}"))
                                 || size_ty.ty.to_token_stream().to_string().as_str()
                                     != format_ident!("usize")
                                         .to_token_stream()
@@ -289,7 +400,8 @@ impl SignatureRef<'_> {
                                 ty: Some(size_ty),
                             }),
                         ) if (size_name.as_str() == format!("{elem_name}_size").as_str()
-                            || size_name.as_str() == format!("{elem_name}_len").as_str())
+                            || size_name.as_str() == format!("{elem_name}_len").as_str()
+                            || size_name.as_str() == format!("{elem_name}_length").as_str())
                             && size_ty.ty.to_token_stream().to_string().as_str()
                                 == format_ident!("usize")
                                     .to_token_stream()
@@ -332,12 +444,64 @@ impl SignatureRef<'_> {
                     }
                 }
 
+                let sized = args
+                    .iter()
+                    .flatten()
+                    .filter_map(|arg| match arg {
+                        MergedParam::Single { name, ty: Some(ty) } => Some((name.as_str(), ty)),
+                        _ => None,
+                    })
+                    .collect::<BTreeMap<_, _>>();
+                let mut sized = COMPUTED_BUFFERS
+                    .iter()
+                    .filter(|(method, ..)| *method == self.name.as_str())
+                    .filter_map(|(_, buffer, factors, scale)| {
+                        let modifiers = match sized.get(buffer)?.modifiers.as_slice() {
+                            [TypeModifier::ConstPtr] => vec![TypeModifier::Slice],
+                            [TypeModifier::MutPtr] => vec![TypeModifier::MutSlice],
+                            _ => return None,
+                        };
+                        let factors = factors
+                            .iter()
+                            .map(|factor| {
+                                let ty = sized.get(factor)?;
+                                let name = format_ident!("arg_{factor}");
+                                Some(if ty.ty.to_token_stream().to_string() == "usize" {
+                                    quote! { #name }
+                                } else {
+                                    quote! { usize::try_from(#name).unwrap_or_default() }
+                                })
+                            })
+                            .chain((*scale > 1).then(|| {
+                                let scale = proc_macro2::Literal::usize_unsuffixed(*scale);
+                                Some(quote! { #scale })
+                            }))
+                            .collect::<Option<Vec<_>>>()?;
+                        Some((
+                            *buffer,
+                            MergedParam::Sized {
+                                slice_name: buffer.to_string(),
+                                slice_ty: ModifiedType {
+                                    modifiers,
+                                    ty: syn::parse_quote! { u8 },
+                                },
+                                factors,
+                            },
+                        ))
+                    })
+                    .collect::<Vec<_>>();
+
                 args.into_iter()
                     .flatten()
                     .map(|arg| match arg {
                         MergedParam::Single { name, ty } => {
                             if name.as_str() == "self_" {
                                 MergedParam::Receiver
+                            } else if let Some(index) = sized
+                                .iter()
+                                .position(|(buffer, _)| *buffer == name.as_str())
+                            {
+                                sized.swap_remove(index).1
                             } else {
                                 MergedParam::Single { name, ty }
                             }
@@ -357,6 +521,7 @@ impl SignatureRef<'_> {
                 MergedParam::Single { name, .. } => Some(name.clone()),
                 MergedParam::Bounded { slice_name, .. } => Some(slice_name.clone()),
                 MergedParam::Buffer { slice_name, .. } => Some(slice_name.clone()),
+                MergedParam::Sized { slice_name, .. } => Some(slice_name.clone()),
             })
             .map(|name| {
                 let local = format!("arg_{name}");
@@ -410,12 +575,121 @@ impl SignatureRef<'_> {
                     .unwrap_or_else(|| slice_ty.ty.to_token_stream());
                 Some(quote! { #slice_name: #slice_ty })
             }
+            MergedParam::Sized {
+                slice_name,
+                slice_ty,
+                ..
+            } => {
+                let slice_name = format_ident!("{slice_name}");
+                let slice_ty = slice_ty.get_sized_type();
+                Some(quote! { #slice_name: #slice_ty })
+            }
             _ => None,
         });
 
         quote! { #(#args),* }
     }
 
+    /// Get the closure arguments for a closure-based builder slot. Ref-counted interfaces are
+    /// passed to the closure as their concrete wrapper types instead of `impl Impl*` arguments,
+    /// so each argument also comes with an optional conversion from the trait method argument.
+    ///
+    /// Returns `None` if any of the arguments can't be represented in a closure yet.
+    fn get_closure_args(&self, tree: &ParseTree) -> Option<Vec<ClosureArg>> {
+        self.merge_params(tree)
+            .filter(|arg| !matches!(arg, MergedParam::Receiver))
+            .map(|arg| {
+                let (name, ty) = match arg {
+                    MergedParam::Single { name, ty } => (name, ty.as_ref()?),
+                    MergedParam::Sized {
+                        slice_name,
+                        slice_ty,
+                        ..
+                    } => {
+                        return Some(ClosureArg {
+                            name: format_ident!("{slice_name}"),
+                            ty: slice_ty.get_sized_type(),
+                            convert: None,
+                        });
+                    }
+                    MergedParam::Bounded {
+                        slice_name,
+                        slice_ty,
+                        ..
+                    }
+                    | MergedParam::Buffer {
+                        slice_name,
+                        slice_ty,
+                        ..
+                    } => (slice_name, slice_ty),
+                    MergedParam::Receiver => unreachable!(),
+                };
+                let name = format_ident!("{name}");
+                let elem = ty.ty.to_token_stream().to_string();
+                let root = tree.root(&elem);
+                let wrapper = match tree.cef_name_map.get(&elem) {
+                    Some(NameMapEntry {
+                        name,
+                        ty: NameMapType::StructDeclaration,
+                    }) if BASE_REF_COUNTED == root && root != elem.as_str() => Some(name),
+                    _ => None,
+                };
+
+                let Some(wrapper) = wrapper else {
+                    let ty = ty
+                        .get_argument_type(tree)
+                        .unwrap_or_else(|| ty.ty.to_token_stream());
+                    return Some(ClosureArg {
+                        name,
+                        ty,
+                        convert: None,
+                    });
+                };
+
+                let impl_trait = format_ident!("Impl{wrapper}");
+                let wrapper = format_ident!("{wrapper}");
+                let wrap = |arg| {
+                    quote! {
+                        #wrapper(unsafe { RefGuard::from_raw_add_ref(#impl_trait::get_raw(#arg)) })
+                    }
+                };
+                let wrap_arg = wrap(quote! { arg });
+                let wrap_mut_arg = wrap(quote! { &*arg });
+                let (ty, convert) = match ty.modifiers.as_slice() {
+                    [TypeModifier::ConstPtr] => (
+                        quote! { Option<&#wrapper> },
+                        Some(quote! {
+                            let #name = #name.map(|arg| #wrap_arg);
+                            let #name = #name.as_ref();
+                        }),
+                    ),
+                    [TypeModifier::MutPtr] => (
+                        quote! { Option<&mut #wrapper> },
+                        Some(quote! {
+                            let mut #name = #name.map(|arg| #wrap_mut_arg);
+                            let #name = #name.as_mut();
+                        }),
+                    ),
+                    [TypeModifier::Slice] => (
+                        quote! { Option<&[Option<#wrapper>]> },
+                        Some(quote! {
+                            let #name = #name.map(|arg| {
+                                arg.iter()
+                                    .map(|arg| arg.as_ref().map(|arg| #wrap_arg))
+                                    .collect::<Vec<_>>()
+                            });
+                            let #name = #name.as_deref();
+                        }),
+                    ),
+                    [TypeModifier::MutSlice] => (ty.get_argument_type(tree)?, None),
+                    _ => return None,
+                };
+
+                Some(ClosureArg { name, ty, convert })
+            })
+            .collect()
+    }
+
     fn unwrap_rust_args(&self, tree: &ParseTree) -> proc_macro2::TokenStream {
         let capture = self.capture_merged_params(tree);
         let args = self.merge_params(tree).filter_map(|arg| match arg {
@@ -426,6 +700,8 @@ impl SignatureRef<'_> {
                 name,
                 ty: Some(arg_ty),
             } => {
+                let out_name = format_ident!("out_{name}");
+                let ptr_name = format_ident!("ptr_{name}");
                 let name = format_ident!("arg_{name}");
                 let (modifiers, arg_ty) = (arg_ty.modifiers.as_slice(), &arg_ty.ty);
                 let ty_tokens = arg_ty.to_token_stream();
@@ -436,14 +712,16 @@ impl SignatureRef<'_> {
                         match modifiers {
                             [TypeModifier::MutPtr, TypeModifier::MutPtr] => {
                                 Some(quote! {
-                                    let mut ptr = std::ptr::null_mut();
-                                    let #name = #name
+                                    let mut #ptr_name = std::ptr::null_mut();
+                                    let mut #out_name = #name;
+                                    let #name = #out_name
+                                        .as_mut()
                                         .map(|arg| {
                                             if let Some(arg) = arg.as_mut() {
                                                 arg.add_ref();
-                                                ptr = arg.get_raw();
+                                                #ptr_name = arg.get_raw();
                                             }
-                                            std::ptr::from_mut(&mut ptr)
+                                            std::ptr::from_mut(&mut #ptr_name)
                                         })
                                         .unwrap_or(std::ptr::null_mut());
                                 })
@@ -548,6 +826,11 @@ impl SignatureRef<'_> {
                                     let #name = #name.into_raw();
                                 })
                             }
+                            None if ty_string == quote!{ bool }.to_string() => {
+                                Some(quote! {
+                                    let #name = ::std::os::raw::c_int::from(#name);
+                                })
+                            }
                             None => {
                                 let is_void = ty_string == quote!{ ::std::os::raw::c_void }.to_string();
                                 let cast = match modifiers {
@@ -590,7 +873,52 @@ impl SignatureRef<'_> {
                 let arg_name = format_ident!("arg_{slice_name}");
                 let out_name = format_ident!("out_{slice_name}");
                 let vec_name = format_ident!("vec_{slice_name}");
-                let add_refs = if tree.root(&slice_ty.to_token_stream().to_string()) == BASE_REF_COUNTED {
+                let is_ref_counted = tree.root(&slice_ty.to_token_stream().to_string()) == BASE_REF_COUNTED;
+                if !is_ref_counted && tree.cef_name_map.contains_key(&slice_ty.to_token_stream().to_string()) {
+                    // Value structs are converted to a temporary array of the CEF struct type.
+                    return match count_modifiers.as_slice() {
+                        [] => Some(quote! {
+                            let #arg_count = #arg_name
+                                .as_ref()
+                                .map(|arg| arg.len())
+                                .unwrap_or_default();
+                            let #vec_name = #arg_name
+                                .as_ref()
+                                .map(|arg| arg
+                                    .iter()
+                                    .map(|elem| elem.clone().into())
+                                    .collect::<Vec<#slice_ty>>())
+                                .unwrap_or_default();
+                            let #arg_name = if #vec_name.is_empty() {
+                                std::ptr::null()
+                            } else {
+                                #vec_name.as_ptr()
+                            };
+                        }),
+                        [TypeModifier::MutPtr] => Some(quote! {
+                            let mut #out_count = #arg_name
+                                .as_ref()
+                                .map(|arg| arg.len())
+                                .unwrap_or_default();
+                            let #arg_count = &mut #out_count;
+                            let #out_name = #arg_name;
+                            let mut #vec_name = #out_name
+                                .as_ref()
+                                .map(|arg| arg
+                                    .iter()
+                                    .map(|elem| elem.clone().into())
+                                    .collect::<Vec<#slice_ty>>())
+                                .unwrap_or_default();
+                            let #arg_name = if #vec_name.is_empty() {
+                                std::ptr::null_mut()
+                            } else {
+                                #vec_name.as_mut_ptr()
+                            };
+                        }),
+                        _ => None,
+                    };
+                }
+                let add_refs = if is_ref_counted {
                     Some(quote! { elem.add_ref(); })
                 } else {
                     None
@@ -712,6 +1040,33 @@ impl SignatureRef<'_> {
                     _ => None,
                 }
             }
+            MergedParam::Sized {
+                slice_name,
+                slice_ty,
+                factors,
+            } => {
+                let arg_name = format_ident!("arg_{slice_name}");
+                let len_name = format_ident!("len_{slice_name}");
+                // A length which overflows saturates, so it fails the assertion.
+                let len = factors
+                    .iter()
+                    .cloned()
+                    .reduce(|len, factor| quote! { #len.saturating_mul(#factor) })?;
+                let message = format!("{slice_name} is shorter than {{}} elements");
+                let (ptr, null) = match slice_ty.modifiers.as_slice() {
+                    [TypeModifier::MutSlice] => (quote! { as_mut_ptr }, quote! { null_mut }),
+                    _ => (quote! { as_ptr }, quote! { null }),
+                };
+                Some(quote! {
+                    let #len_name = #len;
+                    let #arg_name = #arg_name
+                        .map(|arg| {
+                            assert!(arg.len() >= #len_name, #message, #len_name);
+                            arg.#ptr().cast()
+                        })
+                        .unwrap_or(std::ptr::#null());
+                })
+            }
             _ => None,
         });
 
@@ -723,6 +1078,27 @@ impl SignatureRef<'_> {
 
     fn rewrap_rust_args(&self, tree: &ParseTree) -> proc_macro2::TokenStream {
         let args = self.merge_params(tree).filter_map(|arg| match arg {
+            MergedParam::Single {
+                name,
+                ty: Some(ModifiedType { ty, modifiers }),
+            } if matches!(
+                modifiers.as_slice(),
+                [TypeModifier::MutPtr, TypeModifier::MutPtr]
+            ) && tree.root(&ty.to_token_stream().to_string()) == BASE_REF_COUNTED =>
+            {
+                // The callee may have replaced the reference in the out-parameter.
+                let out_name = format_ident!("out_{name}");
+                let ptr_name = format_ident!("ptr_{name}");
+                Some(quote! {
+                    if let Some(#out_name) = #out_name {
+                        *#out_name = if #ptr_name.is_null() {
+                            None
+                        } else {
+                            Some(#ptr_name.wrap_result())
+                        };
+                    }
+                })
+            }
             MergedParam::Bounded {
                 count_name,
                 count_ty:
@@ -731,17 +1107,23 @@ impl SignatureRef<'_> {
                         ..
                     },
                 slice_name,
-                ..
+                slice_ty,
             } if matches!(count_modifiers.as_slice(), [TypeModifier::MutPtr]) => {
                 let out_count = format_ident!("out_{count_name}");
                 let out_name = format_ident!("out_{slice_name}");
                 let vec_name = format_ident!("vec_{slice_name}");
+                let wrap_elem =
+                    if tree.root(&slice_ty.ty.to_token_stream().to_string()) == BASE_REF_COUNTED {
+                        quote! { if elem.is_null() { None } else { Some(elem.wrap_result()) } }
+                    } else {
+                        quote! { elem.into() }
+                    };
                 Some(quote! {
                     if let Some(#out_name) = #out_name {
                         *#out_name = #vec_name
                             .into_iter()
                             .take(#out_count)
-                            .map(|elem| if elem.is_null() { None } else { Some(elem.wrap_result()) })
+                            .map(|elem| #wrap_elem)
                             .collect();
                     }
                 })
@@ -775,6 +1157,10 @@ impl SignatureRef<'_> {
     }
 
     fn get_rust_output(&self, tree: &ParseTree) -> Option<proc_macro2::TokenStream> {
+        if self.is_bool_output() {
+            return Some(quote! { -> bool });
+        }
+
         self.output.map(|output| {
             let ty = tree
                 .resolve_modified_type(output)
@@ -798,6 +1184,8 @@ impl SignatureRef<'_> {
                 ty: Some(arg_ty),
             } => {
                 let arg_name = format_ident!("arg_{name}");
+                let out_name = format_ident!("out_{name}");
+                let value_name = format_ident!("value_{name}");
                 let (modifiers, arg_ty) = (arg_ty.modifiers.as_slice(), &arg_ty.ty);
                 let ty_tokens = arg_ty.to_token_stream();
                 let ty_string = ty_tokens.to_string();
@@ -826,16 +1214,19 @@ impl SignatureRef<'_> {
                                         });
                                         let #arg_name = #arg_name.as_mut();
                                     }),
-                                    [TypeModifier::MutPtr, TypeModifier::MutPtr] => Some(quote! {
-                                        let mut #arg_name = unsafe { #arg_name.as_mut() }.and_then(|ptr| {
-                                            if ptr.is_null() {
-                                                None
-                                            } else {
-                                                Some(#name(unsafe { RefGuard::from_raw(*ptr) }))
-                                            }
-                                        });
-                                        let #arg_name = Some(&mut #arg_name);
-                                    }),
+                                    [TypeModifier::MutPtr, TypeModifier::MutPtr] => {
+                                        Some(quote! {
+                                            let #out_name = #arg_name;
+                                            let mut #value_name = unsafe { #out_name.as_mut() }.and_then(|ptr| {
+                                                if ptr.is_null() {
+                                                    None
+                                                } else {
+                                                    Some(#name(unsafe { RefGuard::from_raw(*ptr) }))
+                                                }
+                                            });
+                                            let #arg_name = Some(&mut #value_name);
+                                        })
+                                    }
                                     _ => None,
                                 }
                             }
@@ -860,6 +1251,8 @@ impl SignatureRef<'_> {
                                 }),
                                 _ => None,
                             }
+                        } else if ty_string == quote! { bool }.to_string() {
+                            Some(quote! { let #arg_name = #arg_name != 0; })
                         } else if ty_string.as_str() == "cef_string_t" ||
                             CUSTOM_STRING_TYPES.contains(&ty_string.as_str())
                         {
@@ -989,6 +1382,43 @@ impl SignatureRef<'_> {
                         }
                     })
                     .flatten()
+                    .or_else(|| {
+                        let name = match entry? {
+                            NameMapEntry {
+                                name,
+                                ty: NameMapType::StructDeclaration,
+                            } if tree.root(&ty_string) != BASE_REF_COUNTED => format_ident!("{name}"),
+                            _ => return None,
+                        };
+
+                        match modifiers {
+                            [TypeModifier::Slice] => Some(quote! {
+                                let #vec_name = (!#arg_name.is_null() && #arg_count > 0).then(|| {
+                                    unsafe { std::slice::from_raw_parts(#arg_name, #arg_count) }
+                                        .iter()
+                                        .map(|elem| (*elem).into())
+                                        .collect::<Vec<#name>>()
+                                });
+                                let #arg_name = #vec_name.as_deref();
+                            }),
+                            [TypeModifier::MutSlice] => Some(quote! {
+                                let #out_count = unsafe { #arg_count.as_mut() };
+                                let #out_name = #arg_name;
+                                let #arg_count = #out_count
+                                    .as_ref()
+                                    .map(|count| **count)
+                                    .unwrap_or_default();
+                                let mut #vec_name = (!#out_name.is_null() && #arg_count > 0).then(|| {
+                                    unsafe { std::slice::from_raw_parts(#out_name, #arg_count) }
+                                        .iter()
+                                        .map(|elem| (*elem).into())
+                                        .collect::<Vec<#name>>()
+                                });
+                                let #arg_name = #vec_name.as_mut();
+                            }),
+                            _ => None,
+                        }
+                    })
                     .or_else(|| {
                         if ty_string.as_str() == "cef_string_t" ||
                             CUSTOM_STRING_TYPES.contains(&ty_string.as_str())
@@ -1057,6 +1487,34 @@ impl SignatureRef<'_> {
                 }
                 .or(Some(quote! { let #arg_name = #arg_name.into_raw(); }))
             }
+            MergedParam::Sized {
+                slice_name,
+                slice_ty,
+                factors,
+            } => {
+                let arg_name = format_ident!("arg_{slice_name}");
+                let len_name = format_ident!("len_{slice_name}");
+                // A length which overflows is treated like a null buffer.
+                let mut factors = factors.iter();
+                let first = factors.next()?;
+                let len = match factors.next() {
+                    Some(second) => factors.fold(
+                        quote! { #first.checked_mul(#second) },
+                        |len, factor| quote! { #len.and_then(|len| len.checked_mul(#factor)) },
+                    ),
+                    None => quote! { Some(#first) },
+                };
+                let from_raw_parts = match slice_ty.modifiers.as_slice() {
+                    [TypeModifier::MutSlice] => quote! { from_raw_parts_mut },
+                    _ => quote! { from_raw_parts },
+                };
+                Some(quote! {
+                    let #len_name = #len.filter(|len| *len > 0 && !#arg_name.is_null());
+                    let #arg_name = #len_name.map(|len| unsafe {
+                        std::slice::#from_raw_parts(#arg_name.cast(), len)
+                    });
+                })
+            }
             _ => None,
         });
 
@@ -1082,7 +1540,26 @@ impl SignatureRef<'_> {
                     },
             } => {
                 let out_count = format_ident!("out_{count_name}");
+                let out_name = format_ident!("out_{slice_name}");
                 let vec_name = format_ident!("vec_{slice_name}");
+                let slice_ty_string = slice_ty.to_token_stream().to_string();
+                if tree.root(&slice_ty_string) != BASE_REF_COUNTED
+                    && tree.cef_name_map.contains_key(&slice_ty_string)
+                {
+                    return match (slice_modifiers.as_slice(), count_modifiers.as_slice()) {
+                        ([TypeModifier::MutSlice], [TypeModifier::MutPtr]) => Some(quote! {
+                            if let (Some(#out_count), Some(#vec_name)) = (#out_count, #vec_name) {
+                                let size = #vec_name.len().min(*#out_count);
+                                let #out_name = unsafe { std::slice::from_raw_parts_mut(#out_name, size) };
+                                for (out, elem) in #out_name.iter_mut().zip(#vec_name) {
+                                    *out = elem.into();
+                                }
+                                *#out_count = size;
+                            }
+                        }),
+                        _ => None,
+                    };
+                }
                 let update_count = match count_modifiers.as_slice() {
                     [TypeModifier::MutPtr] => {
                         Some(quote! {
@@ -1149,6 +1626,32 @@ impl SignatureRef<'_> {
                     _ => None,
                 }
             }
+            MergedParam::Single {
+                name,
+                ty: Some(ModifiedType { ty, modifiers }),
+            } if matches!(modifiers.as_slice(), [TypeModifier::MutPtr, TypeModifier::MutPtr]) => {
+                // Hand the reference in the (possibly replaced) out-parameter back to the caller.
+                let ty_string = ty.to_token_stream().to_string();
+                if tree.root(&ty_string) != BASE_REF_COUNTED
+                    || !matches!(
+                        tree.cef_name_map.get(ty_string.as_str()),
+                        Some(NameMapEntry {
+                            ty: NameMapType::StructDeclaration,
+                            ..
+                        })
+                    )
+                {
+                    return None;
+                }
+
+                let out_name = format_ident!("out_{name}");
+                let value_name = format_ident!("value_{name}");
+                Some(quote! {
+                    if let Some(#out_name) = unsafe { #out_name.as_mut() } {
+                        *#out_name = #value_name.map(Into::into).unwrap_or(std::ptr::null_mut());
+                    }
+                })
+            }
             _ => None,
         });
 
@@ -1235,7 +1738,8 @@ impl<'a> TryFrom<&'a syn::Field> for SignatureRef<'a> {
         else {
             return Err(Unrecognized::FieldType);
         };
-        if abi.value() != "C" {
+        let abi = abi.value();
+        if abi != "C" && abi != "stdcall" {
             return Err(Unrecognized::FieldType);
         }
 
@@ -1254,14 +1758,166 @@ impl<'a> TryFrom<&'a syn::Field> for SignatureRef<'a> {
         };
 
         Ok(Self {
+            owner: String::new(),
             name,
+            abi,
             inputs,
             output,
+            doc: get_doc_comment(&value.attrs).unwrap_or_default(),
             merged_params: Default::default(),
         })
     }
 }
 
+/// Get the doc comment from the attributes generated by `bindgen`, with all of the whitespace
+/// (including line breaks) collapsed to single spaces.
+fn get_doc_comment(attrs: &[syn::Attribute]) -> Option<String> {
+    let comment = attrs
+        .iter()
+        .filter_map(|attr| match attr {
+            syn::Attribute {
+                style: syn::AttrStyle::Outer,
+                meta:
+                    syn::Meta::NameValue(syn::MetaNameValue {
+                        path,
+                        value:
+                            syn::Expr::Lit(syn::ExprLit {
+                                lit: syn::Lit::Str(value),
+                                ..
+                            }),
+                        ..
+                    }),
+                ..
+            } if path.is_ident("doc") => Some(value.value()),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    if comment.is_empty() {
+        return None;
+    }
+
+    Some(
+        comment
+            .iter()
+            .flat_map(|comment| comment.split_whitespace())
+            .collect::<Vec<_>>()
+            .join(" "),
+    )
+}
+
+/// Functions returning an `int` whose doc comments mention `true (1)` or `false (0)` in
+/// another context, e.g. `get_exit_code` refers to the result of `cef_initialize`.
+const INT_OUTPUTS: &[&str] = &["get_exit_code"];
+
+/// CEF uses `int` for boolean values. Most of them are documented as returning `true (1)` or
+/// `false (0)`, these are the functions returning a boolean which the doc comments don't
+/// identify, as `(struct, method)` pairs. Global functions have an empty struct name.
+const BOOL_OUTPUTS: &[(&str, &str)] = &[
+    ("", "post_delayed_task"),
+    ("", "post_task"),
+    ("_cef_command_handler_t", "is_chrome_app_menu_item_enabled"),
+    ("_cef_command_handler_t", "is_chrome_app_menu_item_visible"),
+    (
+        "_cef_command_handler_t",
+        "is_chrome_page_action_icon_visible",
+    ),
+    ("_cef_command_handler_t", "is_chrome_toolbar_button_visible"),
+    ("_cef_command_line_t", "has_arguments"),
+    ("_cef_dictionary_value_t", "get_bool"),
+    ("_cef_dictionary_value_t", "get_keys"),
+    ("_cef_dictionary_value_t", "remove"),
+    ("_cef_drag_data_t", "get_file_names"),
+    ("_cef_drag_data_t", "get_file_paths"),
+    ("_cef_image_t", "add_bitmap"),
+    ("_cef_image_t", "add_jpeg"),
+    ("_cef_image_t", "add_png"),
+    ("_cef_list_value_t", "get_bool"),
+    ("_cef_list_value_t", "remove"),
+    ("_cef_menu_model_t", "remove"),
+    ("_cef_read_handler_t", "eof"),
+    ("_cef_stream_reader_t", "eof"),
+    ("_cef_task_runner_t", "post_delayed_task"),
+    ("_cef_task_runner_t", "post_task"),
+    ("_cef_v8_value_t", "get_bool_value"),
+    ("_cef_v8_value_t", "get_keys"),
+    ("_cef_v8_value_t", "neuter_array_buffer"),
+    ("_cef_value_t", "get_bool"),
+    ("_cef_window_delegate_t", "get_linux_window_properties"),
+    ("_cef_window_delegate_t", "get_titlebar_height"),
+];
+
+/// Size and buffer parameter pairs as `(function, size, buffer)`, for the pairs which don't
+/// follow the `*_count`, `*_size`, `*_len` or `*_length` naming conventions.
+const SLICE_PARAMS: &[(&str, &str, &str)] = &[
+    ("get_bytes", "size", "bytes"),
+    ("set_to_bytes", "size", "bytes"),
+];
+
+/// Raw buffers whose length is computed from other parameters as `(function, buffer, factors,
+/// scale)`, e.g. the BGRA pixel buffer in `on_paint` holds `width * height * 4` bytes.
+const COMPUTED_BUFFERS: &[(&str, &str, &[&str], usize)] = &[
+    ("on_paint", "buffer", &["width", "height"], 4),
+    ("read", "data_out", &["bytes_to_read"], 1),
+    ("read", "ptr", &["size", "n"], 1),
+    ("read_response", "data_out", &["bytes_to_read"], 1),
+    ("write", "ptr", &["size", "n"], 1),
+];
+
+/// Boolean `int` parameters as `(function, parameter)` pairs, for the parameters which the doc
+/// comments don't describe as `true (1)` or `false (0)`.
+const BOOL_INPUTS: &[(&str, &str)] = &[
+    ("copy", "exclude_empty_children"),
+    ("find", "findNext"),
+    ("find", "forward"),
+    ("find", "matchCase"),
+    ("get_auth_credentials", "isProxy"),
+    ("on_before_browse", "is_redirect"),
+    ("on_before_unload_dialog", "is_reload"),
+    ("on_loading_state_change", "canGoBack"),
+    ("on_loading_state_change", "canGoForward"),
+    ("on_loading_state_change", "isLoading"),
+    ("on_media_access_change", "has_audio_access"),
+    ("on_media_access_change", "has_video_access"),
+    ("on_print_dialog", "has_selection"),
+    ("on_select_client_certificate", "isProxy"),
+    ("on_window_activation_changed", "active"),
+    ("on_window_changed", "added"),
+    ("send_mouse_click_event", "mouseUp"),
+    ("send_mouse_move_event", "mouseLeave"),
+    ("set_accelerator", "alt_pressed"),
+    ("set_accelerator", "ctrl_pressed"),
+    ("set_accelerator", "shift_pressed"),
+    ("set_accelerator_at", "alt_pressed"),
+    ("set_accelerator_at", "ctrl_pressed"),
+    ("set_accelerator_at", "shift_pressed"),
+    ("set_always_on_top", "on_top"),
+    ("set_audio_muted", "mute"),
+    ("set_auto_resize_enabled", "enabled"),
+    ("set_bool", "value"),
+    ("set_checked", "checked"),
+    ("set_checked_at", "checked"),
+    ("set_collate", "collate"),
+    ("set_enabled", "enabled"),
+    ("set_enabled_at", "enabled"),
+    ("set_focus", "focus"),
+    ("set_focusable", "focusable"),
+    ("set_fullscreen", "fullscreen"),
+    ("set_ink_drop_enabled", "enabled"),
+    ("set_orientation", "landscape"),
+    ("set_password_input", "password_input"),
+    ("set_read_only", "read_only"),
+    ("set_rethrow_exceptions", "rethrow"),
+    ("set_selection_only", "selection_only"),
+    ("set_visible", "visible"),
+    ("set_visible_at", "visible"),
+    ("stop_finding", "clearSelection"),
+    ("was_hidden", "hidden"),
+];
+
+fn is_c_int(ty: &syn::Type) -> bool {
+    ty.to_token_stream().to_string() == quote! { ::std::os::raw::c_int }.to_string()
+}
+
 const BASE_REF_COUNTED: &str = "_cef_base_ref_counted_t";
 
 const BASE_SCOPED: &str = "_cef_base_scoped_t";
@@ -1324,6 +1980,16 @@ struct ModifiedType {
 }
 
 impl ModifiedType {
+    /// Buffers with a computed length are borrowed in place, instead of being copied to and
+    /// from a `Vec` like the other mutable slices.
+    fn get_sized_type(&self) -> proc_macro2::TokenStream {
+        let elem = &self.ty;
+        match self.modifiers.as_slice() {
+            [TypeModifier::MutSlice] => quote! { Option<&mut [#elem]> },
+            _ => quote! { Option<&[#elem]> },
+        }
+    }
+
     fn get_argument_type(&self, tree: &ParseTree) -> Option<proc_macro2::TokenStream> {
         let elem = self.ty.to_token_stream();
         let elem_string = elem.to_string();
@@ -1341,7 +2007,7 @@ impl ModifiedType {
                         [TypeModifier::ConstPtr] => Some(quote! { Option<&impl #impl_trait> }),
                         [TypeModifier::MutPtr] => Some(quote! { Option<&mut impl #impl_trait> }),
                         [TypeModifier::MutPtr, TypeModifier::MutPtr] => {
-                            Some(quote! { Option<&mut Option<impl #impl_trait>> })
+                            Some(quote! { Option<&mut Option<#name>> })
                         }
                         [TypeModifier::Slice] => {
                             Some(quote! { Option<&[Option<impl #impl_trait>]> })
@@ -1360,10 +2026,8 @@ impl ModifiedType {
                         [TypeModifier::MutPtr, TypeModifier::MutPtr] => {
                             Some(quote! { Option<&mut Option<#name>> })
                         }
-                        [TypeModifier::Slice] => Some(quote! { Option<&[Option<#name>]> }),
-                        [TypeModifier::MutSlice] => {
-                            Some(quote! { Option<&mut Vec<Option<#name>>> })
-                        }
+                        [TypeModifier::Slice] => Some(quote! { Option<&[#name]> }),
+                        [TypeModifier::MutSlice] => Some(quote! { Option<&mut Vec<#name>> }),
                         _ => None,
                     }
                 }
@@ -1602,7 +2266,9 @@ impl ParseTree<'_> {
                 non_camel_case_types,
                 unused_variables,
                 clippy::not_unsafe_ptr_arg_deref,
-                clippy::too_many_arguments
+                clippy::too_many_arguments,
+                clippy::type_complexity,
+                clippy::unnecessary_cast
             )]
             use crate::rc::{
                 ConvertParam, ConvertReturnValue, Rc, RcImpl, RefGuard, WrapParamRef,
@@ -1820,7 +2486,13 @@ impl ParseTree<'_> {
                         }
                         _ => None,
                     }
-                    .unwrap_or(quote! { result.wrap_result() });
+                    .unwrap_or_else(|| {
+                        if m.is_bool_output() {
+                            quote! { result != 0 }
+                        } else {
+                            quote! { result.wrap_result() }
+                        }
+                    });
 
                     let impl_default = output_type
                         .as_ref()
@@ -1914,7 +2586,8 @@ impl ParseTree<'_> {
                                 Some(quote! { #name })
                             }
                             MergedParam::Bounded { slice_name, .. }
-                            | MergedParam::Buffer { slice_name, .. } => {
+                            | MergedParam::Buffer { slice_name, .. }
+                            | MergedParam::Sized { slice_name, .. } => {
                                 let name = format_ident!("{slice_name}");
                                 Some(quote! { #name })
                             }
@@ -2123,7 +2796,13 @@ impl ParseTree<'_> {
                         }
                         _ => None,
                     }
-                    .unwrap_or(quote! { result.wrap_result() });
+                    .unwrap_or_else(|| {
+                        if m.is_bool_output() {
+                            quote! { result != 0 }
+                        } else {
+                            quote! { result.wrap_result() }
+                        }
+                    });
 
                     let impl_default = output_type
                         .as_ref()
@@ -2250,7 +2929,8 @@ impl ParseTree<'_> {
                                 Some(quote! { #name })
                             }
                             MergedParam::Bounded { slice_name, .. }
-                            | MergedParam::Buffer { slice_name, .. } => {
+                            | MergedParam::Buffer { slice_name, .. }
+                            | MergedParam::Sized { slice_name, .. } => {
                                 let name = format_ident!("{slice_name}");
                                 Some(quote! { #name })
                             }
@@ -2308,7 +2988,8 @@ impl ParseTree<'_> {
                         Some(quote! { #name })
                     }
                     MergedParam::Bounded { slice_name, .. }
-                    | MergedParam::Buffer { slice_name, .. } => {
+                    | MergedParam::Buffer { slice_name, .. }
+                    | MergedParam::Sized { slice_name, .. } => {
                         let name = format_ident!("arg_{slice_name}");
                         Some(quote! { #name })
                     }
@@ -2349,14 +3030,22 @@ impl ParseTree<'_> {
                         _ => None,
                     }
                 });
+                let forward_output = if m.is_bool_output() {
+                    Some(quote! { result.into() })
+                } else {
+                    forward_output
+                };
                 let mut call_impl =
                     quote! { #impl_trait::#name(&arg_self_.interface, #(#forward_args),*) };
-                if forward_output.is_some() || !unwrapped_args.is_empty() {
+                if forward_output.is_some() {
                     call_impl = quote! { let result = #call_impl; };
+                } else if !unwrapped_args.is_empty() {
+                    call_impl = quote! { #call_impl; };
                 }
 
+                let abi = &m.abi;
                 quote! {
-                    extern "C" fn #name<I: #impl_trait>(#(#args),*) #output {
+                    extern #abi fn #name<I: #impl_trait>(#(#args),*) #output {
                         #wrapped_args
                         #call_impl
                         #unwrapped_args
@@ -2468,7 +3157,209 @@ impl ParseTree<'_> {
         }
         .to_string();
 
-        writeln!(f, "{wrapper}")
+        writeln!(f, "{wrapper}")?;
+        self.write_closure_builder(f, s, root, name_ident, rust_name)
+    }
+
+    fn write_closure_builder(
+        &self,
+        f: &mut Formatter<'_>,
+        s: &StructDeclarationRef<'_>,
+        root: &str,
+        name_ident: &syn::Ident,
+        rust_name: &syn::Ident,
+    ) -> fmt::Result {
+        let mut structs = vec![s];
+        let mut base_name = self.base(&s.name);
+        while let Some(next_base) = base_name
+            .filter(|base| *base != root)
+            .and_then(|base| self.lookup_struct_declaration.get(base))
+            .and_then(|&i| self.struct_declarations.get(i))
+        {
+            base_name = self.base(&next_base.name);
+            structs.push(next_base);
+        }
+
+        let builder_name = format_ident!("{rust_name}Builder");
+        let impl_trait = format_ident!("Impl{rust_name}");
+        let wrap_trait = format_ident!("Wrap{rust_name}");
+        let builder_mod = format_ident!("builder{}", s.name);
+
+        let mut fields = vec![];
+        let mut setters = vec![];
+        let mut reset_slots = vec![];
+        let mut impl_traits = vec![];
+        for (depth, s) in structs.into_iter().enumerate() {
+            let Some(NameMapEntry {
+                name: struct_rust_name,
+                ty: NameMapType::StructDeclaration,
+            }) = self.cef_name_map.get(&s.name)
+            else {
+                continue;
+            };
+            let struct_impl_trait = format_ident!("Impl{struct_rust_name}");
+            let struct_name_ident = format_ident!("{}", s.name);
+            let bases = iter::repeat_n(format_ident!("base"), depth).collect::<Vec<_>>();
+
+            let methods = s.methods.iter().filter_map(|m| {
+                let name = &m.name;
+                let name = format_ident!("{name}");
+                let output = m.get_rust_output(self);
+                let args = m
+                    .get_closure_args(self)
+                    .filter(|_| !output.to_token_stream().to_string().contains('&'));
+                let Some(args) = args else {
+                    reset_slots.push(quote! {
+                        object.#(#bases.)*#name = None;
+                    });
+                    return None;
+                };
+
+                let arg_names = args.iter().map(|arg| &arg.name).collect::<Vec<_>>();
+                let arg_types = args.iter().map(|arg| &arg.ty).collect::<Vec<_>>();
+                let convert_args = args.iter().filter_map(|arg| arg.convert.as_ref());
+                fields.push(quote! {
+                    #name: Option<Box<dyn Fn(#(#arg_types),*) #output + Send + Sync>>,
+                });
+                setters.push(quote! {
+                    pub fn #name(
+                        mut self,
+                        f: impl Fn(#(#arg_types),*) #output + Send + Sync + 'static,
+                    ) -> Self {
+                        self.#name = Some(Box::new(f));
+                        self
+                    }
+                });
+                reset_slots.push(quote! {
+                    if builder.#name.is_none() {
+                        object.#(#bases.)*#name = None;
+                    }
+                });
+
+                let sig = m.get_signature(self);
+                let impl_default =
+                    m.output.map(
+                        |ty| match syn::parse2::<ModifiedType>(ty.to_token_stream()) {
+                            Ok(ty)
+                                if ty.ty.to_token_stream().to_string()
+                                    != quote! { ::std::os::raw::c_void }.to_string() =>
+                            {
+                                quote! { Default::default() }
+                            }
+                            _ => quote! { unsafe { std::mem::zeroed() } },
+                        },
+                    );
+                let impl_default = impl_default.map(|impl_default| {
+                    quote! {
+                        else {
+                            #impl_default
+                        }
+                    }
+                });
+                Some(quote! {
+                    #sig {
+                        if let Some(f) = &self.builder.#name {
+                            #(#convert_args)*
+                            f(#(#arg_names),*)
+                        } #impl_default
+                    }
+                })
+            });
+            let methods = methods.collect::<Vec<_>>();
+
+            impl_traits.push(quote! {
+                impl #struct_impl_trait for Closures {
+                    #(#methods)*
+
+                    fn get_raw(&self) -> *mut #struct_name_ident {
+                        self.object.cast()
+                    }
+                }
+            });
+        }
+
+        let base_ident = format_ident!("{BASE_REF_COUNTED}");
+
+        let builder = quote! {
+            #[derive(Default)]
+            pub struct #builder_name {
+                #(#fields)*
+            }
+
+            impl #rust_name {
+                pub fn builder() -> #builder_name {
+                    Default::default()
+                }
+            }
+
+            impl #builder_name {
+                #(#setters)*
+
+                pub fn build(self) -> #rust_name {
+                    let builder = std::sync::Arc::new(self);
+                    let handler = #rust_name::new(#builder_mod::Closures {
+                        object: std::ptr::null_mut(),
+                        builder: builder.clone(),
+                    });
+                    unsafe {
+                        let object = &mut *#impl_trait::get_raw(&handler);
+                        #(#reset_slots)*
+                    }
+                    handler
+                }
+            }
+
+            mod #builder_mod {
+                use super::*;
+
+                pub struct Closures {
+                    pub object: *mut RcImpl<#name_ident, Self>,
+                    pub builder: std::sync::Arc<#builder_name>,
+                }
+
+                impl #wrap_trait for Closures {
+                    fn wrap_rc(&mut self, object: *mut RcImpl<#name_ident, Self>) {
+                        self.object = object;
+                    }
+                }
+
+                impl Clone for Closures {
+                    fn clone(&self) -> Self {
+                        unsafe {
+                            let rc_impl = &mut *self.object;
+                            rc_impl.interface.add_ref();
+                        }
+
+                        Self {
+                            object: self.object,
+                            builder: self.builder.clone(),
+                        }
+                    }
+                }
+
+                impl Rc for Closures {
+                    fn as_base(&self) -> &#base_ident {
+                        unsafe {
+                            let base = &*self.object;
+                            std::mem::transmute(&base.cef_object)
+                        }
+                    }
+                }
+
+                #(#impl_traits)*
+            }
+        }
+        .to_string();
+
+        writeln!(
+            f,
+            "\n/// Closure-based builder for [{rust_name}], see [{rust_name}::builder]. Any callbacks"
+        )?;
+        writeln!(
+            f,
+            "/// which are not set fall back to the default CEF behavior."
+        )?;
+        writeln!(f, "{builder}")
     }
 
     fn write_scoped_struct(
@@ -2570,7 +3461,13 @@ impl ParseTree<'_> {
                         }
                         _ => None,
                     }
-                    .unwrap_or(quote! { result.wrap_result() });
+                    .unwrap_or_else(|| {
+                        if m.is_bool_output() {
+                            quote! { result != 0 }
+                        } else {
+                            quote! { result.wrap_result() }
+                        }
+                    });
 
                     let impl_default = output_type
                         .as_ref()
@@ -2682,7 +3579,8 @@ impl ParseTree<'_> {
                                 Some(quote! { #name })
                             }
                             MergedParam::Bounded { slice_name, .. }
-                            | MergedParam::Buffer { slice_name, .. } => {
+                            | MergedParam::Buffer { slice_name, .. }
+                            | MergedParam::Sized { slice_name, .. } => {
                                 let name = format_ident!("{slice_name}");
                                 Some(quote! { #name })
                             }
@@ -2737,7 +3635,8 @@ impl ParseTree<'_> {
                         Some(quote! { #name })
                     }
                     MergedParam::Bounded { slice_name, .. }
-                    | MergedParam::Buffer { slice_name, .. } => {
+                    | MergedParam::Buffer { slice_name, .. }
+                    | MergedParam::Sized { slice_name, .. } => {
                         let name = format_ident!("arg_{slice_name}");
                         Some(quote! { #name })
                     }
@@ -2778,14 +3677,22 @@ impl ParseTree<'_> {
                         _ => None,
                     }
                 });
+                let forward_output = if m.is_bool_output() {
+                    Some(quote! { result.into() })
+                } else {
+                    forward_output
+                };
                 let mut call_impl =
                     quote! { #impl_trait::#name(&arg_self_.interface, #(#forward_args),*) };
-                if forward_output.is_some() || !unwrapped_args.is_empty() {
+                if forward_output.is_some() {
                     call_impl = quote! { let result = #call_impl; };
+                } else if !unwrapped_args.is_empty() {
+                    call_impl = quote! { #call_impl; };
                 }
 
+                let abi = &m.abi;
                 quote! {
-                    extern "C" fn #name<I: #impl_trait>(#(#args),*) #output {
+                    extern #abi fn #name<I: #impl_trait>(#(#args),*) #output {
                         #wrapped_args
                         #call_impl
                         #unwrapped_args
@@ -3071,50 +3978,162 @@ impl ParseTree<'_> {
         let enum_names = self
             .enum_names
             .iter()
+            .filter(|e| e.ty.is_none_or(|ty| !ty.variants.is_empty()))
             .filter_map(|e| make_rust_type_name(&e.name).map(|rust_name| (rust_name, e)));
         for (rust_name, e) in enum_names {
             let name = &e.name;
             writeln!(f, "\n/// See [{name}] for more documentation.")?;
             let name = format_ident!("{name}");
             let rust_name = format_ident!("{rust_name}");
-            let impl_default =
-                e.ty.and_then(|ty| ty.variants.first())
-                    .map(|v| {
-                        let v = &v.ident;
-                        quote! { Self(#name::#v) }
-                    })
-                    .unwrap_or(quote! { unsafe { std::mem::zeroed() } });
-            let wrapper = quote! {
-                #[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
-                pub struct #rust_name(#name);
+            let wrapper = if e.is_flags() {
+                let flags = make_flags_names(&e.consts)
+                    .into_iter()
+                    .map(|flag| format_ident!("{flag}"));
+                // The raw value is a `c_int` on Windows and a `c_uint` everywhere else.
+                let flags_values = e
+                    .consts
+                    .iter()
+                    .map(|v| quote! { #name::#v.0 as u32 })
+                    .collect::<Vec<_>>();
+
+                quote! {
+                    #[derive(Debug, Default, Copy, Clone, Hash, PartialEq, Eq)]
+                    pub struct #rust_name(u32);
+
+                    impl #rust_name {
+                        #(pub const #flags: Self = Self(#flags_values);)*
+
+                        pub const fn empty() -> Self {
+                            Self(0)
+                        }
 
-                impl AsRef<#name> for #rust_name {
-                    fn as_ref(&self) -> &#name {
-                        &self.0
+                        pub const fn all() -> Self {
+                            Self(#(#flags_values)|*)
+                        }
+
+                        pub const fn bits(&self) -> u32 {
+                            self.0
+                        }
+
+                        pub const fn from_bits_retain(bits: u32) -> Self {
+                            Self(bits)
+                        }
+
+                        pub const fn is_empty(&self) -> bool {
+                            self.0 == 0
+                        }
+
+                        pub const fn contains(&self, other: Self) -> bool {
+                            self.0 & other.0 == other.0
+                        }
+
+                        pub const fn intersects(&self, other: Self) -> bool {
+                            self.0 & other.0 != 0
+                        }
+
+                        pub fn insert(&mut self, other: Self) {
+                            self.0 |= other.0;
+                        }
+
+                        pub fn remove(&mut self, other: Self) {
+                            self.0 &= !other.0;
+                        }
                     }
-                }
 
-                impl AsMut<#name> for #rust_name {
-                    fn as_mut(&mut self) -> &mut #name {
-                        &mut self.0
+                    impl std::ops::BitOr for #rust_name {
+                        type Output = Self;
+
+                        fn bitor(self, other: Self) -> Self {
+                            Self(self.0 | other.0)
+                        }
                     }
-                }
 
-                impl From<#name> for #rust_name {
-                    fn from(value: #name) -> Self {
-                        Self(value)
+                    impl std::ops::BitOrAssign for #rust_name {
+                        fn bitor_assign(&mut self, other: Self) {
+                            self.0 |= other.0;
+                        }
                     }
-                }
 
-                impl From<#rust_name> for #name  {
-                    fn from(value: #rust_name) -> Self {
-                        value.0
+                    impl std::ops::BitAnd for #rust_name {
+                        type Output = Self;
+
+                        fn bitand(self, other: Self) -> Self {
+                            Self(self.0 & other.0)
+                        }
+                    }
+
+                    impl std::ops::BitAndAssign for #rust_name {
+                        fn bitand_assign(&mut self, other: Self) {
+                            self.0 &= other.0;
+                        }
+                    }
+
+                    impl std::ops::Sub for #rust_name {
+                        type Output = Self;
+
+                        fn sub(self, other: Self) -> Self {
+                            Self(self.0 & !other.0)
+                        }
+                    }
+
+                    impl std::ops::SubAssign for #rust_name {
+                        fn sub_assign(&mut self, other: Self) {
+                            self.0 &= !other.0;
+                        }
                     }
+
+                    impl From<#name> for #rust_name {
+                        fn from(value: #name) -> Self {
+                            Self(value.0 as u32)
+                        }
+                    }
+
+                    impl From<#rust_name> for #name {
+                        fn from(value: #rust_name) -> Self {
+                            Self(value.0 as _)
+                        }
+                    }
+                }
+            } else {
+                let Some(ty) = e.ty else {
+                    continue;
+                };
+                let variants = ty.variants.iter().map(|v| &v.ident).collect::<Vec<_>>();
+                if variants.is_empty() {
+                    continue;
                 }
+                let rust_variants = make_variant_names(&variants)
+                    .into_iter()
+                    .map(|variant| format_ident!("{variant}"))
+                    .collect::<Vec<_>>();
+                let (first_variant, rest_variants) = rust_variants.split_at(1);
+                // The sys enum is generated from the same headers, so it has no other variants,
+                // but it is `#[non_exhaustive]`.
+                let unreachable = format!("unknown {name} variant");
 
-                impl Default for #rust_name {
-                    fn default() -> Self {
-                        #impl_default
+                quote! {
+                    #[derive(Debug, Default, Copy, Clone, Hash, PartialEq, Eq)]
+                    pub enum #rust_name {
+                        #[default]
+                        #(#first_variant,)*
+                        #(#rest_variants,)*
+                    }
+
+                    impl From<#name> for #rust_name {
+                        fn from(value: #name) -> Self {
+                            match value {
+                                #(#name::#variants => Self::#rust_variants,)*
+                                _ => unreachable!(#unreachable),
+                            }
+                        }
+                    }
+
+                    impl From<#rust_name> for #name {
+                        fn from(value: #rust_name) -> Self {
+                            match value {
+                                #(#rust_name::#rust_variants => Self::#variants,)*
+                            }
+                        }
                     }
                 }
             }
@@ -3183,7 +4202,13 @@ impl ParseTree<'_> {
                         }
                         _ => None,
                     }
-                    .unwrap_or(quote! { result.wrap_result() });
+                    .unwrap_or_else(|| {
+                        if global_fn.is_bool_output() {
+                            quote! { result != 0 }
+                        } else {
+                            quote! { result.wrap_result() }
+                        }
+                    });
 
                     quote! {
                         pub fn #name(#args) #output {
@@ -3245,11 +4270,34 @@ impl<'a> From<&'a syn::File> for ParseTree<'a> {
                 syn::Item::Enum(e) => Some(EnumRef {
                     name: e.ident.to_string(),
                     ty: Some(e),
+                    consts: vec![],
                 }),
                 syn::Item::Struct(item_struct) => match &item_struct.fields {
                     syn::Fields::Unnamed(fields) if fields.unnamed.len() == 1 => Some(EnumRef {
                         name: item_struct.ident.to_string(),
                         ty: None,
+                        consts: value
+                            .items
+                            .iter()
+                            .filter_map(|item| match item {
+                                syn::Item::Impl(item_impl) if item_impl.trait_.is_none() => {
+                                    match item_impl.self_ty.as_ref() {
+                                        syn::Type::Path(syn::TypePath { path, .. })
+                                            if path.is_ident(&item_struct.ident) =>
+                                        {
+                                            Some(item_impl.items.iter())
+                                        }
+                                        _ => None,
+                                    }
+                                }
+                                _ => None,
+                            })
+                            .flatten()
+                            .filter_map(|item| match item {
+                                syn::ImplItem::Const(item_const) => Some(&item_const.ident),
+                                _ => None,
+                            })
+                            .collect(),
                     }),
                     _ => None,
                 },
@@ -3267,39 +4315,17 @@ impl<'a> From<&'a syn::File> for ParseTree<'a> {
                         let mut methods = vec![];
 
                         for member in named.iter() {
-                            if let Ok(method) = SignatureRef::try_from(member) {
+                            if let Ok(mut method) = SignatureRef::try_from(member) {
+                                method.owner = item_struct.ident.to_string();
                                 methods.push(method);
                             } else if let Ok(field) = FieldRef::try_from(member) {
                                 fields.push(field);
                             }
                         }
 
-                        let is_sealed = item_struct
-                            .attrs
-                            .iter()
-                            .find_map(|attr| match attr {
-                                syn::Attribute {
-                                    style: syn::AttrStyle::Outer,
-                                    meta:
-                                        syn::Meta::NameValue(syn::MetaNameValue {
-                                            path,
-                                            value:
-                                                syn::Expr::Lit(syn::ExprLit {
-                                                    lit: syn::Lit::Str(value),
-                                                    ..
-                                                }),
-                                            ..
-                                        }),
-                                    ..
-                                } if path.to_token_stream().to_string()
-                                    == quote! { doc }.to_string() =>
-                                {
-                                    Some(value.to_token_stream().to_string())
-                                }
-                                _ => None,
-                            })
+                        let is_sealed = get_doc_comment(&item_struct.attrs)
                             .map(|comment| {
-                                comment.ends_with(r#"NOTE: This struct is allocated DLL-side.\n""#)
+                                comment.ends_with("NOTE: This struct is allocated DLL-side.")
                             })
                             .unwrap_or_default();
 
@@ -3334,6 +4360,7 @@ impl<'a> From<&'a syn::File> for ParseTree<'a> {
             .flat_map(|items| {
                 items.iter().filter_map(|item| match item {
                     syn::ForeignItem::Fn(syn::ForeignItemFn {
+                        attrs,
                         sig:
                             syn::Signature {
                                 ident,
@@ -3343,7 +4370,9 @@ impl<'a> From<&'a syn::File> for ParseTree<'a> {
                             },
                         ..
                     }) => Some(SignatureRef {
+                        owner: String::new(),
                         name: ident.to_string(),
+                        abi: String::from("C"),
                         inputs: inputs
                             .iter()
                             .map(|arg| match arg {
@@ -3365,6 +4394,7 @@ impl<'a> From<&'a syn::File> for ParseTree<'a> {
                             syn::ReturnType::Default => None,
                             syn::ReturnType::Type(_, ty) => Some(ty.as_ref()),
                         },
+                        doc: get_doc_comment(attrs).unwrap_or_default(),
                         merged_params: Default::default(),
                     }),
                     _ => None,
@@ -3467,6 +4497,79 @@ fn format_bindings(source_path: &Path) -> crate::Result<()> {
     Ok(())
 }
 
+/// Strip the longest prefix of whole `_` separated words which all of the enum values share,
+/// e.g. `TID_UI` becomes `UI`. If that leaves any names which are empty, start with a digit, or
+/// collide with `reserved`, strip fewer words instead.
+fn strip_common_prefix(values: &[&syn::Ident], reserved: &str) -> Vec<Vec<String>> {
+    let words = values
+        .iter()
+        .map(|value| {
+            value
+                .to_string()
+                .split('_')
+                .map(ToOwned::to_owned)
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    let shortest = words
+        .iter()
+        .map(|words| words.len() - 1)
+        .min()
+        .unwrap_or_default();
+    let prefix_len = words
+        .first()
+        .map(|first| {
+            (0..shortest)
+                .take_while(|&i| words.iter().all(|words| words[i] == first[i]))
+                .count()
+        })
+        .unwrap_or_default();
+
+    (0..=prefix_len)
+        .rev()
+        .map(|prefix_len| {
+            words
+                .iter()
+                .map(|words| words[prefix_len..].to_vec())
+                .collect::<Vec<_>>()
+        })
+        .find(|names| {
+            let mut seen = BTreeSet::new();
+            names.iter().all(|name| {
+                name.first()
+                    .and_then(|word| word.chars().next())
+                    .is_some_and(|c| !c.is_ascii_digit())
+                    && !name.join("_").eq_ignore_ascii_case(reserved)
+                    && seen.insert(name.join("_").to_ascii_lowercase())
+            })
+        })
+        .unwrap_or(words)
+}
+
+fn make_variant_names(values: &[&syn::Ident]) -> Vec<String> {
+    strip_common_prefix(values, "Unknown")
+        .into_iter()
+        .map(|words| {
+            words
+                .iter()
+                .filter_map(|word| {
+                    let mut chars = word.chars();
+                    chars.next().map(|c| {
+                        c.to_ascii_uppercase().to_string() + &chars.as_str().to_ascii_lowercase()
+                    })
+                })
+                .collect()
+        })
+        .collect()
+}
+
+fn make_flags_names(values: &[&syn::Ident]) -> Vec<String> {
+    strip_common_prefix(values, "")
+        .into_iter()
+        .map(|words| words.join("_"))
+        .collect()
+}
+
 fn make_rust_type_name(name: &str) -> Option<String> {
     static PATTERN: OnceLock<Regex> = OnceLock::new();
     let pattern = PATTERN.get_or_init(|| Regex::new(r"^_?cef_(\w+)_t$").unwrap());
@@ -3489,3 +4592,218 @@ fn make_rust_type_name(name: &str) -> Option<String> {
 fn make_snake_case_value_name(name: &str) -> String {
     name.from_case(Case::Camel).to_case(Case::Snake)
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn sys_bindings() -> syn::File {
+        let mut path = crate::dirs::get_sys_dir().unwrap();
+        path.extend(["src", "bindings", "x86_64_unknown_linux_gnu.rs"]);
+        syn::parse_file(&crate::read_bindings(&path).unwrap()).unwrap()
+    }
+
+    fn method<'a>(tree: &'a ParseTree, ty: &str, name: &str) -> &'a SignatureRef<'a> {
+        tree.struct_declarations
+            .iter()
+            .find(|s| s.name == ty)
+            .and_then(|s| s.methods.iter().find(|m| m.name == name))
+            .unwrap_or_else(|| panic!("missing {ty}::{name}"))
+    }
+
+    fn idents(names: &[&str]) -> Vec<syn::Ident> {
+        names.iter().map(|name| format_ident!("{name}")).collect()
+    }
+
+    struct Enums<'a>(&'a ParseTree<'a>);
+
+    impl Display for Enums<'_> {
+        fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+            self.0.write_enums(f)
+        }
+    }
+
+    #[test]
+    fn detects_bool_outputs() {
+        let bindings = sys_bindings();
+        let tree = ParseTree::from(&bindings);
+
+        for (ty, name) in [
+            ("_cef_download_item_t", "get_percent_complete"),
+            ("_cef_response_t", "get_status"),
+            ("_cef_v8_exception_t", "get_line_number"),
+            ("_cef_v8_stack_frame_t", "get_line_number"),
+        ] {
+            assert!(!method(&tree, ty, name).is_bool_output(), "{ty}::{name}");
+        }
+        let get_exit_code = tree
+            .global_function_declarations
+            .iter()
+            .find(|f| f.name == "cef_get_exit_code")
+            .unwrap();
+        assert!(!get_exit_code.is_bool_output());
+
+        let post_task = tree
+            .global_function_declarations
+            .iter()
+            .find(|f| f.name == "cef_post_task")
+            .unwrap();
+        assert!(post_task.is_bool_output());
+
+        for (ty, name) in [
+            ("_cef_browser_t", "can_go_back"),
+            ("_cef_value_t", "get_bool"),
+            ("_cef_task_runner_t", "post_task"),
+            ("_cef_browser_t", "is_valid"),
+            ("_cef_v8_value_t", "is_same"),
+        ] {
+            assert!(method(&tree, ty, name).is_bool_output(), "{ty}::{name}");
+        }
+    }
+
+    #[test]
+    fn checks_buffer_length_overflow() {
+        let bindings = sys_bindings();
+        let tree = ParseTree::from(&bindings);
+        let read = method(&tree, "_cef_read_handler_t", "read");
+
+        let wrapped = read.wrap_cef_args(&tree).to_string();
+        assert!(
+            wrapped.contains("let len_ptr = arg_size . checked_mul (arg_n)"),
+            "{wrapped}"
+        );
+        assert!(!wrapped.contains("arg_size * arg_n"), "{wrapped}");
+
+        let unwrapped = read.unwrap_rust_args(&tree).to_string();
+        assert!(
+            unwrapped.contains("let len_ptr = arg_size . saturating_mul (arg_n)"),
+            "{unwrapped}"
+        );
+    }
+
+    #[test]
+    fn strips_common_prefix() {
+        let values = idents(&["CEF_WOD_UNKNOWN", "CEF_WOD_CURRENT_TAB"]);
+        let values = values.iter().collect::<Vec<_>>();
+        assert_eq!(
+            strip_common_prefix(&values, ""),
+            [vec!["UNKNOWN"], vec!["CURRENT", "TAB"]]
+        );
+        // Keep enough of the prefix to avoid the reserved name.
+        assert_eq!(
+            strip_common_prefix(&values, "Unknown"),
+            [vec!["WOD", "UNKNOWN"], vec!["WOD", "CURRENT", "TAB"]]
+        );
+
+        // Names must not start with a digit.
+        let values = idents(&["SCALE_FACTOR_100P", "SCALE_FACTOR_200P"]);
+        let values = values.iter().collect::<Vec<_>>();
+        assert_eq!(
+            strip_common_prefix(&values, ""),
+            [vec!["FACTOR", "100P"], vec!["FACTOR", "200P"]]
+        );
+    }
+
+    #[test]
+    fn makes_variant_names() {
+        let values = idents(&["CEF_WOD_UNKNOWN", "CEF_WOD_CURRENT_TAB"]);
+        let values = values.iter().collect::<Vec<_>>();
+        assert_eq!(make_variant_names(&values), ["WodUnknown", "WodCurrentTab"]);
+
+        let values = idents(&["PK_DIR_CURRENT", "PK_DIR_EXE", "PK_DIR_USER_DATA"]);
+        let values = values.iter().collect::<Vec<_>>();
+        assert_eq!(make_variant_names(&values), ["Current", "Exe", "UserData"]);
+    }
+
+    #[test]
+    fn keeps_combined_flags() {
+        let bindings: syn::File = syn::parse_quote! {
+            impl cef_event_flags_t {
+                pub const EVENTFLAG_NONE: cef_event_flags_t = cef_event_flags_t(0);
+            }
+            impl cef_event_flags_t {
+                pub const EVENTFLAG_SHIFT_DOWN: cef_event_flags_t = cef_event_flags_t(2);
+            }
+            impl cef_event_flags_t {
+                pub const EVENTFLAG_CONTROL_DOWN: cef_event_flags_t = cef_event_flags_t(4);
+            }
+            #[repr(transparent)]
+            pub struct cef_event_flags_t(pub ::std::os::raw::c_int);
+        };
+        let tree = ParseTree::from(&bindings);
+        let output = syn::parse_file(&Enums(&tree).to_string()).unwrap();
+
+        let consts = output
+            .items
+            .iter()
+            .filter_map(|item| match item {
+                syn::Item::Impl(item_impl) if item_impl.trait_.is_none() => Some(item_impl),
+                _ => None,
+            })
+            .flat_map(|item_impl| &item_impl.items)
+            .filter_map(|item| match item {
+                syn::ImplItem::Const(item_const) => Some(item_const.ident.to_string()),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(consts, ["NONE", "SHIFT_DOWN", "CONTROL_DOWN"]);
+
+        // Both conversions keep every bit, instead of looking for a single matching flag.
+        let conversions = output
+            .items
+            .iter()
+            .filter_map(|item| match item {
+                syn::Item::Impl(syn::ItemImpl {
+                    trait_: Some((_, path, _)),
+                    self_ty,
+                    items,
+                    ..
+                }) if path.segments.last().is_some_and(|s| s.ident == "From") => {
+                    Some((self_ty.to_token_stream().to_string(), items))
+                }
+                _ => None,
+            })
+            .map(|(ty, items)| {
+                let [syn::ImplItem::Fn(from)] = items.as_slice() else {
+                    panic!("unexpected From impl for {ty}");
+                };
+                (ty, from.block.to_token_stream().to_string())
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            conversions,
+            [
+                (
+                    "EventFlags".to_string(),
+                    "{ Self (value . 0 as u32) }".to_string()
+                ),
+                (
+                    "cef_event_flags_t".to_string(),
+                    "{ Self (value . 0 as _) }".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn generates_flags_as_newtypes() {
+        let bindings = sys_bindings();
+        let enums = bindings
+            .items
+            .iter()
+            .filter_map(|item| match item {
+                syn::Item::Enum(e) => Some(e.ident.to_string()),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert!(enums.iter().any(|e| e == "cef_window_open_disposition_t"));
+        for e in enums {
+            assert!(
+                !e.ends_with("_flags_t")
+                    && !e.ends_with("_mask_t")
+                    && !crate::upgrade::FLAGS_ENUMS.contains(&e.as_str()),
+                "{e} must be a bitfield enum"
+            );
+        }
+    }
+}
@@ -19,6 +19,16 @@ const TARGETS: &[&str] = &[
     "arm-unknown-linux-gnueabi",
 ];
 
+/// Enums which hold combinations of bit flags, but don't follow the `*_flags_t` or `*_mask_t`
+/// naming conventions.
+pub const FLAGS_ENUMS: &[&str] = &[
+    "cef_cert_status_t",
+    "cef_json_writer_options_t",
+    "cef_scheme_options_t",
+    "cef_ssl_content_status_t",
+    "cef_v8_propertyattribute_t",
+];
+
 pub fn download(target: &str) -> PathBuf {
     assert!(TARGETS.contains(&target), "unsupported target {target}");
 
@@ -64,11 +74,16 @@ fn bindgen(target: &str, cef_path: &Path) -> crate::Result<()> {
         .allowlist_item("CEF_API_VERSION(_.+)?")
         .default_macro_constant_type(bindgen::MacroTypeVariation::Signed)
         .bitfield_enum(".*_mask_t")
+        .bitfield_enum(".*_flags_t")
         .clang_args([
             format!("-I{}", cef_path.display()),
             format!("--target={target}"),
         ]);
 
+    for name in FLAGS_ENUMS {
+        bindings = bindings.bitfield_enum(name);
+    }
+
     if target.contains("windows") {
         bindings = bindings.new_type_alias("HINSTANCE").new_type_alias("HWND");
     } else if target.contains("apple") {